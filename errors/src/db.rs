@@ -1,11 +1,10 @@
 use reporting::files;
-#[cfg(not(target_arch = "wasm32"))]
 use std::{
-    fs,
-    io::{self, Read},
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
-
-use std::{ops::Range, path::PathBuf, sync::Arc};
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct FileId(salsa::InternId);
 
@@ -28,8 +27,16 @@ impl salsa::InternKey for FileId {
     }
 }
 
+/// Provides the contents of the files used by the database.
+/// Implementors can override `read_file` to serve files that haven't been saved to disk.
+pub trait FileSystem {
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+}
+
 #[salsa::query_group(FileDatabaseStorage)]
-pub trait FileDatabase {
+pub trait FileDatabase: FileSystem {
     #[salsa::interned]
     fn intern_file(&self, path: PathBuf) -> FileId;
     #[salsa::interned]
@@ -43,8 +50,14 @@ pub trait FileDatabase {
 
 #[cfg(not(target_arch = "wasm32"))]
 fn source(db: &impl FileDatabase, file_id: FileId) -> Arc<String> {
-    let contents = read_file(&db.lookup_intern_file(file_id))
-        .expect("Couldn't read file. TODO handle deletion of file");
+    // The file can change without salsa knowing so we re-read it on every new revision
+    db.salsa_runtime().report_untracked_read();
+
+    // A file that was deleted or can't be read is treated as empty rather than
+    // taking down the language server
+    let contents = db
+        .read_file(&db.lookup_intern_file(file_id))
+        .unwrap_or_default();
     Arc::new(contents)
 }
 
@@ -87,17 +100,6 @@ fn file(db: &impl FileDatabase, file_id: FileId) -> Arc<File> {
     })
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-fn read_file(name: &PathBuf) -> io::Result<String> {
-    let mut file = fs::File::open(name)?;
-
-    let mut contents = String::new();
-
-    file.read_to_string(&mut contents)?;
-
-    Ok(contents)
-}
-
 impl File {
     fn line_start(&self, line_index: usize) -> Option<usize> {
         use std::cmp::Ordering;
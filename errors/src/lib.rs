@@ -4,7 +4,7 @@ pub mod pos;
 mod reporter;
 pub use crate::reporter::Reporter;
 pub use codespan_reporting::{
-    diagnostic::Diagnostic,
+    diagnostic::{Diagnostic, Severity},
     files::Files,
     term::{
         emit,
@@ -15,5 +15,5 @@ pub use codespan_reporting::{
 
 pub use pos::Span;
 
-pub use db::{FileDatabase, FileDatabaseStorage, FileId};
+pub use db::{FileDatabase, FileDatabaseStorage, FileId, FileSystem};
 pub type WithError<T> = Result<T, Vec<Diagnostic<FileId>>>;
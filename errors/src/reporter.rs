@@ -1,4 +1,4 @@
-use codespan_reporting::diagnostic::{Diagnostic, Label, LabelStyle};
+use codespan_reporting::diagnostic::{Diagnostic, Label, LabelStyle, Severity};
 
 use crate::FileId;
 use std::cell::RefCell;
@@ -63,8 +63,12 @@ impl Reporter {
         self.diagnostics.borrow_mut().push(diagnostic)
     }
 
+    /// Whether an error has been reported, warnings don't count
     pub fn has_errors(&self) -> bool {
-        !self.diagnostics.borrow().is_empty()
+        self.diagnostics
+            .borrow()
+            .iter()
+            .any(|diagnostic| diagnostic.severity >= Severity::Error)
     }
 }
 
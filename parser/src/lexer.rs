@@ -16,7 +16,11 @@ mod tests {
         let db = MockDatabaseImpl::default();
         let handle = db.intern_file(file.path().to_path_buf());
 
-        db.lex(handle).unwrap()
+        let (tokens, diagnostics) = db.lex(handle);
+
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+        tokens
     }
 
     #[test]
@@ -53,6 +57,13 @@ mod tests {
         assert_debug_snapshot!(get_tokens(input))
     }
 
+    #[test]
+    fn lex_labels() {
+        let input = "'outer: 'a' 'b";
+
+        assert_debug_snapshot!(get_tokens(input))
+    }
+
     #[test]
     fn lex_line_comment() {
         let input = "12 // this is a line comment";
@@ -1,35 +1,56 @@
 use crate::db::ParseDatabase;
 use crate::Parser;
-use errors::{pos::Span, FileId, Reporter, WithError};
+use errors::{pos::Span, Diagnostic, FileId, Reporter};
 use syntax::{ast::SourceFile, Lexer, Token};
 
-pub fn lex_query(db: &impl ParseDatabase, file: FileId) -> WithError<Vec<Span<Token>>> {
+pub fn lex_query(
+    db: &impl ParseDatabase,
+    file: FileId,
+) -> (Vec<Span<Token>>, Vec<Diagnostic<FileId>>) {
     let reporter = Reporter::new(file);
     let source = db.source(file);
     let mut lexer = Lexer::new(&source, reporter);
     let tokens = lexer.lex();
-    let reporter = lexer.reporter();
 
-    if reporter.has_errors() {
-        Err(reporter.finish())
-    } else {
-        Ok(tokens)
-    }
+    (tokens, lexer.reporter().finish())
 }
 
-pub fn parse_query(db: &impl ParseDatabase, file: FileId) -> WithError<SourceFile> {
+/// Parsing always produces a tree, errors are recorded as `ERROR` nodes
+/// and returned alongside the tree so later stages can still run
+pub fn parse_query(db: &impl ParseDatabase, file: FileId) -> (SourceFile, Vec<Diagnostic<FileId>>) {
     let reporter = Reporter::new(file);
 
     let source = db.source(file);
 
-    let tokens = db.lex(file)?;
+    let (tokens, mut diagnostics) = db.lex(file);
     let mut parser = Parser::new(&tokens, reporter, &source);
     let program = parser.parse_program();
-    let reporter = parser.reporter();
 
-    if reporter.has_errors() {
-        Err(reporter.finish())
-    } else {
-        Ok(program)
+    diagnostics.extend(parser.reporter().finish());
+
+    (program, diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::MockDatabaseImpl;
+    use crate::ParseDatabase;
+    use errors::FileDatabase;
+    use std::io::Write;
+    use syntax::FnDefOwner;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn keeps_tree_with_errors() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "fn main() {{ let x = ; }} fn foo() {{}}").unwrap();
+
+        let db = MockDatabaseImpl::default();
+        let handle = db.intern_file(file.path().to_path_buf());
+
+        let (source_file, diagnostics) = db.parse(handle);
+
+        assert!(!diagnostics.is_empty());
+        assert_eq!(source_file.functions().count(), 2);
     }
 }
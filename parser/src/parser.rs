@@ -9,6 +9,7 @@ mod pattern;
 mod pratt;
 mod restrictions;
 mod source_file;
+mod traits;
 mod type_alias;
 mod type_args;
 mod type_params;
@@ -44,6 +45,8 @@ pub struct Parser<'a> {
     token_pos: usize,
     prefix: HashMap<RuleToken, &'a dyn PrefixParser>,
     infix: HashMap<RuleToken, &'a dyn InfixParser>,
+    /// The restrictions of the expression being parsed, which the operands of an infix operator inherit
+    restrictions: Restrictions,
 }
 
 impl<'a> Parser<'a> {
@@ -57,6 +60,7 @@ impl<'a> Parser<'a> {
             builder: GreenNodeBuilder::new(),
             prefix: HashMap::new(),
             infix: HashMap::new(),
+            restrictions: Restrictions::default(),
             reporter,
             input,
         };
@@ -66,7 +70,11 @@ impl<'a> Parser<'a> {
         parser.prefix(RuleToken::Excl, &expressions::UnaryParselet);
         parser.prefix(RuleToken::Minus, &expressions::UnaryParselet);
         parser.prefix(RuleToken::LParen, &expressions::GroupingParselet);
+        parser.prefix(RuleToken::LBracket, &expressions::ArrayParselet);
         parser.prefix(RuleToken::Pipe, &expressions::ClosureParselet);
+        parser.prefix(RuleToken::PipePipe, &expressions::ClosureParselet);
+        parser.prefix(RuleToken::Loop, &expressions::LoopParselet);
+        parser.prefix(RuleToken::Label, &expressions::LoopParselet);
 
         parser.infix(
             RuleToken::LBrace,
@@ -83,6 +91,13 @@ impl<'a> Parser<'a> {
             &expressions::IndexParselet(Precedence::Call),
         );
 
+        parser.infix(RuleToken::As, &expressions::CastParselet(Precedence::Cast));
+
+        parser.infix(
+            RuleToken::Question,
+            &expressions::TryParselet(Precedence::Call),
+        );
+
         parser.infix(
             RuleToken::Dot,
             &expressions::FieldParselet(Precedence::Call),
@@ -112,6 +127,11 @@ impl<'a> Parser<'a> {
             &expressions::BinaryParselet(Precedence::Assignment),
         );
 
+        parser.infix(
+            RuleToken::PercentEq,
+            &expressions::BinaryParselet(Precedence::Assignment),
+        );
+
         parser.infix(
             RuleToken::Plus,
             &expressions::BinaryParselet(Precedence::Term),
@@ -122,11 +142,15 @@ impl<'a> Parser<'a> {
         );
         parser.infix(
             RuleToken::Slash,
-            &expressions::BinaryParselet(Precedence::Term),
+            &expressions::BinaryParselet(Precedence::Factor),
         );
         parser.infix(
             RuleToken::Star,
-            &expressions::BinaryParselet(Precedence::Term),
+            &expressions::BinaryParselet(Precedence::Factor),
+        );
+        parser.infix(
+            RuleToken::Percent,
+            &expressions::BinaryParselet(Precedence::Factor),
         );
         parser.infix(
             RuleToken::Comparison,
@@ -148,6 +172,41 @@ impl<'a> Parser<'a> {
             &expressions::BinaryParselet(Precedence::Equality),
         );
 
+        parser.infix(
+            RuleToken::Pipe,
+            &expressions::BinaryParselet(Precedence::BitOr),
+        );
+
+        parser.infix(
+            RuleToken::Caret,
+            &expressions::BinaryParselet(Precedence::BitXor),
+        );
+
+        parser.infix(
+            RuleToken::Amp,
+            &expressions::BinaryParselet(Precedence::BitAnd),
+        );
+
+        parser.infix(
+            RuleToken::Shl,
+            &expressions::BinaryParselet(Precedence::Shift),
+        );
+
+        parser.infix(
+            RuleToken::Shr,
+            &expressions::BinaryParselet(Precedence::Shift),
+        );
+
+        parser.infix(
+            RuleToken::DotDot,
+            &expressions::BinaryParselet(Precedence::Range),
+        );
+
+        parser.infix(
+            RuleToken::DotDotEq,
+            &expressions::BinaryParselet(Precedence::Range),
+        );
+
         parser
     }
 
@@ -171,6 +230,21 @@ impl<'a> Parser<'a> {
         self.tokens.get(self.token_pos + 1)
     }
 
+    /// The kind of the first token after the current one that isn't trivia
+    fn peek(&self) -> SyntaxKind {
+        self.nth(1)
+    }
+
+    /// The kind of the `n`th token after the current one, skipping trivia
+    fn nth(&self, n: usize) -> SyntaxKind {
+        self.tokens[self.token_pos..]
+            .iter()
+            .map(|token| token.value.kind)
+            .filter(|kind| !kind.is_trivia())
+            .nth(n)
+            .unwrap_or(EOF)
+    }
+
     fn start_node(&mut self, kind: SyntaxKind) {
         match replace(&mut self.state, State::Normal) {
             State::PendingStart => {
@@ -287,10 +361,25 @@ impl<'a> Parser<'a> {
         &self.input[token.start.absolute as usize..token.end.absolute as usize]
     }
 
-    fn precedence(&mut self) -> Precedence {
-        let token = self.current();
+    /// The lexer never produces `>>` so that `A<B<i32>>` closes both type argument lists.
+    /// Two `>` with nothing in between them are treated as a shift instead
+    fn at_shift_right(&mut self) -> bool {
+        self.at(T![>])
+            && self
+                .lookahead()
+                .map_or(false, |token| token.value.kind == T![>])
+    }
+
+    fn current_rule(&mut self) -> RuleToken {
+        if self.at_shift_right() {
+            RuleToken::Shr
+        } else {
+            self.current().rule()
+        }
+    }
 
-        let rule = token.rule();
+    fn precedence(&mut self) -> Precedence {
+        let rule = self.current_rule();
 
         self.infix
             .get(&rule)
@@ -384,10 +473,30 @@ impl<'a> Parser<'a> {
         self.add_token(self.current);
     }
 
-    fn ident(&mut self) {
-        self.start_node(NAME);
-        self.expect(IDENT);
-        self.finish_node()
+    /// Bumps a `>>` made out of two `>` tokens as a single `SHR` token
+    pub(crate) fn bump_shift_right(&mut self) {
+        let len = TextUnit::from(self.current.value.len)
+            + TextUnit::from(self.tokens[self.token_pos + 1].value.len);
+        let range = TextRange::offset_len(self.current.start.absolute.into(), len);
+
+        let text = &self.input[range];
+
+        self.text_pos += len;
+        self.token_pos += 2;
+
+        self.current = &self.tokens[self.token_pos];
+        self.builder.token(T![>>].into(), text.into());
+    }
+
+    pub(crate) fn ident(&mut self) {
+        // A missing name doesn't get a node so that every `NAME` has an identifier
+        if self.at(IDENT) {
+            self.start_node(NAME);
+            self.bump();
+            self.finish_node()
+        } else {
+            self.expect(IDENT)
+        }
     }
 }
 
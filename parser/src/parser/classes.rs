@@ -16,6 +16,11 @@ impl<'a> Parser<'a> {
             self.parse_type_params(false);
         }
 
+        if self.at(T![extends]) {
+            self.bump();
+            self.parse_type();
+        }
+
         self.parse_class_body();
 
         self.finish_node()
@@ -94,4 +99,7 @@ mod tests {
     test_parser! {parse_class_fields,"class Person { name:String; surname:String;}"}
     test_parser! {parse_class_fields_methods,"class Person { name:String; surname:String; fn hello(self) {}}"}
     test_parser! {parse_class_methods,"class Person { name:String; surname:String; fn new() -> Person {}}"}
+    test_parser! {parse_class_extends,"class Dog extends Animal { breed:String; }"}
+    test_parser! {parse_generic_class_extends,"class Pair<T> extends Base<T> {}"}
+    test_parser! {parse_class_method_self_and_params,"class Person { fn greet(self, other:Person) {}}"}
 }
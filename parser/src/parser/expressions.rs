@@ -5,10 +5,12 @@ use crate::parser::{
 
 use syntax::T;
 
+mod array_expr;
 mod binary;
 mod block;
 mod break_expr;
 mod call_expr;
+mod cast_expr;
 mod closure_expr;
 mod continue_expr;
 mod do_expr;
@@ -19,27 +21,47 @@ mod grouping;
 mod ident;
 mod if_expr;
 mod index_expr;
+mod label;
 mod let_expr;
 mod literal;
+mod loop_expr;
 mod match_expr;
 mod record_expr;
 mod return_expr;
+mod try_expr;
 mod unary;
 mod while_expr;
 
+pub use array_expr::ArrayParselet;
 pub use binary::BinaryParselet;
 pub use call_expr::CallParselet;
+pub use cast_expr::CastParselet;
 pub use closure_expr::ClosureParselet;
 pub use field_expr::FieldParselet;
 pub use grouping::GroupingParselet;
 pub use ident::IdentParselet;
 pub use index_expr::IndexParselet;
 pub use literal::LiteralParselet;
+pub use loop_expr::LoopParselet;
 pub use record_expr::RecordParselet;
+pub use try_expr::TryParselet;
 pub use unary::UnaryParselet;
 
 impl<'a> Parser<'a> {
     pub(crate) fn parse_expression(&mut self, precedence: Precedence, restrictions: Restrictions) {
+        let outer_restrictions = std::mem::replace(&mut self.restrictions, restrictions);
+
+        self.parse_expression_inner(precedence, restrictions);
+
+        self.restrictions = outer_restrictions;
+    }
+
+    /// The restrictions of the expression currently being parsed
+    pub(crate) fn restrictions(&self) -> Restrictions {
+        self.restrictions
+    }
+
+    fn parse_expression_inner(&mut self, precedence: Precedence, restrictions: Restrictions) {
         let check_point = self.builder.checkpoint();
 
         let token = self.current();
@@ -64,10 +86,7 @@ impl<'a> Parser<'a> {
         parser.parse(self);
 
         while precedence <= self.precedence() {
-            {
-                let token = self.current();
-                rule = token.rule();
-            }
+            rule = self.current_rule();
 
             let parser = self.infix.get(&rule);
 
@@ -0,0 +1,48 @@
+use syntax::T;
+
+use crate::parser::pratt::{Precedence, PrefixParser};
+use crate::parser::{Parser, Restrictions};
+
+use crate::SyntaxKind::*;
+
+#[derive(Debug)]
+pub struct ArrayParselet;
+
+impl PrefixParser for ArrayParselet {
+    fn parse(&self, parser: &mut Parser) {
+        parser.start_node(ARRAY_EXPR);
+
+        parser.bump(); // Eats the `[`
+
+        if !parser.at(T!["]"]) {
+            parser.parse_expression(Precedence::Assignment, Restrictions::default());
+
+            if parser.at(T![;]) {
+                // A repeat literal `[0; 16]`
+                parser.bump();
+                parser.parse_expression(Precedence::Assignment, Restrictions::default());
+            } else if parser.at(T![,]) {
+                parser.bump();
+
+                while !parser.at(EOF) && !parser.at(T!["]"]) {
+                    parser.parse_expression(Precedence::Assignment, Restrictions::default());
+                    if !parser.at(T!["]"]) && !parser.expected(T![,]) {
+                        break;
+                    }
+                }
+            }
+        }
+
+        parser.expect(T!["]"]);
+
+        parser.finish_node();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_parser! {parse_empty_array_expr,"fn main() {[];}"}
+    test_parser! {parse_array_expr,"fn main() {[1,2,3,];}"}
+    test_parser! {parse_array_repeat_expr,"fn main() {[0;16];}"}
+    test_parser! {parse_nested_array_index_expr,"fn main() {[[1,2],[3]][0][1];}"}
+}
@@ -1,23 +1,33 @@
 use syntax::T;
 
 use crate::parser::pratt::{InfixParser, Precedence};
-use crate::parser::{Parser, Restrictions};
+use crate::parser::Parser;
 
 use crate::SyntaxKind::*;
 
 impl<'a> Parser<'a> {
     pub(crate) fn parse_op(&mut self) {
+        if self.at_shift_right() {
+            self.bump_shift_right();
+            return;
+        }
+
         match self.current() {
             T![-]
             | T![+]
             | T![*]
             | T![/]
+            | T![%]
             | T![&&]
             | T![||]
-            |T![<]
-            |T![>]
-            // | T![^]
-            // | T![%]
+            | T![&]
+            | T![|]
+            | T![^]
+            | T![<<]
+            | T![..]
+            | T![..=]
+            | T![<]
+            | T![>]
             | T![=]
             | T![==]
             | T![!]
@@ -27,8 +37,9 @@ impl<'a> Parser<'a> {
             | T![+=]
             | T![-=]
             | T![*=]
-            | T![/=] => self.bump(),
-            _ => self.error("Expected an operator",format!("Expected one of `-` | `+` |`*`| `/` | `&&` | `||` | `<` | `>` | `==` | `!` | `!=` | `>=` | `<=` | `+=` | `-=` | `*=` | `/=`  instead found `{}`",self.current_string())),
+            | T![/=]
+            | T![%=] => self.bump(),
+            _ => self.error("Expected an operator",format!("Expected one of `-` | `+` |`*`| `/` | `%` | `&&` | `||` | `&` | `|` | `^` | `<<` | `>>` | `..` | `..=` | `<` | `>` | `==` | `!` | `!=` | `>=` | `<=` | `+=` | `-=` | `*=` | `/=` | `%=` instead found `{}`",self.current_string())),
         }
     }
 }
@@ -45,7 +56,7 @@ impl InfixParser for BinaryParselet {
 
         parser.parse_op();
 
-        parser.parse_expression(self.0.higher(), Restrictions::default());
+        parser.parse_expression(self.0.higher(), parser.restrictions());
 
         parser.finish_node();
     }
@@ -57,4 +68,9 @@ mod tests {
     test_parser! {parse_wrapped_bin_expr_literal,"fn main() {1.0+2.0+2.0;}"}
     test_parser! {parse_assign_bin_expr_,"fn main() {x=10;x+=10;x-=10;x/=10;x*=10;}"}
     test_parser! {parse_chained_assign_bin_expr_,"fn main() {x=10=10=2=3;}"}
+    test_parser! {parse_factor_bin_expr,"fn main() {1+2*3%4/5;}"}
+    test_parser! {parse_logical_bin_expr,"fn main() {a && b || c == d;}"}
+    test_parser! {parse_bitwise_bin_expr,"fn main() {a | b ^ c & d << 1 >> 2;}"}
+    test_parser! {parse_range_bin_expr,"fn main() {0..10;0..=a+1;}"}
+    test_parser! {parse_rem_assign_bin_expr,"fn main() {x%=10;}"}
 }
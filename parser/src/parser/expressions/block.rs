@@ -33,6 +33,16 @@ impl<'a> Parser<'a> {
                     self.parse_while_expr();
                     self.finish_node();
                 }
+                T![loop] => {
+                    self.start_node(EXPR_STMT);
+                    self.parse_loop_expr();
+                    self.finish_node();
+                }
+                LABEL => {
+                    self.start_node(EXPR_STMT);
+                    self.parse_labelled_expr();
+                    self.finish_node();
+                }
                 T![return] => {
                     self.start_node(EXPR_STMT);
                     self.parse_return_expr();
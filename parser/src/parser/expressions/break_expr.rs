@@ -1,6 +1,6 @@
 use syntax::T;
 
-use crate::parser::Parser;
+use crate::parser::{Parser, Precedence, Restrictions};
 
 use crate::SyntaxKind::*;
 
@@ -10,6 +10,14 @@ impl<'a> Parser<'a> {
 
         self.expect(T![break]);
 
+        if self.at(LABEL) {
+            self.bump();
+        }
+
+        if !self.matches(vec![T![;], T!["}"], T![,], EOF]) {
+            self.parse_expression(Precedence::Assignment, Restrictions::default());
+        }
+
         self.finish_node()
     }
 }
@@ -17,4 +25,6 @@ impl<'a> Parser<'a> {
 #[cfg(test)]
 mod tests {
     test_parser! {parse_break_expr,"fn main() {break;}"}
+    test_parser! {parse_break_value_expr,"fn main() {break 1+2;}"}
+    test_parser! {parse_break_label_expr,"fn main() {break 'outer; break 'outer 10;}"}
 }
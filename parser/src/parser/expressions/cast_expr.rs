@@ -0,0 +1,32 @@
+use syntax::T;
+
+use crate::parser::pratt::{InfixParser, Precedence};
+use crate::parser::Parser;
+
+use crate::SyntaxKind::*;
+
+#[derive(Debug)]
+pub struct CastParselet(pub Precedence);
+
+impl InfixParser for CastParselet {
+    fn parse(&self, parser: &mut Parser, checkpoint: rowan::Checkpoint) {
+        parser.start_node_at(checkpoint, CAST_EXPR);
+
+        parser.expect(T![as]);
+
+        parser.parse_type();
+
+        parser.finish_node();
+    }
+
+    fn pred(&self) -> Precedence {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_parser! {parse_cast_expr,"fn main() {1 as f32;}"}
+    test_parser! {parse_cast_precedence,"fn main() {-a as f32 * 2.0 + b as f32;}"}
+    test_parser! {parse_chained_cast_expr,"fn main() {a as i32 as string;}"}
+}
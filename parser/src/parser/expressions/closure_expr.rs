@@ -13,16 +13,21 @@ impl<'a> Parser<'a> {
     pub(crate) fn parse_closure_expr(&mut self) {
         self.start_node(CLOSURE_EXPR);
 
-        self.expect(T![|]);
+        // `||` is lexed as a single token so a closure without parameters starts with one
+        if self.at(T![||]) {
+            self.bump();
+        } else {
+            self.expect(T![|]);
 
-        while !self.at(EOF) && !self.at(T![|]) {
-            self.func_param();
-            if !self.at(T![|]) && !self.expected(T![,]) {
-                break;
+            while !self.at(EOF) && !self.at(T![|]) {
+                self.func_param();
+                if !self.at(T![|]) && !self.expected(T![,]) {
+                    break;
+                }
             }
-        }
 
-        self.expect(T![|]);
+            self.expect(T![|]);
+        }
 
         if self.at(T![->]) {
             self.expect(T![->]);
@@ -10,6 +10,10 @@ impl<'a> Parser<'a> {
 
         self.expect(T![continue]);
 
+        if self.at(LABEL) {
+            self.bump();
+        }
+
         self.finish_node()
     }
 }
@@ -17,4 +21,5 @@ impl<'a> Parser<'a> {
 #[cfg(test)]
 mod tests {
     test_parser! {parse_continue_expr,"fn main() {continue;}"}
+    test_parser! {parse_continue_label_expr,"fn main() {continue 'outer;}"}
 }
@@ -10,13 +10,17 @@ impl<'a> Parser<'a> {
     pub(crate) fn parse_do_expr(&mut self) {
         self.start_node(DO_EXPR);
 
+        self.parse_label();
+
         self.expect(T![do]);
 
         self.parse_block();
 
         self.expect(T![while]);
 
+        self.start_node(CONDITION);
         self.parse_expression(Precedence::Assignment, Restrictions::no_records());
+        self.finish_node();
 
         self.finish_node()
     }
@@ -1,7 +1,7 @@
 use syntax::T;
 
 use crate::parser::pratt::{InfixParser, Precedence};
-use crate::parser::{Parser, Restrictions};
+use crate::parser::Parser;
 
 use crate::SyntaxKind::*;
 
@@ -14,7 +14,8 @@ impl InfixParser for FieldParselet {
 
         parser.expect(T![.]);
 
-        parser.parse_expression(Precedence::Assignment, Restrictions::default());
+        // Only the name belongs to the field so that `a.b.c()` calls `c` on `a.b`
+        parser.parse_expression(Precedence::Primary, parser.restrictions());
 
         parser.finish_node();
     }
@@ -30,4 +31,5 @@ mod tests {
     test_parser! {parse_field_access_chain,"fn main(){a.b.c.d.e.f;}"}
     test_parser! {parse_field_access_method,"fn main(){a.b();}"}
     test_parser! {parse_field_access_method_chain,"fn main(){a.b.c.d.e();}"}
+    test_parser! {parse_method_call_args,"fn main(){self.a.b(1, c).d();}"}
 }
@@ -7,8 +7,29 @@ impl<'a> Parser<'a> {
     pub(crate) fn parse_for_expr(&mut self) {
         self.start_node(FOR_EXPR);
 
+        self.parse_label();
+
         self.expect(T![for]);
 
+        if self.at(T!["("]) && !self.at_parenthesized_pattern() {
+            self.parse_for_parts();
+        } else {
+            self.parse_pattern(false);
+
+            self.expect(T![in]);
+
+            self.parse_expression(Precedence::Assignment, Restrictions::no_records());
+        }
+
+        self.parse_block();
+
+        self.finish_node()
+    }
+
+    /// Parses the `(init; cond; incr)` of a C-style loop, any of which can be left out.
+    /// The init is a statement and the condition gets its own node so that the parts can be
+    /// told apart when some are missing
+    fn parse_for_parts(&mut self) {
         self.expect(T!["("]);
 
         if self.at(T![;]) {
@@ -17,29 +38,50 @@ impl<'a> Parser<'a> {
             self.parse_let_expr();
             self.expect(T![;]);
         } else {
+            self.start_node(EXPR_STMT);
             self.parse_expression(Precedence::Assignment, Restrictions::default());
+            self.finish_node();
             self.expect(T![;]);
         }
 
         if self.at(T![;]) {
             self.bump()
         } else {
-            self.parse_expression(Precedence::Comparison, Restrictions::default());
+            self.start_node(CONDITION);
+            self.parse_expression(Precedence::Assignment, Restrictions::default());
+            self.finish_node();
             self.expect(T![;]);
         }
 
         if self.at(T![;]) {
             self.bump();
-        } else if self.at(T![")"]) {
-        } else {
+        } else if !self.at(T![")"]) {
             self.parse_expression(Precedence::Assignment, Restrictions::default());
         }
 
         self.expect(T![")"]);
+    }
 
-        self.parse_block();
+    /// `for (a, b) in pairs` starts like a C-style loop,
+    /// so look past the parentheses for an `in`
+    fn at_parenthesized_pattern(&self) -> bool {
+        let mut depth = 0;
 
-        self.finish_node()
+        let mut kinds = self.tokens[self.token_pos..]
+            .iter()
+            .map(|token| token.value.kind)
+            .filter(|kind| !kind.is_trivia());
+
+        while let Some(kind) = kinds.next() {
+            match kind {
+                T!["("] => depth += 1,
+                T![")"] if depth == 1 => return kinds.next() == Some(T![in]),
+                T![")"] => depth -= 1,
+                _ => (),
+            }
+        }
+
+        false
     }
 }
 
@@ -54,4 +96,7 @@ mod tests {
     test_parser! {parse_for_no_incr,"fn main() { for (let x=10;x<10;;){};}"}
     test_parser! {parse_for_no_cond,"fn main() { for (let x=10;;x+=10){};}"}
     test_parser! {parse_for_no_init,"fn main() { for (;x<10;x+=10){};}"}
+    test_parser! {parse_for_expr_init,"fn main() { for (i = 0;;i+=1){};}"}
+    test_parser! {parse_for_in_expr,"fn main() { for x in 0..10 {} }"}
+    test_parser! {parse_for_in_tuple_pattern,"fn main() { 'outer: for (a, b) in pairs {} }"}
 }
@@ -1,7 +1,7 @@
 use syntax::T;
 
-use crate::parser::pratt::{Precedence, PrefixParser};
-use crate::parser::{restrictions::Restrictions, Parser};
+use crate::parser::pratt::PrefixParser;
+use crate::parser::Parser;
 use crate::SyntaxKind::*;
 
 #[derive(Debug)]
@@ -14,7 +14,11 @@ impl PrefixParser for IdentParselet {
 
         parser.start_node(NAME);
 
-        parser.expect(IDENT);
+        if parser.at(T![self]) {
+            parser.bump();
+        } else {
+            parser.expect(IDENT);
+        }
 
         if parser.at(T![::]) {
             parser.finish_node(); // close name
@@ -29,16 +33,12 @@ impl PrefixParser for IdentParselet {
 
                 parser.start_node(IDENT_EXPR);
 
-                parser.start_node(NAME);
-                parser.expect(IDENT);
+                parser.ident();
 
                 parser.finish_node();
-                parser.finish_node();
-
-                if !parser.at(T![;]) {
-                    parser.parse_expression(Precedence::Assignment, Restrictions::default());
-                }
 
+                // The value of a variant such as `Foo::A(1)` and the arguments of a static
+                // method call such as `Foo::new(1)` are both parsed as a call of the path
                 parser.start_node_at(c, ENUM_EXPR);
                 parser.finish_node();
             }
@@ -57,4 +57,13 @@ mod tests {
     test_parser! {
         parse_generic_ident_expr,"fn main(){a::<i32>;}"
     }
+    test_parser! {
+        parse_enum_expr_in_condition,"fn main(){if x == Foo::A(1) {} else if y == Foo::B {}}"
+    }
+    test_parser! {
+        parse_static_method_call,"fn main(){Person::new(1, 2);}"
+    }
+    test_parser! {
+        parse_self_expr,"fn main(){self;}"
+    }
 }
@@ -10,9 +10,7 @@ impl<'a> Parser<'a> {
 
         self.expect(T![if]);
 
-        self.start_node(CONDITION);
-        self.parse_expression(Precedence::Assignment, Restrictions::no_records());
-        self.finish_node();
+        self.parse_condition();
         self.parse_block();
 
         if self.current() == T![else] {
@@ -22,6 +20,21 @@ impl<'a> Parser<'a> {
         self.finish_node()
     }
 
+    /// The condition of an `if` or `while`, which is either an expression or
+    /// a `let pat = expr` that holds when the value matches the pattern
+    pub(crate) fn parse_condition(&mut self) {
+        self.start_node(CONDITION);
+
+        if self.at(T![let]) {
+            self.bump();
+            self.parse_pattern(true);
+            self.expect(T![=]);
+        }
+
+        self.parse_expression(Precedence::Assignment, Restrictions::no_records());
+        self.finish_node();
+    }
+
     fn parse_else(&mut self) {
         self.bump(); // eat the `else`
 
@@ -39,4 +52,6 @@ mod tests {
     test_parser! {parse_if_and_else,"fn main() { if true {} else {}}"}
     test_parser! {parse_chained_if,"fn main() { if true {} else if false {} else if true {} else if false {} }"}
     test_parser! {parse_chained_if_and_else,"fn main() { if true {} else if false {} else if true {} else {} }"}
+    test_parser! {parse_if_operator_cond,"fn main() { if n > 10 {} else if !a.b {} }"}
+    test_parser! {parse_if_let,"fn main() { if let Foo::A(x) = value {} else if let (a, _) = pair {} }"}
 }
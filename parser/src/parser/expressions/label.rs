@@ -0,0 +1,49 @@
+use syntax::T;
+
+use crate::parser::Parser;
+
+use crate::SyntaxKind::*;
+
+impl<'a> Parser<'a> {
+    /// Parses an optional `'label:` in front of a loop
+    pub(crate) fn parse_label(&mut self) {
+        if self.at(LABEL) {
+            self.bump();
+            self.expect(T![:]);
+        }
+    }
+
+    /// Parses a loop that starts with a label such as `'outer: while true {}`
+    pub(crate) fn parse_labelled_expr(&mut self) {
+        match self.nth(2) {
+            T![while] => self.parse_while_expr(),
+            T![do] => self.parse_do_expr(),
+            T![loop] => self.parse_loop_expr(),
+            T![for] => self.parse_for_expr(),
+            _ => {
+                self.error(
+                    "Expected a loop",
+                    format!(
+                        "Only loops can be labelled but `{}` is followed by `{}`",
+                        self.current_string(),
+                        self.nth(2).text()
+                    ),
+                );
+
+                if self.at(T![:]) {
+                    self.bump();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_parser! {parse_labelled_loops,"fn main() {
+        'outer: while true {
+            'inner: loop { break 'outer; }
+        }
+        'a: do { continue 'a; } while false;
+    }"}
+}
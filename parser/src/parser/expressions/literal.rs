@@ -11,13 +11,13 @@ pub struct LiteralParselet;
 impl PrefixParser for LiteralParselet {
     fn parse(&self, parser: &mut Parser) {
         match parser.current() {
-            INT_NUMBER  | FLOAT_NUMBER | STRING | T![nil] |T![true]|T![false] => {
+            INT_NUMBER  | FLOAT_NUMBER | STRING | CHAR | T![nil] |T![true]|T![false] => {
                 parser.start_node(LITERAL);
                 parser.bump();
                 parser.finish_node();
             }
 
-            _ => parser.error("Expected `{{int}}` or `{{nil}}` or `{{true|false}}` or `{{ident}}` or `{{string}}`",format!("Expected `{{int}}` or `{{nil}}` or `{{true|false}}` or `{{ident}}` or `{{string}}` found `{}`",parser.current_string()))
+            _ => parser.error("Expected `{{int}}` or `{{nil}}` or `{{true|false}}` or `{{ident}}` or `{{string}}` or `{{char}}`",format!("Expected `{{int}}` or `{{nil}}` or `{{true|false}}` or `{{ident}}` or `{{string}}` or `{{char}}` found `{}`",parser.current_string()))
         }
     }
 }
@@ -26,7 +26,12 @@ impl PrefixParser for LiteralParselet {
 mod tests {
     test_parser! {parse_int_literal,"fn main() {1;}"}
     test_parser! {parse_float_literal,"fn main() {1.0;}"}
+    test_parser! {parse_number_literals,"fn main() {0xFF;0b1010;0o17;1_000;1.5e-3;2E+10;10i32;3f32;1..2;}"}
     test_parser! {parse_string_literal,"fn main() {\"abc\";}"}
+    test_parser! {parse_escaped_string_literal,r#"fn main() {"say \"hi\"\n";}"#}
+    test_parser! {parse_raw_string_literal,r###"fn main() {r#"a "raw" \n"#;}"###}
+    test_parser! {parse_multi_line_string_literal,"fn main() {\"a\nb\";}"}
+    test_parser! {parse_char_literal,r"fn main() {'a';'\'';}"}
     test_parser! {parse_nil_literal,"fn main() {nil};"}
     test_parser! {parse_bool_literal,"fn main() {true;false;}"}
 }
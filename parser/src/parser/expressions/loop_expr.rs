@@ -0,0 +1,40 @@
+use syntax::T;
+
+use crate::parser::pratt::PrefixParser;
+use crate::parser::Parser;
+
+use crate::SyntaxKind::*;
+
+#[derive(Debug)]
+pub struct LoopParselet;
+
+impl<'a> Parser<'a> {
+    pub(crate) fn parse_loop_expr(&mut self) {
+        self.start_node(LOOP_EXPR);
+
+        self.parse_label();
+
+        self.expect(T![loop]);
+
+        self.parse_block();
+
+        self.finish_node()
+    }
+}
+
+/// Allows loops to be used as values, i.e `let x = loop { break 10; };`
+impl PrefixParser for LoopParselet {
+    fn parse(&self, parser: &mut Parser) {
+        if parser.at(LABEL) {
+            parser.parse_labelled_expr();
+        } else {
+            parser.parse_loop_expr();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_parser! {parse_loop_expr,"fn main() { loop { x = x + 1; break; } }"}
+    test_parser! {parse_loop_value_expr,"fn main() { let x = loop { break 10; }; }"}
+}
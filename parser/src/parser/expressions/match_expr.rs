@@ -12,7 +12,7 @@ impl<'a> Parser<'a> {
 
         self.expect(T![match]);
 
-        self.parse_expression(Precedence::Call, Restrictions::no_records()); // TODO Forbid struct literal
+        self.parse_expression(Precedence::Assignment, Restrictions::no_records());
 
         self.expect(T!["{"]);
 
@@ -40,16 +40,27 @@ impl<'a> Parser<'a> {
         self.start_node(MATCH_ARM);
         self.parse_pat_list();
 
+        if self.at(T![if]) {
+            self.parse_match_guard();
+        }
+
         self.expected(T![=>]);
 
         if self.at(T!["{"]) {
             self.parse_block()
         } else {
-            self.parse_expression(Precedence::Call, Restrictions::default())
+            self.parse_expression(Precedence::Assignment, Restrictions::default())
         }
         self.finish_node();
     }
 
+    fn parse_match_guard(&mut self) {
+        self.start_node(MATCH_GUARD);
+        self.bump(); // Eat the `if`
+        self.parse_expression(Precedence::Assignment, Restrictions::default());
+        self.finish_node();
+    }
+
     fn parse_pat_list(&mut self) {
         if self.at(T![|]) {
             self.bump();
@@ -67,4 +78,6 @@ impl<'a> Parser<'a> {
 mod tests {
     test_parser! {parse_empty_match_expr,"fn main() { match a {};}"}
     test_parser! {parse_match_expr,"fn main() { match (a,b) { x => 10,1 =>3};}"}
+    test_parser! {parse_match_guard,"fn main() { match a { x if x > 1 => 10, _ =>3};}"}
+    test_parser! {parse_match_or_pattern,"fn main() { match a { 1 | 2 => 10, _ =>3};}"}
 }
@@ -39,11 +39,7 @@ impl<'a> Parser<'a> {
     pub(crate) fn parse_record_field(&mut self) {
         self.start_node(NAMED_FIELD);
 
-        self.start_node(NAME);
-
-        self.expect(IDENT);
-
-        self.finish_node();
+        self.ident();
 
         if self.at(T![:]) {
             self.bump();
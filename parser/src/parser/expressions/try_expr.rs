@@ -0,0 +1,30 @@
+use syntax::T;
+
+use crate::parser::pratt::{InfixParser, Precedence};
+use crate::parser::Parser;
+
+use crate::SyntaxKind::*;
+
+/// `expr?` returns early with the error of a `Result`
+#[derive(Debug)]
+pub struct TryParselet(pub Precedence);
+
+impl InfixParser for TryParselet {
+    fn parse(&self, parser: &mut Parser, checkpoint: rowan::Checkpoint) {
+        parser.start_node_at(checkpoint, TRY_EXPR);
+
+        parser.expect(T![?]);
+
+        parser.finish_node();
+    }
+
+    fn pred(&self) -> Precedence {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_parser! {parse_try_expr,"fn main() {parse(a)?;}"}
+    test_parser! {parse_chained_try_expr,"fn main() {a.first()?.second()?;}"}
+}
@@ -1,7 +1,7 @@
 use syntax::T;
 
 use crate::parser::pratt::{Precedence, PrefixParser};
-use crate::parser::{Parser, Restrictions};
+use crate::parser::Parser;
 
 use crate::SyntaxKind::*;
 
@@ -28,7 +28,7 @@ impl PrefixParser for UnaryParselet {
 
         parser.parse_unary_op();
 
-        parser.parse_expression(Precedence::Unary, Restrictions::default());
+        parser.parse_expression(Precedence::Unary, parser.restrictions());
 
         parser.finish_node();
     }
@@ -1,20 +1,18 @@
 use syntax::T;
 
-use crate::parser::{Parser, Restrictions};
+use crate::parser::Parser;
 
 use crate::SyntaxKind::*;
 
-use crate::parser::Precedence;
-
 impl<'a> Parser<'a> {
     pub(crate) fn parse_while_expr(&mut self) {
         self.start_node(WHILE_EXPR);
 
+        self.parse_label();
+
         self.expect(T![while]);
 
-        self.start_node(CONDITION);
-        self.parse_expression(Precedence::Assignment, Restrictions::no_records());
-        self.finish_node();
+        self.parse_condition();
 
         self.parse_block();
 
@@ -26,4 +24,6 @@ impl<'a> Parser<'a> {
 mod tests {
     test_parser! {parse_empty_while_expr,"fn main(){while true {}}"}
     test_parser! {parse_while_expr,"fn main(){while true {print(\"it works\")}}"}
+    test_parser! {parse_while_comparison_expr,"fn main(){while i < n {i += 1;}}"}
+    test_parser! {parse_while_let_expr,"fn main(){while let Option::Some(x) = next(it) {}}"}
 }
@@ -8,6 +8,25 @@ impl<'a> Parser<'a> {
     pub(crate) fn parse_function(&mut self, checkpoint: rowan::Checkpoint) {
         self.start_node_at(checkpoint, FN_DEF);
 
+        self.parse_function_signature();
+
+        self.parse_block();
+
+        self.finish_node()
+    }
+
+    /// A function without a body such as the methods of a trait
+    pub(crate) fn parse_function_declaration(&mut self, checkpoint: rowan::Checkpoint) {
+        self.start_node_at(checkpoint, FN_DEF);
+
+        self.parse_function_signature();
+
+        self.expect(T![;]);
+
+        self.finish_node()
+    }
+
+    fn parse_function_signature(&mut self) {
         self.expect(T![fn]);
 
         self.ident();
@@ -23,10 +42,6 @@ impl<'a> Parser<'a> {
         if self.current() == T![->] {
             self.parse_return_type();
         }
-
-        self.parse_block();
-
-        self.finish_node()
     }
 
     fn parse_return_type(&mut self) {
@@ -41,4 +56,5 @@ impl<'a> Parser<'a> {
 mod tests {
     test_parser! {parse_function,"fn main() {}"}
     test_parser! {parse_exported_function,"export fn main() {}"}
+    test_parser! {parse_function_bounds,"fn show<T: Show + Debug, U>(x:T) {}"}
 }
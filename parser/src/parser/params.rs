@@ -9,6 +9,17 @@ impl<'a> Parser<'a> {
 
         self.bump();
 
+        // Only the first param of a method can be `self`
+        if self.at(T![self]) {
+            self.start_node(SELF_PARAM);
+            self.bump();
+            self.finish_node();
+
+            if !self.at(T![")"]) {
+                self.expected(T![,]);
+            }
+        }
+
         while !self.at(EOF) && !self.at(T![")"]) {
             self.func_param();
 
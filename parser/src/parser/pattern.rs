@@ -7,9 +7,14 @@ impl<'a> Parser<'a> {
     pub(crate) fn parse_pattern(&mut self, allow_literal: bool) {
         match self.current() {
             T!["("] => self.parse_tuple_pattern(allow_literal),
-            IDENT => self.parse_binding_pattern(),
+            IDENT => match self.peek() {
+                T![::] => self.parse_variant_pattern(allow_literal),
+                T!["{"] => self.parse_record_pattern(allow_literal),
+                _ => self.parse_binding_pattern(),
+            },
             T![self] => self.bump(),
             T![_] => self.parse_placeholder_pattern(),
+            T![..] => self.parse_rest_pattern(),
             e => {
                 if allow_literal {
                     self.start_node(LITERAL_PAT);
@@ -56,6 +61,65 @@ impl<'a> Parser<'a> {
         self.ident();
         self.finish_node();
     }
+
+    fn parse_rest_pattern(&mut self) {
+        self.start_node(REST_PAT);
+        self.expect(T![..]);
+        self.finish_node();
+    }
+
+    /// Parses `Foo::Bar` and `Foo::Bar(pat)`
+    fn parse_variant_pattern(&mut self, allow_literal: bool) {
+        self.start_node(VARIANT_PAT);
+
+        self.ident();
+        self.expect(T![::]);
+        self.ident();
+
+        if self.at(T!["("]) {
+            self.bump();
+            self.parse_pattern(allow_literal);
+            self.expect(T![")"]);
+        }
+
+        self.finish_node();
+    }
+
+    /// Parses `Foo { bar, baz: pat, .. }`
+    fn parse_record_pattern(&mut self, allow_literal: bool) {
+        self.start_node(RECORD_PAT);
+
+        self.ident();
+        self.expect(T!["{"]);
+
+        while !self.at(EOF) && !self.at(T!["}"]) {
+            if self.at(T![..]) {
+                self.parse_rest_pattern();
+            } else {
+                self.parse_record_pattern_field(allow_literal);
+            }
+
+            if !self.at(T!["}"]) && !self.expected(T![,]) {
+                break;
+            }
+        }
+
+        self.expect(T!["}"]);
+        self.finish_node();
+    }
+
+    fn parse_record_pattern_field(&mut self, allow_literal: bool) {
+        self.start_node(RECORD_PAT_FIELD);
+
+        self.ident();
+
+        if self.at(T![:]) {
+            self.bump();
+            self.parse_pattern(allow_literal);
+        }
+
+        self.finish_node();
+    }
 }
 
 #[cfg(test)]
@@ -67,4 +131,10 @@ mod tests {
     test_parser! {parse_binding_pattern,"fn main(x:i32) {}"}
 
     test_parser! {parse_nested_tuple_pattern,"fn main((x,(y,_)):i32) {}"}
+
+    test_parser! {parse_rest_pattern,"fn main((x,..,y):i32) {}"}
+
+    test_parser! {parse_variant_pattern,"fn main() { match foo { Foo::A => 1, Foo::B(x) => x } }"}
+
+    test_parser! {parse_record_pattern,"fn main() { match foo { Foo { bar, baz:(x,_), .. } => 1 } }"}
 }
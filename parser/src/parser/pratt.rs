@@ -20,12 +20,18 @@ pub trait InfixParser: Debug + Any {
 pub enum Precedence {
     None,
     Assignment,
+    Range,
     Or,
     And,
     Equality,
     Comparison,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
     Term,
     Factor,
+    Cast,
     Unary,
     Call,
     Primary,
@@ -53,21 +59,39 @@ pub enum RuleToken {
     MinusEq,
     StarEq,
     SlashEq,
+    PercentEq,
     AmpAmp,
     PipePipe,
+    Amp,
+    Caret,
+    Percent,
+    Shl,
+    Shr,
+    DotDot,
+    DotDotEq,
     ColonColon,
+    As,
+    Loop,
+    Label,
+    Question,
 }
 
 impl Precedence {
     pub fn higher(self) -> Precedence {
         match self {
-            Precedence::None | Precedence::Assignment => Precedence::Or,
+            Precedence::None | Precedence::Assignment => Precedence::Range,
+            Precedence::Range => Precedence::Or,
             Precedence::Or => Precedence::And,
             Precedence::And => Precedence::Equality,
             Precedence::Equality => Precedence::Comparison,
-            Precedence::Comparison => Precedence::Term,
+            Precedence::Comparison => Precedence::BitOr,
+            Precedence::BitOr => Precedence::BitXor,
+            Precedence::BitXor => Precedence::BitAnd,
+            Precedence::BitAnd => Precedence::Shift,
+            Precedence::Shift => Precedence::Term,
             Precedence::Term => Precedence::Factor,
-            Precedence::Factor => Precedence::Unary,
+            Precedence::Factor => Precedence::Cast,
+            Precedence::Cast => Precedence::Unary,
             Precedence::Unary => Precedence::Call,
             Precedence::Call => Precedence::Primary,
             Precedence::Primary => Precedence::Primary,
@@ -78,27 +102,42 @@ impl Precedence {
 impl Rule for SyntaxKind {
     fn rule(&self) -> RuleToken {
         match self {
-            INT_NUMBER | FLOAT_NUMBER | STRING | T![nil] | T![true] | T![false] => {
+            INT_NUMBER | FLOAT_NUMBER | STRING | CHAR | T![nil] | T![true] | T![false] => {
                 RuleToken::Literal
             }
-            IDENT => RuleToken::Ident,
+            IDENT | T![self] => RuleToken::Ident,
             T![::] => RuleToken::ColonColon,
             T![=] => RuleToken::Eq,
             T![.] => RuleToken::Dot,
             T![+=] => RuleToken::PlusEq,
             T![-=] => RuleToken::MinusEq,
             T![/=] => RuleToken::SlashEq,
-            T![*=] => RuleToken::MinusEq,
+            T![*=] => RuleToken::StarEq,
+            T![%=] => RuleToken::PercentEq,
             T![+] => RuleToken::Plus,
+            T![*] => RuleToken::Star,
+            T![/] => RuleToken::Slash,
+            T![%] => RuleToken::Percent,
             T![!] => RuleToken::Excl,
             T![-] => RuleToken::Minus,
             T!["("] => RuleToken::LParen,
             T!["["] => RuleToken::LBracket,
             T!["{"] => RuleToken::LBrace,
-            T![!=] | T![<] | T![>] | T![<=] | T![>=] => RuleToken::Comparison,
+            T![==] | T![!=] => RuleToken::EqEq,
+            T![<] | T![>] | T![<=] | T![>=] => RuleToken::Comparison,
             T![&&] => RuleToken::AmpAmp,
             T![||] => RuleToken::PipePipe,
             T![|] => RuleToken::Pipe,
+            T![&] => RuleToken::Amp,
+            T![^] => RuleToken::Caret,
+            T![<<] => RuleToken::Shl,
+            T![>>] => RuleToken::Shr,
+            T![..] => RuleToken::DotDot,
+            T![..=] => RuleToken::DotDotEq,
+            T![as] => RuleToken::As,
+            T![loop] => RuleToken::Loop,
+            LABEL => RuleToken::Label,
+            T![?] => RuleToken::Question,
             _ => RuleToken::None,
         }
     }
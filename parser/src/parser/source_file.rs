@@ -26,6 +26,8 @@ impl<'a> Parser<'a> {
                 T![import] => self.parse_import(),
                 T![enum] => self.parse_enum(checkpoint),
                 T![class] => self.parse_class(checkpoint),
+                T![trait] => self.parse_trait(checkpoint),
+                T![impl] => self.parse_impl(checkpoint),
                 T!["//"] => {
                     self.bump();
                     continue;
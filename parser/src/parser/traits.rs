@@ -0,0 +1,76 @@
+use syntax::T;
+
+use crate::parser::Parser;
+
+use crate::SyntaxKind::*;
+
+impl<'a> Parser<'a> {
+    pub(crate) fn parse_trait(&mut self, checkpoint: rowan::Checkpoint) {
+        self.start_node_at(checkpoint, TRAIT_DEF);
+
+        self.expect(T![trait]);
+
+        self.ident();
+
+        self.expect(T!["{"]);
+
+        while !self.at(EOF) && !self.at(T!["}"]) {
+            let checkpoint = self.checkpoint();
+
+            match self.current() {
+                T![fn] => self.parse_function_declaration(checkpoint),
+                _ => self.error(
+                    "Expected `fn`",
+                    format!(
+                        "Expected `fn` but instead found `{}`",
+                        self.current_string()
+                    ),
+                ),
+            }
+        }
+
+        self.expect(T!["}"]);
+
+        self.finish_node()
+    }
+
+    pub(crate) fn parse_impl(&mut self, checkpoint: rowan::Checkpoint) {
+        self.start_node_at(checkpoint, IMPL_DEF);
+
+        self.expect(T![impl]);
+
+        self.parse_type();
+
+        self.expect(T![for]);
+
+        self.parse_type();
+
+        self.expect(T!["{"]);
+
+        while !self.at(EOF) && !self.at(T!["}"]) {
+            let checkpoint = self.checkpoint();
+
+            match self.current() {
+                T![fn] => self.parse_function(checkpoint),
+                _ => self.error(
+                    "Expected `fn`",
+                    format!(
+                        "Expected `fn` but instead found `{}`",
+                        self.current_string()
+                    ),
+                ),
+            }
+        }
+
+        self.expect(T!["}"]);
+
+        self.finish_node()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_parser! {parse_trait,"trait Show { fn show(self) -> string; fn name() -> string; }"}
+    test_parser! {parse_exported_trait,"export trait Show {}"}
+    test_parser! {parse_impl,"impl Show for Person { fn show(self) -> string { return self.name; } }"}
+}
@@ -31,4 +31,5 @@ mod tests {
     test_parser! {parse_type_alias,"type Foo = i32;"}
     test_parser! {parse_exported_type_alias,"export type Foo = i32;"}
     test_parser! {parse_type_alias_params,"type ParseResult<T> = Result<T,void>;"}
+    test_parser! {parse_type_alias_nested_params,"type Nested<T> = Option<Result<T,void>>;"}
 }
@@ -28,6 +28,18 @@ impl<'a> Parser<'a> {
     fn type_param(&mut self) {
         self.start_node(TYPE_PARAM);
         self.ident();
+
+        // The traits that the type must implement `T: Show + Debug`
+        if self.at(T![:]) {
+            self.bump();
+            self.parse_type();
+
+            while self.at(T![+]) {
+                self.bump();
+                self.parse_type();
+            }
+        }
+
         self.finish_node();
     }
 }
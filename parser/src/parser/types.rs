@@ -4,6 +4,8 @@ use syntax::T;
 
 impl<'a> Parser<'a> {
     pub(crate) fn parse_type(&mut self) {
+        let checkpoint = self.checkpoint();
+
         match self.current() {
             IDENT | T![self] => self.parse_ident_type(),
             T!["["] => self.parse_array_type(),
@@ -17,6 +19,13 @@ impl<'a> Parser<'a> {
                 ),
             ),
         };
+
+        // `T?` is either a `T` or `nil`
+        if self.at(T![?]) {
+            self.start_node_at(checkpoint, OPTIONAL_TYPE);
+            self.bump();
+            self.finish_node();
+        }
     }
 
     fn parse_ident_type(&mut self) {
@@ -106,6 +115,8 @@ mod tests {
     test_parser! {parse_fn_tuple_type,"fn main(_:fn((i32,i32)) -> i32) {}"}
     test_parser! {parse_ident_type,"fn main(x:i32) {}"}
     test_parser! {parse_array_type,"fn main(x:[i32]) {}"}
+    test_parser! {parse_sized_array_type,"fn main(x:[i32;4]) {}"}
     test_parser! {parse_array_tuple_type,"fn main(x:[(i32,i32)]) {}"}
     test_parser! {parse_tuple_type,"fn main(x:(i32,i32)) {}"}
+    test_parser! {parse_optional_type,"fn main(x:i32?, y:[Foo<i32>?]) -> string? {}"}
 }
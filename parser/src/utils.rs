@@ -25,6 +25,9 @@ impl salsa::Database for MockDatabaseImpl {
     }
 }
 
+#[cfg(test)]
+impl errors::FileSystem for MockDatabaseImpl {}
+
 #[cfg(test)]
 pub fn parse<'a>(input: &'a str) -> SourceFile {
     use crate::ParseDatabase;
@@ -37,5 +40,9 @@ pub fn parse<'a>(input: &'a str) -> SourceFile {
     let db = MockDatabaseImpl::default();
     let handle = db.intern_file(file.path().to_path_buf());
 
-    db.parse(handle).unwrap()
+    let (source_file, diagnostics) = db.parse(handle);
+
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+    source_file
 }
@@ -1,5 +1,1250 @@
 use crate::db::HirDatabase;
-use crate::hir::{self};
-use std::collections::HashMap;
+use crate::hir::{
+    self, BinOp, BlockId, Expr, ExprId, FunctionAstMap, Name, NameId, PatId, UnaryOp,
+};
+use crate::infer::{Type, TypeCon, TypeMap};
+use crate::resolver::Resolver;
+use crate::util;
+use errors::{FileId, Reporter, WithError};
+use std::convert::TryFrom;
+use std::sync::Arc;
+use vm::{CastKind, Chunk, Constant, OpCode};
 
-pub(crate) fn compile_function_query(db: &impl HirDatabase, function: hir::Function) {}
+type Span = (usize, usize);
+
+#[derive(Debug)]
+struct Local {
+    /// Hidden locals used by the compiler have no name
+    name: Option<NameId>,
+    depth: usize,
+}
+
+#[derive(Debug)]
+struct Loop {
+    label: Option<NameId>,
+    /// Where `continue` jumps back to, `None` when a condition or step comes after the body
+    start: Option<usize>,
+    /// The forward jumps of `continue` that need to be patched to the condition or step
+    continues: Vec<usize>,
+    /// The jumps that need to be patched to the end of the loop
+    breaks: Vec<usize>,
+}
+
+/// A part of a value that a sub pattern destructures
+#[derive(Debug, Clone, Copy)]
+enum Part {
+    Element(usize),
+    /// An element of a tuple counted back from the last element
+    ElementFromEnd(usize),
+    Field(NameId),
+    /// The value held by an enum variant
+    VariantValue,
+}
+
+#[derive(Debug)]
+struct FunctionCompiler<'a, DB> {
+    db: &'a DB,
+    /// Used to tell the variants of enums apart from static methods
+    resolver: Arc<Resolver>,
+    /// The types that were inferred for the function
+    types: Option<&'a TypeMap>,
+    ast_map: &'a FunctionAstMap,
+    chunk: Chunk,
+    locals: Vec<Local>,
+    max_locals: usize,
+    scope_depth: usize,
+    loops: Vec<Loop>,
+    reporter: Reporter,
+}
+
+impl<'a, DB> FunctionCompiler<'a, DB>
+where
+    DB: HirDatabase,
+{
+    fn span(&self, id: &ExprId) -> Span {
+        let range = self.ast_map.expr_span(id);
+        (range.start().to_usize(), range.end().to_usize())
+    }
+
+    fn name(&self, name: NameId) -> String {
+        self.db.lookup_intern_name(name).to_string()
+    }
+
+    fn emit(&mut self, op: OpCode, span: Span) {
+        self.chunk.write(op, span)
+    }
+
+    /// The type that was inferred for the expression
+    fn expr_type(&self, id: &ExprId) -> Option<&'a Type> {
+        self.types?.expr(id)
+    }
+
+    /// Emits the arithmetic `op`. The vm stores `i32`s as `i64`s so when `operand` is
+    /// an `i32` the result is checked to still fit into one
+    fn emit_arithmetic(&mut self, op: OpCode, operand: &ExprId, span: Span) {
+        self.emit(op, span);
+
+        let is_i32 = self.expr_type(operand) == Some(&Type::Con(TypeCon::Int));
+
+        let overflows = matches!(
+            op,
+            OpCode::Add | OpCode::Sub | OpCode::Mul | OpCode::Div | OpCode::Shl | OpCode::Negate
+        );
+
+        if is_i32 && overflows {
+            self.emit(OpCode::CheckInt32, span);
+        }
+    }
+
+    /// Reports an operand that doesn't fit into the bytecode, `what` names the things it counts
+    fn too_many(&mut self, what: &str, max: usize, span: Span) {
+        self.reporter.error(
+            format!("Too many {} for the vm", what),
+            format!("At most {} are supported", max),
+            span,
+        );
+    }
+
+    fn byte(&mut self, operand: usize, what: &str, span: Span) -> u8 {
+        u8::try_from(operand).unwrap_or_else(|_| {
+            self.too_many(what, u8::max_value() as usize, span);
+            0
+        })
+    }
+
+    fn short(&mut self, operand: usize, what: &str, span: Span) -> u16 {
+        u16::try_from(operand).unwrap_or_else(|_| {
+            self.too_many(what, u16::max_value() as usize, span);
+            0
+        })
+    }
+
+    fn emit_byte(&mut self, op: OpCode, byte: usize, span: Span) {
+        let what = match op {
+            OpCode::Call => "arguments",
+            OpCode::Tuple | OpCode::TupleGet | OpCode::TupleGetBack => "tuple elements",
+            _ => "locals",
+        };
+
+        let byte = self.byte(byte, what, span);
+
+        self.chunk.write(op, span);
+        self.chunk.write_byte(byte);
+    }
+
+    fn add_constant(&mut self, constant: Constant, span: Span) -> u16 {
+        match self.chunk.add_constant(constant) {
+            Some(index) => index,
+            None => {
+                self.too_many("constants", u16::max_value() as usize + 1, span);
+                0
+            }
+        }
+    }
+
+    fn emit_constant(&mut self, op: OpCode, constant: Constant, span: Span) {
+        let index = self.add_constant(constant, span);
+
+        self.chunk.write(op, span);
+        self.chunk.write_u16(index);
+    }
+
+    fn emit_error(&mut self, msg: impl Into<String>, span: Span) {
+        self.emit_constant(OpCode::Error, Constant::Str(msg.into()), span)
+    }
+
+    /// Emits a jump with a placeholder offset and returns the position of the offset
+    fn emit_jump(&mut self, op: OpCode, span: Span) -> usize {
+        self.chunk.write(op, span);
+        self.chunk.write_u16(u16::max_value());
+        self.chunk.code.len() - 2
+    }
+
+    /// Points the jump at `offset` to the next instruction
+    fn patch_jump(&mut self, offset: usize) {
+        let span = self.chunk.span(offset);
+        let jump = self.short(
+            self.chunk.code.len() - offset - 2,
+            "instructions to jump over",
+            span,
+        );
+        let [hi, lo] = jump.to_be_bytes();
+
+        self.chunk.code[offset] = hi;
+        self.chunk.code[offset + 1] = lo;
+    }
+
+    fn emit_loop(&mut self, start: usize, span: Span) {
+        self.chunk.write(OpCode::Loop, span);
+
+        let jump = self.short(
+            self.chunk.code.len() - start + 2,
+            "instructions in a loop",
+            span,
+        );
+        self.chunk.write_u16(jump);
+    }
+
+    /// The index of the loop that a `break` or `continue` with the given label targets
+    fn loop_target(&self, label: &Option<util::Span<NameId>>) -> Option<usize> {
+        let label = label.as_ref().map(|label| label.item);
+
+        self.loops
+            .iter()
+            .rposition(|l| label.is_none() || l.label == label)
+    }
+
+    /// Pops the innermost loop and points its `break`s past the end of the loop
+    fn patch_breaks(&mut self) {
+        for jump in self.loops.pop().unwrap().breaks {
+            self.patch_jump(jump);
+        }
+    }
+
+    fn begin_scope(&mut self) {
+        self.scope_depth += 1;
+    }
+
+    fn end_scope(&mut self) {
+        self.scope_depth -= 1;
+
+        let depth = self.scope_depth;
+
+        while self.locals.last().map(|local| local.depth > depth) == Some(true) {
+            self.locals.pop();
+        }
+    }
+
+    /// Returns the slot for a new local.
+    /// Names that are bound twice in the same scope reuse their slot
+    fn declare(&mut self, name: Option<NameId>) -> usize {
+        if name.is_some() {
+            if let Some(slot) = self
+                .locals
+                .iter()
+                .rposition(|local| local.depth == self.scope_depth && local.name == name)
+            {
+                return slot;
+            }
+        }
+
+        self.locals.push(Local {
+            name,
+            depth: self.scope_depth,
+        });
+
+        self.max_locals = self.max_locals.max(self.locals.len());
+        self.locals.len() - 1
+    }
+
+    fn resolve(&self, name: NameId) -> Option<usize> {
+        self.locals
+            .iter()
+            .rposition(|local| local.name == Some(name))
+    }
+
+    fn compile_statement(&mut self, stmt: &hir::StmtId) {
+        match self.ast_map.stmt(stmt) {
+            hir::Stmt::Let {
+                pat, initializer, ..
+            } => {
+                let span = pat.as_reporter_span();
+
+                if let Some(expr) = initializer {
+                    self.compile_expr(expr)
+                } else {
+                    self.emit(OpCode::Nil, span)
+                }
+
+                self.bind_pattern(&pat.item, span);
+            }
+            hir::Stmt::Expr(expr) => {
+                self.compile_expr(expr);
+                self.emit(OpCode::Pop, self.span(expr));
+            }
+        }
+    }
+
+    fn compile_block(&mut self, block: &BlockId) {
+        self.begin_scope();
+
+        for stmt in &self.ast_map.block(block).0 {
+            self.compile_statement(stmt);
+        }
+
+        self.end_scope();
+    }
+
+    /// The sub patterns of a pattern along with the part of the value they destructure
+    fn sub_patterns(&self, pat: &PatId) -> Vec<(Part, PatId)> {
+        match self.ast_map.pat(pat) {
+            hir::Pattern::Tuple(patterns) => {
+                let rest = patterns
+                    .iter()
+                    .position(|pat| self.ast_map.pat(&pat.item) == &hir::Pattern::Rest);
+
+                patterns
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, pat)| match rest {
+                        Some(rest) if i == rest => None,
+                        Some(rest) if i > rest => {
+                            Some((Part::ElementFromEnd(patterns.len() - 1 - i), pat.item))
+                        }
+                        _ => Some((Part::Element(i), pat.item)),
+                    })
+                    .collect()
+            }
+            hir::Pattern::Variant { pat: Some(pat), .. } => vec![(Part::VariantValue, pat.item)],
+            hir::Pattern::Record { fields, .. } => fields
+                .iter()
+                .map(|(field, pat)| (Part::Field(field.item), pat.item))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Replaces the value on top of the stack with the part of it
+    fn emit_part(&mut self, part: Part, span: Span) {
+        match part {
+            Part::Element(i) => self.emit_byte(OpCode::TupleGet, i, span),
+            Part::ElementFromEnd(i) => self.emit_byte(OpCode::TupleGetBack, i, span),
+            Part::Field(name) => {
+                let name = Constant::Str(self.name(name));
+                self.emit_constant(OpCode::GetField, name, span)
+            }
+            Part::VariantValue => self.emit(OpCode::VariantValue, span),
+        }
+    }
+
+    /// Pops the value on top of the stack and binds it to the pattern
+    fn bind_pattern(&mut self, pat: &PatId, span: Span) {
+        match self.ast_map.pat(pat) {
+            hir::Pattern::Bind { name } => {
+                let slot = self.declare(Some(name.item));
+                self.emit_byte(OpCode::SetLocal, slot, span);
+            }
+            hir::Pattern::Placeholder
+            | hir::Pattern::Literal(_)
+            | hir::Pattern::Rest
+            | hir::Pattern::Missing => self.emit(OpCode::Pop, span),
+            hir::Pattern::Tuple(_) | hir::Pattern::Variant { .. } | hir::Pattern::Record { .. } => {
+                for (part, pat) in self.sub_patterns(pat) {
+                    self.emit(OpCode::Dup, span);
+                    self.emit_part(part, span);
+                    self.bind_pattern(&pat, span);
+                }
+
+                self.emit(OpCode::Pop, span);
+            }
+        }
+    }
+
+    /// Pops the value on top of the stack and pushes whether it matches the pattern
+    fn test_pattern(&mut self, pat: &PatId, span: Span) {
+        match self.ast_map.pat(pat) {
+            hir::Pattern::Bind { .. }
+            | hir::Pattern::Placeholder
+            | hir::Pattern::Rest
+            | hir::Pattern::Missing => {
+                self.emit(OpCode::Pop, span);
+                self.emit(OpCode::True, span);
+            }
+            hir::Pattern::Literal(literal) => {
+                self.compile_literal(*literal, span);
+                self.emit(OpCode::Equal, span);
+            }
+            hir::Pattern::Tuple(_) | hir::Pattern::Variant { .. } | hir::Pattern::Record { .. } => {
+                let mut fails = Vec::new();
+
+                if let hir::Pattern::Variant { variant, .. } = self.ast_map.pat(pat) {
+                    let name = Constant::Str(self.name(variant.item));
+
+                    self.emit(OpCode::Dup, span);
+                    self.emit_constant(OpCode::IsVariant, name, span);
+                    fails.push(self.emit_jump(OpCode::JumpIfFalse, span));
+                }
+
+                for (part, pat) in self.sub_patterns(pat) {
+                    self.emit(OpCode::Dup, span);
+                    self.emit_part(part, span);
+                    self.test_pattern(&pat, span);
+                    fails.push(self.emit_jump(OpCode::JumpIfFalse, span));
+                }
+
+                self.emit(OpCode::Pop, span);
+                self.emit(OpCode::True, span);
+
+                let end = self.emit_jump(OpCode::Jump, span);
+
+                for fail in fails {
+                    self.patch_jump(fail);
+                }
+
+                self.emit(OpCode::Pop, span);
+                self.emit(OpCode::False, span);
+
+                self.patch_jump(end);
+            }
+        }
+    }
+
+    /// `Foo::Bar` or `Foo::Bar(expr)`
+    fn compile_variant(
+        &mut self,
+        def: &util::Span<NameId>,
+        variant: &util::Span<NameId>,
+        expr: Option<&ExprId>,
+        span: Span,
+    ) {
+        if let Some(expr) = expr {
+            self.compile_expr(expr);
+        }
+
+        let def = self.add_constant(Constant::Str(self.name(def.item)), span);
+        let variant = self.add_constant(Constant::Str(self.name(variant.item)), span);
+
+        self.emit(OpCode::Enum, span);
+        self.chunk.write_u16(def);
+        self.chunk.write_u16(variant);
+        self.chunk.write_byte(expr.is_some() as u8);
+    }
+
+    fn compile_literal(&mut self, literal: hir::LiteralId, span: Span) {
+        let constant = match self.db.lookup_intern_literal(literal) {
+            hir::Literal::String(s) => Constant::Str(s.to_string()),
+            hir::Literal::Char(c) => Constant::Char(c),
+            hir::Literal::Int(i) | hir::Literal::Int64(i) => Constant::Int(i),
+            hir::Literal::Float(bits) | hir::Literal::Float64(bits) => {
+                Constant::Float(hir::Literal::float_value(bits))
+            }
+            hir::Literal::Nil => return self.emit(OpCode::Nil, span),
+            hir::Literal::True => return self.emit(OpCode::True, span),
+            hir::Literal::False => return self.emit(OpCode::False, span),
+        };
+
+        self.emit_constant(OpCode::Constant, constant, span)
+    }
+
+    /// Every expression leaves exactly one value on the stack
+    fn compile_expr(&mut self, id: &ExprId) {
+        let span = self.span(id);
+
+        match self.ast_map.expr(id) {
+            Expr::Array(exprs) => {
+                for expr in exprs {
+                    self.compile_expr(expr);
+                }
+
+                let len = self.short(exprs.len(), "elements in an array literal", span);
+
+                self.emit(OpCode::Array, span);
+                self.chunk.write_u16(len);
+            }
+            Expr::ArrayRepeat { value, count } => {
+                self.compile_expr(value);
+                self.emit_constant(OpCode::Constant, Constant::Int(*count as i64), span);
+                self.emit(OpCode::ArrayRepeat, span);
+            }
+            Expr::Binary { lhs, op, rhs } => self.compile_binary(lhs, *op, rhs, span),
+            Expr::Block(block) => {
+                self.compile_block(block);
+                self.emit(OpCode::Nil, span);
+            }
+            Expr::Break { label, expr } => {
+                let target = match self.loop_target(label) {
+                    Some(target) => target,
+                    None => return self.emit_error("`break` outside of a loop", span),
+                };
+
+                // The value is left on the stack as the value of the loop
+                match expr {
+                    Some(expr) => self.compile_expr(expr),
+                    None => self.emit(OpCode::Nil, span),
+                }
+
+                let jump = self.emit_jump(OpCode::Jump, span);
+                self.loops[target].breaks.push(jump);
+            }
+            Expr::Continue { label } => {
+                let target = match self.loop_target(label) {
+                    Some(target) => target,
+                    None => return self.emit_error("`continue` outside of a loop", span),
+                };
+
+                match self.loops[target].start {
+                    Some(start) => self.emit_loop(start, span),
+                    None => {
+                        let jump = self.emit_jump(OpCode::Jump, span);
+                        self.loops[target].continues.push(jump);
+                    }
+                }
+            }
+            Expr::Missing => self.emit_error("Missing expression", span),
+            Expr::Closure { .. } => {
+                self.reporter.error(
+                    "Closures are not supported by the vm",
+                    "Run the program with the interpreter instead",
+                    span,
+                );
+                self.emit(OpCode::Nil, span);
+            }
+            Expr::Call { callee, args, .. } => {
+                // `Foo::Bar(value)`, the resolver has checked that a single value is given
+                if let Expr::Path { def, name } = self.ast_map.expr(callee) {
+                    if self.resolver.ctx.is_enum(&def.item) {
+                        return self.compile_variant(def, name, args.first(), span);
+                    }
+                }
+
+                self.compile_expr(callee);
+
+                for arg in args {
+                    self.compile_expr(arg);
+                }
+
+                self.emit_byte(OpCode::Call, args.len(), span);
+            }
+            Expr::MethodCall {
+                receiver,
+                method,
+                args,
+                ..
+            } => {
+                self.compile_expr(receiver);
+
+                for arg in args {
+                    self.compile_expr(arg);
+                }
+
+                let args = self.byte(args.len(), "arguments", span);
+
+                let name = Constant::Str(self.name(method.item));
+                self.emit_constant(OpCode::Invoke, name, span);
+                self.chunk.write_byte(args);
+            }
+            Expr::Path { def, name } if self.resolver.ctx.is_enum(&def.item) => {
+                self.compile_variant(def, name, None, span)
+            }
+            Expr::Path { def, name } => {
+                let function = Constant::Function(format!(
+                    "{}::{}",
+                    self.name(def.item),
+                    self.name(name.item)
+                ));
+                self.emit_constant(OpCode::Constant, function, span)
+            }
+            Expr::Cast { expr, .. } => {
+                self.compile_expr(expr);
+
+                // The inferred type has the aliases in the spelled type resolved
+                let kind = match self.expr_type(id) {
+                    Some(Type::Con(TypeCon::Int)) => Some(CastKind::Int),
+                    Some(Type::Con(TypeCon::Int64)) => Some(CastKind::Int64),
+                    Some(Type::Con(TypeCon::Float)) => Some(CastKind::Float),
+                    Some(Type::Con(TypeCon::Float64)) => Some(CastKind::Float64),
+                    Some(Type::Con(TypeCon::Str)) => Some(CastKind::Str),
+                    _ => None,
+                };
+
+                if let Some(kind) = kind {
+                    self.emit_byte(OpCode::Cast, kind as usize, span)
+                }
+            }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.compile_expr(cond);
+
+                let else_jump = self.emit_jump(OpCode::JumpIfFalse, span);
+
+                self.compile_expr(then_branch);
+
+                let end_jump = self.emit_jump(OpCode::Jump, span);
+
+                self.patch_jump(else_jump);
+
+                match else_branch {
+                    Some(else_branch) => self.compile_expr(else_branch),
+                    None => self.emit(OpCode::Nil, span),
+                }
+
+                self.patch_jump(end_jump);
+            }
+            Expr::Ident(name) => self.compile_ident(name.item, span),
+            Expr::Next(iter) => match self.resolve(iter.item) {
+                Some(slot) => self.emit_byte(OpCode::IterNext, slot, span),
+                None => self.emit(OpCode::Nil, span),
+            },
+            Expr::Index { base, index } => {
+                self.compile_expr(base);
+                self.compile_expr(index);
+                self.emit(OpCode::Index, span);
+            }
+            Expr::While {
+                label,
+                cond,
+                body,
+                step,
+            } => {
+                let start = self.chunk.code.len();
+
+                self.compile_expr(cond);
+
+                let exit = self.emit_jump(OpCode::JumpIfFalse, span);
+
+                // With a step a `continue` jumps forward to it instead of back to the condition
+                self.loops.push(Loop {
+                    label: label.as_ref().map(|label| label.item),
+                    start: if step.is_some() { None } else { Some(start) },
+                    continues: Vec::new(),
+                    breaks: Vec::new(),
+                });
+
+                self.compile_block(body);
+
+                if let Some(step) = step {
+                    for jump in std::mem::take(&mut self.loops.last_mut().unwrap().continues) {
+                        self.patch_jump(jump);
+                    }
+
+                    self.compile_expr(step);
+                    self.emit(OpCode::Pop, span);
+                }
+
+                self.emit_loop(start, span);
+                self.patch_jump(exit);
+                self.emit(OpCode::Nil, span);
+
+                self.patch_breaks();
+            }
+            Expr::DoWhile { label, body, cond } => {
+                let start = self.chunk.code.len();
+
+                self.loops.push(Loop {
+                    label: label.as_ref().map(|label| label.item),
+                    start: None,
+                    continues: Vec::new(),
+                    breaks: Vec::new(),
+                });
+
+                self.compile_block(body);
+
+                for jump in std::mem::take(&mut self.loops.last_mut().unwrap().continues) {
+                    self.patch_jump(jump);
+                }
+
+                self.compile_expr(cond);
+
+                let exit = self.emit_jump(OpCode::JumpIfFalse, span);
+                self.emit_loop(start, span);
+                self.patch_jump(exit);
+                self.emit(OpCode::Nil, span);
+
+                self.patch_breaks();
+            }
+            Expr::Loop { label, body } => {
+                let start = self.chunk.code.len();
+
+                self.loops.push(Loop {
+                    label: label.as_ref().map(|label| label.item),
+                    start: Some(start),
+                    continues: Vec::new(),
+                    breaks: Vec::new(),
+                });
+
+                self.compile_block(body);
+                self.emit_loop(start, span);
+
+                // A `loop` is only left through a `break`, which pushes the value of the loop
+                self.patch_breaks();
+            }
+            Expr::Literal(literal) => self.compile_literal(*literal, span),
+            Expr::Paren(expr) => self.compile_expr(expr),
+            Expr::Try(expr) => {
+                self.compile_expr(expr);
+
+                // An `Err` is returned as it is and an `Ok` is unwrapped
+                let err = Constant::Str("Err".into());
+
+                self.emit(OpCode::Dup, span);
+                self.emit_constant(OpCode::IsVariant, err, span);
+
+                let ok_jump = self.emit_jump(OpCode::JumpIfFalse, span);
+
+                self.emit(OpCode::Return, span);
+
+                self.patch_jump(ok_jump);
+
+                self.emit(OpCode::VariantValue, span);
+            }
+            Expr::Tuple(exprs) => {
+                for expr in exprs {
+                    self.compile_expr(expr);
+                }
+
+                self.emit_byte(OpCode::Tuple, exprs.len(), span);
+            }
+            Expr::Unary { op, expr } => {
+                self.compile_expr(expr);
+
+                match op {
+                    UnaryOp::Minus => self.emit_arithmetic(OpCode::Negate, expr, span),
+                    UnaryOp::Excl => self.emit(OpCode::Not, span),
+                }
+            }
+            Expr::Field { base, fields } => {
+                self.compile_expr(base);
+
+                for field in fields {
+                    let name = Constant::Str(self.name(field.item));
+                    self.emit_constant(OpCode::GetField, name, field.as_reporter_span());
+                }
+            }
+            Expr::Return(expr) => {
+                match expr {
+                    Some(expr) => self.compile_expr(expr),
+                    None => self.emit(OpCode::Nil, span),
+                }
+
+                self.emit(OpCode::Return, span);
+            }
+            Expr::Match { expr, arms } => self.compile_match(expr, arms, span),
+            Expr::RecordLiteral { def, fields } => {
+                for (_, expr) in fields {
+                    self.compile_expr(expr);
+                }
+
+                let def = self.add_constant(Constant::Str(self.name(def.item)), span);
+                let count = self.byte(fields.len(), "fields", span);
+
+                self.emit(OpCode::Instance, span);
+                self.chunk.write_u16(def);
+                self.chunk.write_byte(count);
+
+                for (name, _) in fields {
+                    let name = self.add_constant(Constant::Str(self.name(name.item)), span);
+                    self.chunk.write_u16(name);
+                }
+            }
+        }
+    }
+
+    fn compile_ident(&mut self, name: NameId, span: Span) {
+        match self.resolve(name) {
+            Some(slot) => self.emit_byte(OpCode::GetLocal, slot, span),
+            None => {
+                let function = Constant::Function(self.name(name));
+                self.emit_constant(OpCode::Constant, function, span)
+            }
+        }
+    }
+
+    fn compile_binary(&mut self, lhs: &ExprId, op: BinOp, rhs: &ExprId, span: Span) {
+        let op = match op {
+            BinOp::And => {
+                self.compile_expr(lhs);
+
+                let short_circuit = self.emit_jump(OpCode::JumpIfFalse, span);
+
+                self.compile_expr(rhs);
+                self.emit(OpCode::Not, span);
+                self.emit(OpCode::Not, span);
+
+                let end = self.emit_jump(OpCode::Jump, span);
+
+                self.patch_jump(short_circuit);
+                self.emit(OpCode::False, span);
+                self.patch_jump(end);
+                return;
+            }
+            BinOp::Or => {
+                self.compile_expr(lhs);
+
+                let rhs_jump = self.emit_jump(OpCode::JumpIfFalse, span);
+
+                self.emit(OpCode::True, span);
+
+                let end = self.emit_jump(OpCode::Jump, span);
+
+                self.patch_jump(rhs_jump);
+                self.compile_expr(rhs);
+                self.emit(OpCode::Not, span);
+                self.emit(OpCode::Not, span);
+                self.patch_jump(end);
+                return;
+            }
+            BinOp::Equal => {
+                return self.compile_assign(lhs, span, |compiler| compiler.compile_expr(rhs))
+            }
+            BinOp::PlusEqual
+            | BinOp::MinusEqual
+            | BinOp::MultEqual
+            | BinOp::DivEqual
+            | BinOp::RemEqual => {
+                let op = match op {
+                    BinOp::PlusEqual => OpCode::Add,
+                    BinOp::MinusEqual => OpCode::Sub,
+                    BinOp::MultEqual => OpCode::Mul,
+                    BinOp::DivEqual => OpCode::Div,
+                    _ => OpCode::Rem,
+                };
+
+                return self.compile_assign(lhs, span, |compiler| {
+                    compiler.compile_expr(lhs);
+                    compiler.compile_expr(rhs);
+                    compiler.emit_arithmetic(op, lhs, span);
+                });
+            }
+            BinOp::Plus => OpCode::Add,
+            BinOp::Minus => OpCode::Sub,
+            BinOp::Mult => OpCode::Mul,
+            BinOp::Div => OpCode::Div,
+            BinOp::Rem => OpCode::Rem,
+            BinOp::BitAnd => OpCode::BitAnd,
+            BinOp::BitOr => OpCode::BitOr,
+            BinOp::BitXor => OpCode::BitXor,
+            BinOp::ShiftLeft => OpCode::Shl,
+            BinOp::ShiftRight => OpCode::Shr,
+            BinOp::Range => OpCode::Range,
+            BinOp::RangeInclusive => OpCode::RangeInclusive,
+            BinOp::LessThan => OpCode::Less,
+            BinOp::GreaterThan => OpCode::Greater,
+            BinOp::EqualEqual => OpCode::Equal,
+            BinOp::NotEqual => OpCode::NotEqual,
+            BinOp::LessThanEqual => OpCode::LessEqual,
+            BinOp::GreaterThanEqual => OpCode::GreaterEqual,
+            BinOp::Excl => {
+                self.compile_expr(lhs);
+                self.compile_expr(rhs);
+                return self.emit_error(format!("Cannot apply `{}`", op), span);
+            }
+        };
+
+        self.compile_expr(lhs);
+        self.compile_expr(rhs);
+        self.emit_arithmetic(op, lhs, span);
+    }
+
+    /// Store a value into a local, a class field or an array element.
+    /// The value is compiled by `value` once the target has been loaded
+    fn compile_assign(&mut self, target: &ExprId, span: Span, value: impl FnOnce(&mut Self)) {
+        match self.ast_map.expr(target) {
+            Expr::Ident(name) => {
+                if let Some(slot) = self.resolve(name.item) {
+                    value(self);
+                    self.emit_byte(OpCode::SetLocal, slot, span);
+                    return self.emit(OpCode::Nil, span);
+                }
+            }
+            Expr::Field { base, fields } => {
+                let slot = match self.ast_map.expr(base) {
+                    Expr::Ident(name) => self.resolve(name.item),
+                    _ => None,
+                };
+
+                if let Some(slot) = slot {
+                    self.emit_byte(OpCode::GetLocal, slot, span);
+
+                    // Load every instance along the path so the updated ones can be written back
+                    for field in &fields[..fields.len() - 1] {
+                        let name = Constant::Str(self.name(field.item));
+                        self.emit(OpCode::Dup, span);
+                        self.emit_constant(OpCode::GetField, name, field.as_reporter_span());
+                    }
+
+                    value(self);
+
+                    for field in fields.iter().rev() {
+                        let name = Constant::Str(self.name(field.item));
+                        self.emit_constant(OpCode::SetField, name, field.as_reporter_span());
+                    }
+
+                    self.emit_byte(OpCode::SetLocal, slot, span);
+                    return self.emit(OpCode::Nil, span);
+                }
+            }
+            Expr::Index { base, index } => {
+                if let Expr::Ident(name) = self.ast_map.expr(base) {
+                    if let Some(slot) = self.resolve(name.item) {
+                        self.emit_byte(OpCode::GetLocal, slot, span);
+                        self.compile_expr(index);
+                        value(self);
+                        self.emit(OpCode::SetIndex, span);
+                        self.emit_byte(OpCode::SetLocal, slot, span);
+                        return self.emit(OpCode::Nil, span);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        self.emit_error("Invalid assignment target", span);
+        self.emit(OpCode::Nil, span);
+    }
+
+    fn compile_match(&mut self, expr: &ExprId, arms: &[hir::MatchArm], span: Span) {
+        self.compile_expr(expr);
+
+        self.begin_scope();
+
+        let scrutinee = self.declare(None);
+
+        self.emit_byte(OpCode::SetLocal, scrutinee, span);
+
+        let mut ends = Vec::new();
+
+        for arm in arms {
+            self.begin_scope();
+
+            let mut bodies = Vec::new();
+            // The jumps taken when a pattern doesn't match or its guard doesn't hold
+            let mut next_pattern = Vec::new();
+
+            for pat in &arm.pats {
+                for jump in next_pattern.drain(..) {
+                    self.patch_jump(jump);
+                }
+
+                let pat_span = pat.as_reporter_span();
+
+                self.emit_byte(OpCode::GetLocal, scrutinee, pat_span);
+                self.test_pattern(&pat.item, pat_span);
+
+                next_pattern.push(self.emit_jump(OpCode::JumpIfFalse, pat_span));
+
+                self.emit_byte(OpCode::GetLocal, scrutinee, pat_span);
+                self.bind_pattern(&pat.item, pat_span);
+
+                if let Some(guard) = &arm.guard {
+                    self.compile_expr(guard);
+                    next_pattern.push(self.emit_jump(OpCode::JumpIfFalse, pat_span));
+                }
+
+                bodies.push(self.emit_jump(OpCode::Jump, pat_span));
+            }
+
+            for jump in bodies {
+                self.patch_jump(jump);
+            }
+
+            self.compile_expr(&arm.expr);
+
+            self.end_scope();
+
+            ends.push(self.emit_jump(OpCode::Jump, span));
+
+            for jump in next_pattern {
+                self.patch_jump(jump);
+            }
+        }
+
+        self.emit_byte(OpCode::GetLocal, scrutinee, span);
+        self.emit(OpCode::NoMatch, span);
+
+        for jump in ends {
+            self.patch_jump(jump);
+        }
+
+        self.end_scope();
+    }
+}
+
+pub(crate) fn compile_function_query(
+    db: &impl HirDatabase,
+    file: FileId,
+    function: hir::FunctionId,
+) -> WithError<Arc<vm::Function>> {
+    let resolver = db.resolve_source_file(file)?;
+    let types = db.infer(file)?;
+    let function = db.lower_function(function);
+
+    let class = method_class(db, &db.lower(file), function.id);
+
+    let mut compiler = FunctionCompiler {
+        db,
+        resolver,
+        types: types.function(class, function.name.item),
+        ast_map: &function.ast_map,
+        chunk: Chunk::new(),
+        locals: Vec::new(),
+        max_locals: 0,
+        scope_depth: 0,
+        loops: Vec::new(),
+        reporter: Reporter::new(file),
+    };
+
+    let span = (
+        function.span.start().to_usize(),
+        function.span.end().to_usize(),
+    );
+
+    // The arguments are placed in the first slots with the receiver of a method before them
+    if let Some(self_param) = &function.self_param {
+        compiler.declare(Some(self_param.item));
+    }
+
+    let receivers = compiler.locals.len();
+
+    let params = function
+        .params
+        .iter()
+        .map(|param| function.ast_map.param(&param.item).pat.item)
+        .collect::<Vec<_>>();
+
+    for pat in &params {
+        match function.ast_map.pat(pat) {
+            hir::Pattern::Bind { name } => compiler.declare(Some(name.item)),
+            _ => compiler.declare(None),
+        };
+    }
+
+    for (slot, pat) in params.iter().enumerate() {
+        if let hir::Pattern::Bind { .. } = function.ast_map.pat(pat) {
+            continue;
+        }
+
+        compiler.emit_byte(OpCode::GetLocal, receivers + slot, span);
+        compiler.bind_pattern(pat, span);
+    }
+
+    if let Some(body) = &function.body {
+        for stmt in body {
+            compiler.compile_statement(stmt);
+        }
+    }
+
+    compiler.emit(OpCode::Nil, span);
+    compiler.emit(OpCode::Return, span);
+
+    if compiler.reporter.has_errors() {
+        return Err(compiler.reporter.finish());
+    }
+
+    Ok(Arc::new(vm::Function {
+        name: db.lookup_intern_name(function.name.item).to_string(),
+        arity: receivers + params.len(),
+        locals: compiler.max_locals,
+        chunk: compiler.chunk,
+    }))
+}
+
+/// The class that a method is defined in or extended with by an impl
+fn method_class(
+    db: &impl HirDatabase,
+    program: &hir::SourceFile,
+    function: hir::FunctionId,
+) -> Option<NameId> {
+    let is_method =
+        |methods: &[Arc<hir::Function>]| methods.iter().any(|method| method.id == function);
+
+    program
+        .classes
+        .iter()
+        .find(|class| is_method(&class.methods))
+        .map(|class| class.name.item)
+        .or_else(|| {
+            program
+                .impls
+                .iter()
+                .find(|impl_def| is_method(&impl_def.methods))
+                .and_then(|impl_def| db.lookup_intern_type(impl_def.target.item).name())
+        })
+}
+
+pub(crate) fn compile_query(db: &impl HirDatabase, file: FileId) -> WithError<Arc<vm::Program>> {
+    // The lowered program has holes where the syntax errors are
+    let (_, diagnostics) = db.parse(file);
+
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    let program = db.lower(file);
+
+    // Inheritance cycles are reported by the resolver and would never end when the vm
+    // looks up the methods of a superclass
+    db.resolve_source_file(file)?;
+
+    let main_name = db.intern_name(Name::new("main"));
+
+    // Every function is compiled so that all of their errors are reported at once
+    let mut errors = Vec::new();
+
+    let mut compile = |function: &Arc<hir::Function>| match db.compile_function(file, function.id) {
+        Ok(function) => Some(function),
+        Err(diagnostics) => {
+            errors.extend(diagnostics);
+            None
+        }
+    };
+
+    let functions = program.functions.iter().filter_map(&mut compile).collect();
+
+    let classes = program
+        .classes
+        .iter()
+        .map(|class| vm::Class {
+            name: db.lookup_intern_name(class.name.item).to_string(),
+            superclass: class
+                .superclass
+                .as_ref()
+                .and_then(|superclass| db.lookup_intern_type(superclass.item).name())
+                .map(|name| db.lookup_intern_name(name).to_string()),
+            methods: class
+                .methods
+                .iter()
+                .chain(
+                    program
+                        .impls
+                        .iter()
+                        .filter(|impl_def| {
+                            db.lookup_intern_type(impl_def.target.item).name()
+                                == Some(class.name.item)
+                        })
+                        .flat_map(|impl_def| &impl_def.methods),
+                )
+                .filter_map(&mut compile)
+                .collect(),
+        })
+        .collect();
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let main = program
+        .functions
+        .iter()
+        .position(|function| function.exported && function.name.item == main_name);
+
+    Ok(Arc::new(vm::Program {
+        file,
+        functions,
+        classes,
+        main,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HirDatabase;
+
+    /// The vm is checked against the same programs as the tree-walking interpreter
+    macro_rules! create_vm_test {
+        ($filename:ident, compile_err) => {
+            #[test]
+            fn $filename() -> std::io::Result<()> {
+                let (db, handle, _dir) = crate::__interpreter_test_db!($filename);
+
+                assert!(db.compile(handle).is_err());
+                Ok(())
+            }
+        };
+        ($filename:ident, is_err) => {
+            #[test]
+            fn $filename() -> std::io::Result<()> {
+                let (db, handle, _dir) = crate::__interpreter_test_db!($filename);
+
+                let program = db.compile(handle).unwrap();
+
+                assert!(vm::run(&program).is_err());
+                Ok(())
+            }
+        };
+        ($filename:ident, $expected:expr) => {
+            #[test]
+            fn $filename() -> std::io::Result<()> {
+                let (db, handle, _dir) = crate::__interpreter_test_db!($filename);
+
+                let program = db.compile(handle).unwrap();
+
+                match vm::run(&program) {
+                    Ok(value) => assert_eq!(value.to_string(), $expected),
+                    Err(errors) => panic!("{:?}", errors),
+                }
+                Ok(())
+            }
+        };
+    }
+
+    create_vm_test!(recursion, "610");
+
+    create_vm_test!(for_loop, "45");
+
+    create_vm_test!(while_break, "5");
+
+    create_vm_test!(classes, "3");
+
+    create_vm_test!(enums, "Shape::Circle(10)");
+
+    create_vm_test!(match_literal, "two");
+
+    create_vm_test!(tuples, "(1,ab)");
+
+    create_vm_test!(destructuring, "(4,5,0,7,9)");
+
+    create_vm_test!(match_guards, "(10,4,0,3,1,2)");
+
+    create_vm_test!(operators, "(2,7,5,16,64,8,2,true,7)");
+
+    create_vm_test!(strings, "(say \"hi\" H,a \"raw\" \\n,',6,true)");
+
+    create_vm_test!(numbers, "(255,10,15,1000000,0.0015,3,10,2000,10)");
+
+    create_vm_test!(arrays, "(10,7,3,[],[[true,false],[false,false]])");
+
+    create_vm_test!(casts, "(3.5,3,1,65,1.5,7!,-14)");
+    create_vm_test!(cast_to_alias, "(3,2!)");
+
+    create_vm_test!(
+        wide_numbers,
+        "(10000000000,-2147483648,-9223372036854775808,25000000000,1410065408,7,-1.5,16)"
+    );
+
+    create_vm_test!(loops, "(5,1,5,12)");
+
+    create_vm_test!(labelled_loops, "(14,35,3,two)");
+
+    create_vm_test!(for_loops, "(13,xy,82,3,3)");
+
+    create_vm_test!(for_continue, "(40,33)");
+
+    create_vm_test!(iterators, "(431,ab,10)");
+
+    create_vm_test!(ranges, "(3,14,3,2..6)");
+
+    create_vm_test!(field_of_expression, "(1,3,4,4,1)");
+
+    create_vm_test!(if_let, "(12,9,0,9,7)");
+
+    create_vm_test!(inheritance, "(Rex,11,pug,1,Bit)");
+
+    create_vm_test!(methods, "(8,7,counter,stepper,5)");
+
+    create_vm_test!(traits, "(3,10,point,line)");
+
+    create_vm_test!(optionals, "(7,0,true,6,negative)");
+
+    create_vm_test!(missing_main, is_err);
+
+    create_vm_test!(deep_recursion, "4501500");
+
+    create_vm_test!(stack_overflow, is_err);
+
+    create_vm_test!(negate_overflow, is_err);
+
+    create_vm_test!(i32_overflow, is_err);
+
+    create_vm_test!(i32_negate_overflow, is_err);
+
+    create_vm_test!(int_widths, "(2147483648,2147483646,-2147483648)");
+
+    create_vm_test!(too_many_locals, compile_err);
+
+    create_vm_test!(closures, compile_err);
+
+    create_vm_test!(inheritance_cycle, compile_err);
+}
@@ -1,6 +1,6 @@
 use crate::{
     hir::{self, NameId},
-    infer::Type,
+    infer::{InferResult, Type},
     resolver::{ModuleGraph, Resolver, TypeKind},
 };
 use errors::{FileId, WithError};
@@ -28,6 +28,12 @@ pub trait InternDatabase {
     #[salsa::interned]
     fn intern_enum(&self, enum_def: ast::EnumDef) -> hir::EnumId;
 
+    #[salsa::interned]
+    fn intern_trait(&self, trait_def: ast::TraitDef) -> hir::TraitId;
+
+    #[salsa::interned]
+    fn intern_impl(&self, impl_def: ast::ImplDef) -> hir::ImplId;
+
     #[salsa::interned]
     fn intern_type_alias(&self, type_alias_def: ast::TypeAliasDef) -> hir::TypeAliasId;
 
@@ -52,8 +58,12 @@ pub trait HirDatabase: std::fmt::Debug + InternDatabase + ParseDatabase {
     fn lower_class(&self, class: hir::ClassId) -> Arc<hir::Class>;
     #[salsa::invoke(crate::lower::lower_enum_query)]
     fn lower_enum(&self, class: hir::EnumId) -> Arc<hir::Enum>;
+    #[salsa::invoke(crate::lower::lower_trait_query)]
+    fn lower_trait(&self, trait_id: hir::TraitId) -> Arc<hir::Trait>;
+    #[salsa::invoke(crate::lower::lower_impl_query)]
+    fn lower_impl(&self, impl_id: hir::ImplId) -> Arc<hir::Impl>;
     #[salsa::invoke(crate::lower::lower_query)]
-    fn lower(&self, file: FileId) -> WithError<Arc<hir::SourceFile>>;
+    fn lower(&self, file: FileId) -> Arc<hir::SourceFile>;
     #[salsa::invoke(crate::resolver::resolve_exports_query)]
     fn resolve_exports(&self, file: FileId) -> WithError<Arc<Resolver>>;
     #[salsa::invoke(crate::resolver::resolve_modules_query)]
@@ -70,5 +80,16 @@ pub trait HirDatabase: std::fmt::Debug + InternDatabase + ParseDatabase {
     fn module_graph(&self, file: FileId) -> WithError<ModuleGraph>;
 
     #[salsa::invoke(crate::infer::infer_query)]
-    fn infer(&self, file: FileId) -> WithError<()>;
+    fn infer(&self, file: FileId) -> WithError<Arc<InferResult>>;
+    #[salsa::invoke(crate::infer::infer_partial_query)]
+    fn infer_partial(&self, file: FileId) -> WithError<Arc<InferResult>>;
+
+    #[salsa::invoke(crate::compile::compile_function_query)]
+    fn compile_function(
+        &self,
+        file: FileId,
+        function: hir::FunctionId,
+    ) -> WithError<Arc<vm::Function>>;
+    #[salsa::invoke(crate::compile::compile_query)]
+    fn compile(&self, file: FileId) -> WithError<Arc<vm::Program>>;
 }
@@ -17,6 +17,8 @@ pub struct SourceFile {
     pub(crate) type_alias: Vec<Arc<TypeAlias>>,
     pub(crate) classes: Vec<Arc<Class>>,
     pub(crate) enums: Vec<Arc<Enum>>,
+    pub(crate) traits: Vec<Arc<Trait>>,
+    pub(crate) impls: Vec<Arc<Impl>>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -52,9 +54,12 @@ pub struct Segment {
 
 #[derive(Debug, Eq, PartialEq, Hash)]
 pub struct Function {
+    pub(crate) id: FunctionId,
     pub(crate) exported: bool,
     pub(crate) name: util::Span<NameId>,
     pub(crate) ast_map: FunctionAstMap,
+    /// The `self` of an instance method. Static methods and functions don't have one
+    pub(crate) self_param: Option<util::Span<NameId>>,
     pub(crate) params: Vec<util::Span<ParamId>>,
     pub(crate) type_params: Vec<util::Span<TypeParamId>>,
     pub(crate) body: Option<Vec<StmtId>>,
@@ -68,11 +73,42 @@ pub struct Class {
     pub(crate) name: util::Span<NameId>,
     pub(crate) ast_map: FunctionAstMap,
     pub(crate) type_params: Vec<util::Span<TypeParamId>>,
+    /// The class named after `extends`
+    pub(crate) superclass: Option<util::Span<TypeId>>,
     pub(crate) fields: Vec<util::Span<Field>>,
     pub(crate) methods: Vec<Arc<Function>>,
     pub(crate) span: TextRange,
 }
 
+impl Class {
+    /// The names of the type params in the order they are declared
+    pub(crate) fn type_param_names(&self) -> impl Iterator<Item = util::Span<NameId>> + '_ {
+        self.type_params
+            .iter()
+            .map(move |type_param| self.ast_map.type_param(&type_param.item).name)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct Trait {
+    pub(crate) exported: bool,
+    pub(crate) name: util::Span<NameId>,
+    /// The methods that an impl of the trait has to define, they don't have a body
+    pub(crate) methods: Vec<Arc<Function>>,
+    pub(crate) span: TextRange,
+}
+
+/// `impl Trait for Class { .. }`
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct Impl {
+    pub(crate) trait_ref: util::Span<TypeId>,
+    /// The type named after `for`
+    pub(crate) target: util::Span<TypeId>,
+    pub(crate) ast_map: FunctionAstMap,
+    pub(crate) methods: Vec<Arc<Function>>,
+    pub(crate) span: TextRange,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Enum {
     pub(crate) exported: bool,
@@ -156,9 +192,18 @@ pub struct Param {
     pub(crate) ty: util::Span<TypeId>,
 }
 
+/// Closure params can leave out their type and have it inferred
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ClosureParam {
+    pub(crate) pat: util::Span<PatId>,
+    pub(crate) ty: Option<util::Span<TypeId>>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct TypeParam {
     pub(crate) name: util::Span<NameId>,
+    /// The traits that the type has to implement `T: Show + Debug`
+    pub(crate) bounds: Vec<util::Span<TypeId>>,
 }
 
 #[derive(Debug, Eq, PartialEq, Hash)]
@@ -187,25 +232,49 @@ pub struct BlockId(pub(crate) u64);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Pattern {
-    Bind { name: util::Span<NameId> },
+    Bind {
+        name: util::Span<NameId>,
+    },
     Placeholder,
     Tuple(Vec<util::Span<PatId>>),
     Literal(LiteralId),
+    Variant {
+        def: util::Span<NameId>,
+        variant: util::Span<NameId>,
+        pat: Option<util::Span<PatId>>,
+    },
+    /// A field without a pattern binds the field to a local of the same name
+    Record {
+        def: util::Span<NameId>,
+        fields: Vec<(util::Span<NameId>, util::Span<PatId>)>,
+        rest: bool,
+    },
+    /// `..` which skips the remaining elements of a tuple
+    Rest,
+    /// A pattern that couldn't be parsed
+    Missing,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct MatchArm {
+    /// The alternatives of an or-pattern, which all bind the same names
     pub(crate) pats: Vec<util::Span<PatId>>,
+    pub(crate) guard: Option<ExprId>,
     pub(crate) expr: ExprId,
 }
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
 pub enum Literal {
+    /// The decoded value of the string
     String(SmolStr),
+    Char(char),
     Nil,
     True,
     False,
-    Int(SmolStr),
-    Float(SmolStr),
+    Int(i64),
+    Int64(i64),
+    /// The bits of the value so that the literal can be hashed and interned
+    Float(u64),
+    Float64(u64),
 }
 
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
@@ -226,6 +295,20 @@ pub enum Type {
         type_args: Vec<util::Span<TypeId>>,
     },
     Ident(NameId),
+    /// `T?`, a value of type `T` or `nil`
+    Optional(util::Span<TypeId>),
+    /// A type that couldn't be parsed
+    Missing,
+}
+
+impl Type {
+    /// The name of a named type such as `Foo` or `Foo<T>`
+    pub(crate) fn name(&self) -> Option<NameId> {
+        match self {
+            Type::Poly { name, .. } | Type::Ident(name) => Some(*name),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -244,13 +327,22 @@ pub struct Block(pub Vec<StmtId>);
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Expr {
     Array(Vec<ExprId>),
+    /// `[value; count]`, the count has to be an integer literal so the size of the array is known
+    ArrayRepeat {
+        value: ExprId,
+        count: usize,
+    },
     Binary {
         lhs: ExprId,
         op: BinOp,
         rhs: ExprId,
     },
     Block(BlockId),
-    Break,
+    /// `break 'label value`, only a `loop` can be broken out of with a value
+    Break {
+        label: Option<util::Span<NameId>>,
+        expr: Option<ExprId>,
+    },
     Call {
         callee: ExprId,
         args: Vec<ExprId>,
@@ -260,7 +352,21 @@ pub enum Expr {
         expr: ExprId,
         ty: util::Span<TypeId>,
     },
-    Continue,
+    /// The locals a closure captures are found by the resolver
+    Closure {
+        params: Vec<ClosureParam>,
+        returns: Option<util::Span<TypeId>>,
+        body: ExprId,
+    },
+    Continue {
+        label: Option<util::Span<NameId>>,
+    },
+    /// `do { body } while cond`, the body runs before the condition is checked
+    DoWhile {
+        label: Option<util::Span<NameId>>,
+        body: BlockId,
+        cond: ExprId,
+    },
     If {
         cond: ExprId,
         then_branch: ExprId,
@@ -271,18 +377,40 @@ pub enum Expr {
         base: ExprId,
         index: ExprId,
     },
+    /// The step of a `for (init; cond; step)` loop runs after the body and on a `continue`
     While {
+        label: Option<util::Span<NameId>>,
         cond: ExprId,
         body: BlockId,
+        step: Option<ExprId>,
     },
     Literal(LiteralId),
+    /// `receiver.method(args)`, the receiver is passed to the method as `self`
+    MethodCall {
+        receiver: ExprId,
+        method: util::Span<NameId>,
+        args: Vec<ExprId>,
+        type_args: util::Span<Vec<util::Span<TypeId>>>,
+    },
+    /// Advances the iterator of a `for` loop that is held in a local, either an array or a
+    /// class with a `next(self)` method.
+    /// Gives `Option::Some` of the next element or `Option::None` once it runs out
+    Next(util::Span<NameId>),
+    /// `loop { body }` which only stops on a `break` or `return`.
+    /// Its value is the value of the `break` that stops it
+    Loop {
+        label: Option<util::Span<NameId>>,
+        body: BlockId,
+    },
     Paren(ExprId),
     Tuple(Vec<ExprId>),
     Unary {
         op: UnaryOp,
         expr: ExprId,
     },
+    /// The fields accessed in order on `base`, `a.b.c` has the base `a` and the fields `b` and `c`
     Field {
+        base: ExprId,
         fields: Vec<util::Span<NameId>>,
     },
     Return(Option<ExprId>),
@@ -290,15 +418,21 @@ pub enum Expr {
         expr: ExprId,
         arms: Vec<MatchArm>,
     },
-    Enum {
-        def: util::Span<NameId>,
-        variant: util::Span<NameId>,
-        expr: Option<ExprId>,
-    },
     RecordLiteral {
         def: util::Span<NameId>,
         fields: Vec<(util::Span<NameId>, ExprId)>,
     },
+    /// `Foo::bar`, either a static method of the class `Foo` or a variant of the enum `Foo`.
+    /// `Foo` can be imported so which one it is is only known once the file is resolved,
+    /// the value of a variant such as `Foo::Bar(value)` is lowered as the argument of a call
+    Path {
+        def: util::Span<NameId>,
+        name: util::Span<NameId>,
+    },
+    /// `expr?`, gives the `Ok` value of a `Result` or returns its `Err` from the function
+    Try(ExprId),
+    /// An expression that couldn't be parsed
+    Missing,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
@@ -307,8 +441,16 @@ pub enum BinOp {
     Minus,
     Mult,
     Div,
+    Rem,
     And,
     Or,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    Range,
+    RangeInclusive,
     LessThan,
     GreaterThan,
     Excl,
@@ -321,6 +463,7 @@ pub enum BinOp {
     MinusEqual,
     MultEqual,
     DivEqual,
+    RemEqual,
 }
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum UnaryOp {
@@ -328,6 +471,51 @@ pub enum UnaryOp {
     Excl,
 }
 
+impl std::fmt::Display for BinOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let op = match self {
+            BinOp::Plus => "+",
+            BinOp::Minus => "-",
+            BinOp::Mult => "*",
+            BinOp::Div => "/",
+            BinOp::Rem => "%",
+            BinOp::And => "and",
+            BinOp::Or => "or",
+            BinOp::BitAnd => "&",
+            BinOp::BitOr => "|",
+            BinOp::BitXor => "^",
+            BinOp::ShiftLeft => "<<",
+            BinOp::ShiftRight => ">>",
+            BinOp::Range => "..",
+            BinOp::RangeInclusive => "..=",
+            BinOp::LessThan => "<",
+            BinOp::GreaterThan => ">",
+            BinOp::Excl => "!",
+            BinOp::Equal => "=",
+            BinOp::EqualEqual => "==",
+            BinOp::NotEqual => "!=",
+            BinOp::LessThanEqual => "<=",
+            BinOp::GreaterThanEqual => ">=",
+            BinOp::PlusEqual => "+=",
+            BinOp::MinusEqual => "-=",
+            BinOp::MultEqual => "*=",
+            BinOp::DivEqual => "/=",
+            BinOp::RemEqual => "%=",
+        };
+
+        write!(f, "{}", op)
+    }
+}
+
+impl std::fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            UnaryOp::Minus => write!(f, "-"),
+            UnaryOp::Excl => write!(f, "!"),
+        }
+    }
+}
+
 macro_rules! create_intern_key {
     ($name:ident) => {
         #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
@@ -355,6 +543,10 @@ pub(crate) struct FunctionAstMap {
     hir_to_stmt: IndexMap<StmtId, Stmt>,
     hir_to_expr: IndexMap<ExprId, Expr>,
     ast_to_expr: IndexMap<ExprId, AstPtr<ast::Expr>>,
+    /// Missing expressions have no node so only their range is kept
+    missing_expr_spans: IndexMap<ExprId, TextRange>,
+    /// Literals that could not be parsed and array sizes that aren't known, these are reported by the resolver
+    lowering_errors: Vec<util::Span<String>>,
 }
 
 impl FunctionAstMap {
@@ -370,8 +562,22 @@ impl FunctionAstMap {
         self.hir_to_stmt.insert(id, stmt);
     }
 
-    pub fn insert_expr(&mut self, id: ExprId, expr: Expr) {
+    pub fn insert_expr(&mut self, id: ExprId, expr: Expr, ptr: AstPtr<ast::Expr>) {
         self.hir_to_expr.insert(id, expr);
+        self.ast_to_expr.insert(id, ptr);
+    }
+
+    pub fn insert_missing_expr(&mut self, id: ExprId, span: TextRange) {
+        self.hir_to_expr.insert(id, Expr::Missing);
+        self.missing_expr_spans.insert(id, span);
+    }
+
+    pub fn insert_lowering_error(&mut self, error: util::Span<String>) {
+        self.lowering_errors.push(error);
+    }
+
+    pub(crate) fn lowering_errors(&self) -> &[util::Span<String>] {
+        &self.lowering_errors
     }
 
     pub fn insert_block(&mut self, id: BlockId, block: Block) {
@@ -390,6 +596,13 @@ impl FunctionAstMap {
         &self.hir_to_expr[id]
     }
 
+    pub(crate) fn expr_span(&self, id: &ExprId) -> TextRange {
+        match self.ast_to_expr.get(id) {
+            Some(ptr) => ptr.syntax_node_ptr().range(),
+            None => self.missing_expr_spans[id],
+        }
+    }
+
     pub(crate) fn block(&self, id: &BlockId) -> &Block {
         &self.hir_to_block[id]
     }
@@ -398,6 +611,28 @@ impl FunctionAstMap {
         &self.hir_to_pattern[id]
     }
 
+    /// The names bound by a pattern in the order they appear
+    pub(crate) fn pattern_bindings(&self, id: &PatId) -> Vec<util::Span<NameId>> {
+        match self.pat(id) {
+            Pattern::Bind { name } => vec![*name],
+            Pattern::Tuple(pats) => pats
+                .iter()
+                .flat_map(|pat| self.pattern_bindings(&pat.item))
+                .collect(),
+            Pattern::Variant { pat, .. } => pat
+                .iter()
+                .flat_map(|pat| self.pattern_bindings(&pat.item))
+                .collect(),
+            Pattern::Record { fields, .. } => fields
+                .iter()
+                .flat_map(|(_, pat)| self.pattern_bindings(&pat.item))
+                .collect(),
+            Pattern::Placeholder | Pattern::Literal(_) | Pattern::Rest | Pattern::Missing => {
+                vec![]
+            }
+        }
+    }
+
     pub(crate) fn type_param(&self, id: &TypeParamId) -> &TypeParam {
         &self.hir_to_type_params[id]
     }
@@ -405,6 +640,14 @@ impl FunctionAstMap {
     pub(crate) fn param(&self, id: &ParamId) -> &Param {
         &self.hir_to_params[id]
     }
+
+    pub(crate) fn exprs(&self) -> impl Iterator<Item = (&ExprId, &Expr)> + '_ {
+        self.hir_to_expr.iter()
+    }
+
+    pub(crate) fn pats(&self) -> impl Iterator<Item = (&PatId, &Pattern)> + '_ {
+        self.hir_to_pattern.iter()
+    }
 }
 
 macro_rules! hash {
@@ -443,6 +686,8 @@ impl Hash for FunctionAstMap {
 
 create_intern_key!(ClassId);
 create_intern_key!(EnumId);
+create_intern_key!(TraitId);
+create_intern_key!(ImplId);
 create_intern_key!(TypeAliasId);
 create_intern_key!(NameId);
 create_intern_key!(FunctionId);
@@ -470,9 +715,17 @@ impl BinOp {
             T![+] => BinOp::Plus,
             T![*] => BinOp::Mult,
             T![/] => BinOp::Div,
+            T![%] => BinOp::Rem,
             T![=] => BinOp::Equal,
             T![&&] => BinOp::And,
             T![||] => BinOp::Or,
+            T![&] => BinOp::BitAnd,
+            T![|] => BinOp::BitOr,
+            T![^] => BinOp::BitXor,
+            T![<<] => BinOp::ShiftLeft,
+            T![>>] => BinOp::ShiftRight,
+            T![..] => BinOp::Range,
+            T![..=] => BinOp::RangeInclusive,
             T![<] => BinOp::LessThan,
             T![>] => BinOp::GreaterThan,
             T![==] => BinOp::EqualEqual,
@@ -484,6 +737,7 @@ impl BinOp {
             T![-=] => BinOp::MinusEqual,
             T![*=] => BinOp::MultEqual,
             T![/=] => BinOp::DivEqual,
+            T![%=] => BinOp::RemEqual,
 
             _ => return None,
         };
@@ -492,18 +746,178 @@ impl BinOp {
 }
 
 impl Literal {
-    pub(crate) fn from_token(token: syntax::SyntaxToken) -> Literal {
+    /// Numbers are parsed and range checked here, an invalid number is returned as an error
+    /// along with a literal of the same type that can be used in its place
+    pub(crate) fn from_token(token: syntax::SyntaxToken) -> Result<Literal, (String, Literal)> {
         use syntax::SyntaxKind::*;
         let text = token.text().clone();
         let kind = token.kind();
-        match kind {
-            INT_NUMBER => Literal::Int(text),
-            STRING => Literal::String(text),
-            FLOAT_NUMBER => Literal::Float(text),
+        let literal = match kind {
+            INT_NUMBER | FLOAT_NUMBER => return Literal::number(&text, false),
+            STRING => Literal::String(syntax::string_value(&text).into()),
+            CHAR => Literal::Char(syntax::char_value(&text)),
             T![true] => Literal::True,
             T![false] => Literal::False,
             T![nil] => Literal::Nil,
             _ => unreachable!(),
+        };
+
+        Ok(literal)
+    }
+
+    /// Lowers `-number` as a single literal so that the range is checked on the negated value,
+    /// which makes `-2147483648` a valid `i32`
+    pub(crate) fn negative_number(
+        token: syntax::SyntaxToken,
+    ) -> Result<Literal, (String, Literal)> {
+        Literal::number(token.text(), true)
+    }
+
+    pub(crate) fn float(value: f64) -> Literal {
+        Literal::Float(value.to_bits())
+    }
+
+    pub(crate) fn float_value(bits: u64) -> f64 {
+        f64::from_bits(bits)
+    }
+
+    /// Parses `0xFF`, `0b1010`, `0o17`, `1_000`, `1.5e-3` and numbers with an `i32`, `i64`, `f32`
+    /// or `f64` suffix
+    fn number(text: &str, negative: bool) -> Result<Literal, (String, Literal)> {
+        let (radix, body) = match text.get(..2) {
+            Some("0x") => (16, &text[2..]),
+            Some("0o") => (8, &text[2..]),
+            Some("0b") => (2, &text[2..]),
+            _ => (10, text),
+        };
+
+        if radix != 10 && body.contains('.') {
+            return Err((
+                "Only decimal numbers can be floats".into(),
+                Literal::float(0.0),
+            ));
+        }
+
+        let suffix_start = if radix == 16 {
+            body.find(|ch: char| !ch.is_ascii_hexdigit() && ch != '_')
+        } else if radix == 10 {
+            body.char_indices()
+                .find(|&(i, ch)| {
+                    let exponent = (ch == 'e' || ch == 'E')
+                        && body[i + 1..]
+                            .trim_start_matches(|ch| ch == '+' || ch == '-')
+                            .starts_with(|ch: char| ch.is_ascii_digit());
+
+                    ch.is_alphabetic() && !exponent
+                })
+                .map(|(i, _)| i)
+        } else {
+            body.find(|ch: char| !ch.is_ascii_digit() && ch != '_')
+        };
+
+        let (digits, suffix) = body.split_at(suffix_start.unwrap_or_else(|| body.len()));
+        let digits = digits.replace('_', "");
+
+        let (is_float, wide) = match suffix {
+            "" => (
+                radix == 10 && digits.contains(|ch| ch == '.' || ch == 'e' || ch == 'E'),
+                false,
+            ),
+            "f32" => (true, false),
+            "f64" => (true, true),
+            "i32" | "i64" if digits.contains('.') => {
+                return Err((
+                    format!("Float literal cannot have an `{}` suffix", suffix),
+                    Literal::Int(0),
+                ))
+            }
+            "i32" => (false, false),
+            "i64" => (false, true),
+            suffix => {
+                return Err((
+                    format!(
+                        "Invalid suffix `{}`, expected `i32`, `i64`, `f32` or `f64`",
+                        suffix
+                    ),
+                    Literal::Int(0),
+                ))
+            }
+        };
+
+        if is_float {
+            let (max, ty) = if wide {
+                (std::f64::MAX, "f64")
+            } else {
+                (f64::from(std::f32::MAX), "f32")
+            };
+
+            return match digits.parse::<f64>() {
+                Ok(value) if value > max => Err((
+                    format!("Float literal is too large for `{}`", ty),
+                    Literal::float(0.0),
+                )),
+                Ok(value) => {
+                    let value = if negative { -value } else { value };
+
+                    if wide {
+                        Ok(Literal::Float64(value.to_bits()))
+                    } else {
+                        Ok(Literal::float(value))
+                    }
+                }
+                Err(_) => Err((
+                    format!("`{}` is not a valid float", text),
+                    Literal::float(0.0),
+                )),
+            };
+        }
+
+        if digits.is_empty() {
+            return Err((
+                "Expected digits after the base prefix".into(),
+                Literal::Int(0),
+            ));
+        }
+
+        if let Some(digit) = digits.chars().find(|ch| !ch.is_digit(radix)) {
+            let base = match radix {
+                2 => "binary",
+                8 => "octal",
+                16 => "hex",
+                _ => "decimal",
+            };
+
+            return Err((
+                format!("Invalid digit `{}` in a {} number", digit, base),
+                Literal::Int(0),
+            ));
+        }
+
+        // The magnitude of the most negative value is one more than the largest positive value
+        let (max, ty) = if wide {
+            (std::i64::MAX as u64 + negative as u64, "i64")
+        } else {
+            (std::i32::MAX as u64 + negative as u64, "i32")
+        };
+
+        match u64::from_str_radix(&digits, radix) {
+            Ok(value) if value <= max => {
+                let value = if negative {
+                    (value as i64).wrapping_neg()
+                } else {
+                    value as i64
+                };
+
+                if wide {
+                    Ok(Literal::Int64(value))
+                } else {
+                    Ok(Literal::Int(value))
+                }
+            }
+            _ => Err((
+                format!("Integer literal is too large for `{}`", ty),
+                Literal::Int(0),
+            )),
         }
     }
 }
@@ -0,0 +1,616 @@
+use crate::{
+    hir::{self, Expr, Function, NameId, SourceFile},
+    infer::Type,
+    util, HirDatabase,
+};
+use errors::FileId;
+use std::sync::Arc;
+use syntax::{TextRange, TextUnit};
+
+/// The information shown when hovering over an expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hover {
+    pub range: TextRange,
+    pub contents: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Field,
+    Enum,
+    Variant,
+    TypeAlias,
+    Module,
+    Trait,
+    Impl,
+}
+
+/// An item shown in the outline of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The range of the whole item
+    pub range: TextRange,
+    /// The range of the name of the item
+    pub selection_range: TextRange,
+    pub children: Vec<Symbol>,
+}
+
+/// A name that is used within a function body
+#[derive(Debug)]
+enum Reference {
+    /// A local variable or a top level item
+    Name(util::Span<NameId>),
+    /// A top level item
+    Item(util::Span<NameId>),
+    /// A field or a method of a class, `path` is the fields between `base` and the field
+    Field {
+        base: hir::ExprId,
+        path: Vec<util::Span<NameId>>,
+        field: util::Span<NameId>,
+    },
+    /// A field given in a record literal of the class `def`
+    RecordField {
+        def: NameId,
+        field: util::Span<NameId>,
+    },
+    /// A variant of the enum `def` or a static method of the class `def`
+    Path {
+        def: NameId,
+        name: util::Span<NameId>,
+    },
+}
+
+fn range<T>(span: &util::Span<T>) -> TextRange {
+    TextRange::from_to(span.start, span.end)
+}
+
+/// All the functions and methods of a file along with the class that a method belongs to
+fn functions<'a>(
+    db: &impl HirDatabase,
+    program: &'a SourceFile,
+) -> Vec<(Option<NameId>, &'a Arc<Function>)> {
+    let methods = program.classes.iter().flat_map(|class| {
+        class
+            .methods
+            .iter()
+            .map(move |method| (Some(class.name.item), method))
+    });
+
+    let impl_methods = program.impls.iter().flat_map(|impl_def| {
+        let class = db.lookup_intern_type(impl_def.target.item).name();
+
+        impl_def.methods.iter().map(move |method| (class, method))
+    });
+
+    program
+        .functions
+        .iter()
+        .map(|function| (None, function))
+        .chain(methods)
+        .chain(impl_methods)
+        .collect()
+}
+
+fn function_at<'a>(
+    db: &impl HirDatabase,
+    program: &'a SourceFile,
+    offset: TextUnit,
+) -> Option<(Option<NameId>, &'a Arc<Function>)> {
+    functions(db, program)
+        .into_iter()
+        .find(|(_, function)| function.span.contains_inclusive(offset))
+}
+
+fn reference_at(function: &Function, offset: TextUnit) -> Option<Reference> {
+    let contains = |span: &util::Span<NameId>| range(span).contains_inclusive(offset);
+
+    for (_, expr) in function.ast_map.exprs() {
+        match expr {
+            Expr::Ident(name) if contains(name) => return Some(Reference::Name(*name)),
+            Expr::Field { base, fields } => {
+                if let Some(i) = fields.iter().position(|field| contains(field)) {
+                    return Some(Reference::Field {
+                        base: *base,
+                        path: fields[..i].to_vec(),
+                        field: fields[i],
+                    });
+                }
+            }
+            Expr::RecordLiteral { def, fields } => {
+                if contains(def) {
+                    return Some(Reference::Item(*def));
+                }
+
+                if let Some((name, _)) = fields.iter().find(|(name, _)| contains(name)) {
+                    return Some(Reference::RecordField {
+                        def: def.item,
+                        field: *name,
+                    });
+                }
+            }
+            Expr::Path { def, name } => {
+                if contains(def) {
+                    return Some(Reference::Item(*def));
+                }
+
+                if contains(name) {
+                    return Some(Reference::Path {
+                        def: def.item,
+                        name: *name,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// The class that a field or a method is looked up on, which is found from the inferred type
+/// of the base expression and the declared types of the fields in between
+fn receiver_class(
+    db: &impl HirDatabase,
+    file: FileId,
+    program: &SourceFile,
+    (class, function): (Option<NameId>, &Function),
+    base: &hir::ExprId,
+    path: &[util::Span<NameId>],
+) -> Option<NameId> {
+    let result = db.infer_partial(file).ok()?;
+    let ty = result
+        .function(class, function.name.item)?
+        .expr(base)
+        .cloned();
+
+    let is_self = |name: &util::Span<NameId>| {
+        function.self_param.as_ref().map(|param| param.item) == Some(name.item)
+    };
+
+    let mut receiver = match (ty.map(Type::unwrap_poly), function.ast_map.expr(base)) {
+        (Some(Type::Class { name, .. }), _) => name,
+        (_, Expr::Ident(name)) if is_self(name) => class?,
+        _ => return None,
+    };
+
+    for field in path {
+        let (def, _) = find_field(db, program, receiver, field.item)?;
+
+        let ty = def
+            .fields
+            .iter()
+            .find(|def| def.item.property.item == field.item)?;
+
+        receiver = db.lookup_intern_type(ty.item.ty.item).name()?;
+    }
+
+    Some(receiver)
+}
+
+/// The class that declares the field or the method `name` of `class`, either the class
+/// itself or one of its superclasses, along with the range of the declaration
+fn find_field<'a>(
+    db: &impl HirDatabase,
+    program: &'a SourceFile,
+    mut class: NameId,
+    name: NameId,
+) -> Option<(&'a hir::Class, TextRange)> {
+    // A class that inherits from itself has been reported already
+    for _ in 0..program.classes.len() {
+        let def = program.classes.iter().find(|def| def.name.item == class)?;
+
+        let declared = def
+            .fields
+            .iter()
+            .map(|field| &field.item.property)
+            .chain(def.methods.iter().map(|method| &method.name))
+            .find(|field| field.item == name);
+
+        if let Some(field) = declared {
+            return Some((def, range(field)));
+        }
+
+        class = db
+            .lookup_intern_type(def.superclass.as_ref()?.item)
+            .name()?;
+    }
+
+    None
+}
+
+fn find_item(program: &SourceFile, name: NameId) -> Option<TextRange> {
+    program
+        .functions
+        .iter()
+        .map(|function| &function.name)
+        .chain(program.classes.iter().map(|class| &class.name))
+        .chain(program.enums.iter().map(|def| &def.name))
+        .chain(program.type_alias.iter().map(|alias| &alias.name))
+        .chain(program.modules.iter().map(|module| &module.name))
+        .find(|span| span.item == name)
+        .map(range)
+}
+
+/// Returns the type of the expression or the binding under the cursor
+pub fn hover(db: &impl HirDatabase, file: FileId, offset: TextUnit) -> Option<Hover> {
+    let program = db.lower(file);
+    let (class, function) = function_at(db, &program, offset)?;
+
+    let result = db.infer_partial(file).ok()?;
+    let types = result.function(class, function.name.item)?;
+
+    let binding = function.ast_map.pats().find_map(|(id, pat)| match pat {
+        hir::Pattern::Bind { name } if range(name).contains_inclusive(offset) => Some((id, name)),
+        _ => None,
+    });
+
+    if let Some((id, name)) = binding {
+        let ty = types.pat_to_type.get(id)?;
+
+        return Some(Hover {
+            range: range(name),
+            contents: format!("{}: {}", db.lookup_intern_name(name.item), ty.display(db)),
+        });
+    }
+
+    // The innermost expression is the one with the smallest range
+    let (id, expr_range) = function
+        .ast_map
+        .exprs()
+        .map(|(id, _)| (id, function.ast_map.expr_span(id)))
+        .filter(|(_, expr_range)| expr_range.contains_inclusive(offset))
+        .min_by_key(|(_, expr_range)| expr_range.len())?;
+
+    let ty = types.expr_to_type.get(id)?;
+
+    let contents = match function.ast_map.expr(id) {
+        Expr::Ident(name) => format!("{}: {}", db.lookup_intern_name(name.item), ty.display(db)),
+        _ => ty.display(db).to_string(),
+    };
+
+    Some(Hover {
+        range: expr_range,
+        contents,
+    })
+}
+
+/// Returns the range of the definition of the name under the cursor
+pub fn goto_definition(db: &impl HirDatabase, file: FileId, offset: TextUnit) -> Option<TextRange> {
+    let program = db.lower(file);
+    let (class, function) = function_at(db, &program, offset)?;
+
+    match reference_at(function, offset)? {
+        Reference::Name(name) => {
+            // The resolver knows which scopes a local is visible in
+            let resolver = db.resolve_source_file(file).ok()?;
+
+            match resolver.binding(&function.name.item, &name) {
+                Some(binding) => Some(range(&binding)),
+                None => find_item(&program, name.item),
+            }
+        }
+        Reference::Item(name) => find_item(&program, name.item),
+        Reference::Field { base, path, field } => {
+            let receiver = receiver_class(db, file, &program, (class, function), &base, &path)?;
+
+            find_field(db, &program, receiver, field.item).map(|(_, range)| range)
+        }
+        Reference::RecordField { def, field } => {
+            find_field(db, &program, def, field.item).map(|(_, range)| range)
+        }
+        Reference::Path { def, name } => match program
+            .enums
+            .iter()
+            .find(|enum_def| enum_def.name.item == def)
+        {
+            Some(enum_def) => enum_def
+                .variants
+                .iter()
+                .find(|def| def.item.name == name.item)
+                .map(range),
+            None => find_field(db, &program, def, name.item).map(|(_, range)| range),
+        },
+    }
+}
+
+fn function_symbol(db: &impl HirDatabase, function: &Function, kind: SymbolKind) -> Symbol {
+    Symbol {
+        name: db.lookup_intern_name(function.name.item).to_string(),
+        kind,
+        range: function.span,
+        selection_range: range(&function.name),
+        children: Vec::new(),
+    }
+}
+
+/// Returns the outline of a file
+pub fn document_symbols(db: &impl HirDatabase, file: FileId) -> Vec<Symbol> {
+    let program = db.lower(file);
+
+    let name = |name: NameId| db.lookup_intern_name(name).to_string();
+
+    let mut symbols = Vec::new();
+
+    for module in &program.modules {
+        symbols.push(Symbol {
+            name: name(module.name.item),
+            kind: SymbolKind::Module,
+            range: module.span,
+            selection_range: range(&module.name),
+            children: Vec::new(),
+        })
+    }
+
+    for function in &program.functions {
+        symbols.push(function_symbol(db, function, SymbolKind::Function))
+    }
+
+    for class in &program.classes {
+        let fields = class.fields.iter().map(|field| Symbol {
+            name: name(field.item.property.item),
+            kind: SymbolKind::Field,
+            range: range(field),
+            selection_range: range(&field.item.property),
+            children: Vec::new(),
+        });
+
+        let methods = class
+            .methods
+            .iter()
+            .map(|method| function_symbol(db, method, SymbolKind::Method));
+
+        symbols.push(Symbol {
+            name: name(class.name.item),
+            kind: SymbolKind::Class,
+            range: class.span,
+            selection_range: range(&class.name),
+            children: fields.chain(methods).collect(),
+        })
+    }
+
+    for trait_def in &program.traits {
+        symbols.push(Symbol {
+            name: name(trait_def.name.item),
+            kind: SymbolKind::Trait,
+            range: trait_def.span,
+            selection_range: range(&trait_def.name),
+            children: trait_def
+                .methods
+                .iter()
+                .map(|method| function_symbol(db, method, SymbolKind::Method))
+                .collect(),
+        })
+    }
+
+    for impl_def in &program.impls {
+        let type_name = |ty: &util::Span<hir::TypeId>| {
+            db.lookup_intern_type(ty.item)
+                .name()
+                .map_or_else(String::new, name)
+        };
+
+        symbols.push(Symbol {
+            name: format!(
+                "impl {} for {}",
+                type_name(&impl_def.trait_ref),
+                type_name(&impl_def.target)
+            ),
+            kind: SymbolKind::Impl,
+            range: impl_def.span,
+            selection_range: range(&impl_def.target),
+            children: impl_def
+                .methods
+                .iter()
+                .map(|method| function_symbol(db, method, SymbolKind::Method))
+                .collect(),
+        })
+    }
+
+    for enum_def in &program.enums {
+        symbols.push(Symbol {
+            name: name(enum_def.name.item),
+            kind: SymbolKind::Enum,
+            range: enum_def.span,
+            selection_range: range(&enum_def.name),
+            children: enum_def
+                .variants
+                .iter()
+                .map(|variant| Symbol {
+                    name: name(variant.item.name),
+                    kind: SymbolKind::Variant,
+                    range: range(variant),
+                    selection_range: range(variant),
+                    children: Vec::new(),
+                })
+                .collect(),
+        })
+    }
+
+    for alias in &program.type_alias {
+        symbols.push(Symbol {
+            name: name(alias.name.item),
+            kind: SymbolKind::TypeAlias,
+            range: alias.span,
+            selection_range: range(&alias.name),
+            children: Vec::new(),
+        })
+    }
+
+    symbols.sort_by_key(|symbol| symbol.range.start());
+
+    symbols
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{document_symbols, goto_definition, hover, SymbolKind};
+    use crate::resolver::tests::MockDatabaseImpl;
+    use errors::{db::FileDatabase, FileId};
+    use std::io::{self, Write};
+    use syntax::TextUnit;
+    use tempfile::TempDir;
+
+    fn single_file(text: &str) -> io::Result<(MockDatabaseImpl, FileId, TempDir)> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("main.tox");
+
+        write!(&mut std::fs::File::create(&path)?, "{}", text)?;
+
+        let db = MockDatabaseImpl::default();
+        let handle = db.intern_file(path);
+
+        Ok((db, handle, dir))
+    }
+
+    /// The offset of the `nth` occurrence of `needle`
+    fn offset(text: &str, needle: &str, nth: usize) -> TextUnit {
+        let (offset, _) = text.match_indices(needle).nth(nth).unwrap();
+        TextUnit::from_usize(offset)
+    }
+
+    const PROGRAM: &str = "class Point { x:i32; y:i32; } fn add(a:i32, b:i32) -> i32 { return a+b; } export fn main() -> i32 { let total = add(1, 2); let p = Point { x:total, y:2 }; return p.x; }";
+
+    #[test]
+    fn hover_local() -> io::Result<()> {
+        let (db, handle, _dir) = single_file(PROGRAM)?;
+
+        let hover = hover(&db, handle, offset(PROGRAM, "total", 1)).unwrap();
+
+        assert_eq!(hover.contents, "total: i32");
+        Ok(())
+    }
+
+    #[test]
+    fn hover_method_named_like_a_function() -> io::Result<()> {
+        let text = "class Counter { count:i32; fn get(self) -> i32 { let flag = self.count; return flag; } } fn get() -> bool { let flag = true; return flag; } export fn main() -> bool { return get(); }";
+        let (db, handle, _dir) = single_file(text)?;
+
+        let method = hover(&db, handle, offset(text, "flag", 1)).unwrap();
+        let function = hover(&db, handle, offset(text, "flag", 3)).unwrap();
+
+        assert_eq!(method.contents, "flag: i32");
+        assert_eq!(function.contents, "flag: bool");
+        Ok(())
+    }
+
+    #[test]
+    fn hover_with_type_errors() -> io::Result<()> {
+        let text = "fn broken() -> i32 { return true; } trait Size { fn size(self) -> i32; } class Point { x:i32; } impl Size for Point { fn size(self) -> i32 { let width = self.x; return width; } } export fn main() -> i32 { let count = 1; return count; }";
+        let (db, handle, _dir) = single_file(text)?;
+
+        let local = hover(&db, handle, offset(text, "count", 1)).unwrap();
+        let impl_local = hover(&db, handle, offset(text, "width", 1)).unwrap();
+
+        assert_eq!(local.contents, "count: i32");
+        assert_eq!(impl_local.contents, "width: i32");
+        Ok(())
+    }
+
+    #[test]
+    fn goto_field_of_receiver() -> io::Result<()> {
+        let text = "class Size { x:i32; } class Base { x:i32; } class Point extends Base { size: Size; } export fn main() -> i32 { let size = Size { x:1 }; let p = Point { x:2, size:size }; return p.x + p.size.x; }";
+        let (db, handle, _dir) = single_file(text)?;
+
+        // The fields are `x:` and the uses are `.x`
+        let inherited = goto_definition(&db, handle, offset(text, "p.x", 0) + 2.into()).unwrap();
+        let nested = goto_definition(&db, handle, offset(text, "size.x", 0) + 5.into()).unwrap();
+        let literal = goto_definition(&db, handle, offset(text, "x:", 3)).unwrap();
+
+        assert_eq!(inherited.start(), offset(text, "x:", 1));
+        assert_eq!(nested.start(), offset(text, "x:", 0));
+        assert_eq!(literal.start(), offset(text, "x:", 1));
+        Ok(())
+    }
+
+    #[test]
+    fn goto_field_of_call() -> io::Result<()> {
+        let text = "class Point { x:i32; fn make() -> Point { return Point { x:1 }; } } export fn main() -> i32 { return Point::make().x; }";
+        let (db, handle, _dir) = single_file(text)?;
+
+        let field = goto_definition(&db, handle, offset(text, ").x", 0) + 2.into()).unwrap();
+
+        assert_eq!(field.start(), offset(text, "x:", 0));
+        Ok(())
+    }
+
+    #[test]
+    fn goto_local_definition() -> io::Result<()> {
+        let (db, handle, _dir) = single_file(PROGRAM)?;
+
+        let def = goto_definition(&db, handle, offset(PROGRAM, "total", 1)).unwrap();
+
+        assert_eq!(def.start(), offset(PROGRAM, "total", 0));
+        Ok(())
+    }
+
+    #[test]
+    fn goto_local_outside_block() -> io::Result<()> {
+        let text = "fn f(x: i32, c: bool) -> i32 { if c { let x = 2; } return x; } export fn main() -> i32 { return f(1, true); }";
+        let (db, handle, _dir) = single_file(text)?;
+
+        let def = goto_definition(&db, handle, offset(text, "x", 2)).unwrap();
+
+        assert_eq!(def.start(), offset(text, "x", 0));
+        Ok(())
+    }
+
+    #[test]
+    fn goto_item_definition() -> io::Result<()> {
+        let (db, handle, _dir) = single_file(PROGRAM)?;
+
+        let function = goto_definition(&db, handle, offset(PROGRAM, "add", 1)).unwrap();
+        let class = goto_definition(&db, handle, offset(PROGRAM, "Point", 1)).unwrap();
+        let field = goto_definition(&db, handle, offset(PROGRAM, "x", 2)).unwrap();
+
+        assert_eq!(function.start(), offset(PROGRAM, "add", 0));
+        assert_eq!(class.start(), offset(PROGRAM, "Point", 0));
+        assert_eq!(field.start(), offset(PROGRAM, "x", 0));
+        Ok(())
+    }
+
+    #[test]
+    fn outline() -> io::Result<()> {
+        let (db, handle, _dir) = single_file(PROGRAM)?;
+
+        let symbols = document_symbols(&db, handle)
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind, symbol.children.len()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            symbols,
+            vec![
+                ("Point".into(), SymbolKind::Class, 2),
+                ("add".into(), SymbolKind::Function, 0),
+                ("main".into(), SymbolKind::Function, 0)
+            ]
+        );
+        Ok(())
+    }
+    #[test]
+    fn outline_traits_and_impls() -> io::Result<()> {
+        let text = "trait Size { fn size(self) -> i32; } class Point { x:i32; } impl Size for Point { fn size(self) -> i32 { return self.x; } } export fn main() -> i32 { return Point { x:1 }.size(); }";
+        let (db, handle, _dir) = single_file(text)?;
+
+        let symbols = document_symbols(&db, handle)
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind, symbol.children.len()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            symbols,
+            vec![
+                ("Size".into(), SymbolKind::Trait, 1),
+                ("Point".into(), SymbolKind::Class, 1),
+                ("impl Size for Point".into(), SymbolKind::Impl, 1),
+                ("main".into(), SymbolKind::Function, 0)
+            ]
+        );
+        Ok(())
+    }
+}
@@ -1,11 +1,59 @@
 mod ctx;
+mod exhaustive;
+mod expression;
 mod infer;
 mod stacked_map;
 mod ty;
 mod unify;
 
 pub use ctx::Ctx;
-pub(crate) use infer::infer_query;
+pub(crate) use ctx::{Trait, TypeParamBounds};
+pub(crate) use infer::{infer_partial_query, infer_query};
+pub use infer::{InferResult, TypeMap};
 pub(crate) use stacked_map::StackedMap;
 
-pub(crate) use ty::{Type, TypeCon, Variant};
+pub(crate) use ty::{Type, TypeCon, TypeDisplay, TypeVar, Variant};
+
+#[macro_export]
+macro_rules! create_infer_test {
+    ($filename:ident ,is_err) => {
+        $crate::__create_infer_test!($filename, is_err);
+    };
+    ($filename:ident ) => {
+        $crate::__create_infer_test!($filename, is_ok);
+    };
+}
+#[macro_export]
+macro_rules! __create_infer_test {
+    ($filename:ident,$kind:ident) => {
+        #[test]
+        fn $filename() -> std::io::Result<()> {
+            use errors::db::FileDatabase;
+            use $crate::HirDatabase;
+
+            let dir = tempfile::tempdir()?;
+
+            let structure = $crate::resolver::tests::load_file(&format!(
+                "{}/src/infer/tests/{}.ron",
+                env!("CARGO_MANIFEST_DIR"),
+                stringify!($filename)
+            ));
+
+            let mut file_names = Vec::new();
+
+            $crate::resolver::tests::create_structure(&dir.path(), &structure, &mut file_names)?;
+
+            let db = $crate::resolver::tests::MockDatabaseImpl::default();
+
+            let handle = db.intern_file(file_names.remove(0));
+
+            match db.infer(handle) {
+                Ok(_) => {}
+                Err(errors) => println!("{:?}", errors),
+            }
+
+            assert!(db.infer(handle).$kind());
+            Ok(())
+        }
+    };
+}
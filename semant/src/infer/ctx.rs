@@ -5,15 +5,33 @@ use crate::{
     hir::{Name, NameId},
     infer::ty::{Type, TypeCon, TypeVar, Variant},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Ctx {
     types: StackedMap<NameId, Type>,
     kind: HashMap<NameId, TypeKind>,
+    traits: HashMap<NameId, Trait>,
+    /// The classes that implement each trait
+    impls: HashMap<NameId, HashSet<NameId>>,
+    type_params: HashMap<TypeVar, TypeParamBounds>,
     tvar_count: u32,
 }
 
+/// The methods a class has to define to implement a trait
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Trait {
+    pub(crate) methods: HashMap<NameId, Type>,
+    pub(crate) instance_methods: HashSet<NameId>,
+}
+
+/// The type var of a type param along with the traits it is bounded by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeParamBounds {
+    pub(crate) name: NameId,
+    pub(crate) bounds: Vec<NameId>,
+}
+
 impl Ctx {
     pub fn new(db: &impl HirDatabase) -> Self {
         let mut types = StackedMap::new();
@@ -25,15 +43,33 @@ impl Ctx {
         types.insert(db.intern_name(Name::new("f32")), Type::Con(TypeCon::Float));
         kind.insert(db.intern_name(Name::new("f32")), TypeKind::Type);
 
+        kind.insert(db.intern_name(Name::new("i64")), TypeKind::Type);
+        types.insert(db.intern_name(Name::new("i64")), Type::Con(TypeCon::Int64));
+
+        types.insert(
+            db.intern_name(Name::new("f64")),
+            Type::Con(TypeCon::Float64),
+        );
+        kind.insert(db.intern_name(Name::new("f64")), TypeKind::Type);
+
         types.insert(db.intern_name(Name::new("bool")), Type::Con(TypeCon::Bool));
         kind.insert(db.intern_name(Name::new("bool")), TypeKind::Type);
 
         types.insert(db.intern_name(Name::new("void")), Type::Con(TypeCon::Void));
         kind.insert(db.intern_name(Name::new("void")), TypeKind::Type);
 
+        types.insert(db.intern_name(Name::new("char")), Type::Con(TypeCon::Char));
+        kind.insert(db.intern_name(Name::new("char")), TypeKind::Type);
+
         types.insert(db.intern_name(Name::new("string")), Type::Con(TypeCon::Str));
         kind.insert(db.intern_name(Name::new("string")), TypeKind::Type);
 
+        types.insert(
+            db.intern_name(Name::new("range")),
+            Type::Con(TypeCon::Range),
+        );
+        kind.insert(db.intern_name(Name::new("range")), TypeKind::Type);
+
         let result_name = db.intern_name(Name::new("Result"));
         kind.insert(db.intern_name(Name::new("Result")), TypeKind::Enum);
 
@@ -58,14 +94,50 @@ impl Ctx {
             result_name,
             Type::Poly(
                 vec![TypeVar::from(0), TypeVar::from(1)],
-                Box::new(Type::Enum(result_variants)),
+                Box::new(Type::Enum(
+                    result_name,
+                    vec![Type::Var(TypeVar::from(0)), Type::Var(TypeVar::from(1))],
+                    result_variants,
+                )),
+            ),
+        );
+
+        let option_name = db.intern_name(Name::new("Option"));
+        kind.insert(option_name, TypeKind::Enum);
+
+        let mut option_variants = HashMap::new();
+
+        option_variants.insert(
+            db.intern_name(Name::new("Some")),
+            Variant {
+                tag: 0,
+                ty: Some(Type::Var(TypeVar::from(2))), // Some(T)
+            },
+        );
+
+        option_variants.insert(
+            db.intern_name(Name::new("None")),
+            Variant { tag: 1, ty: None },
+        );
+        types.insert(
+            option_name,
+            Type::Poly(
+                vec![TypeVar::from(2)],
+                Box::new(Type::Enum(
+                    option_name,
+                    vec![Type::Var(TypeVar::from(2))],
+                    option_variants,
+                )),
             ),
         );
 
         Self {
             types,
-            tvar_count: 2,
+            tvar_count: 3,
             kind,
+            traits: HashMap::new(),
+            impls: HashMap::new(),
+            type_params: HashMap::new(),
         }
     }
 
@@ -88,6 +160,15 @@ impl Ctx {
         self.types.get(name).map(Clone::clone)
     }
 
+    /// Whether `Foo::bar` names a variant of the enum `Foo` rather than a static method of the
+    /// class `Foo`, which includes the enums that have been imported
+    pub(crate) fn is_enum(&self, name: &NameId) -> bool {
+        matches!(
+            self.get_type(name).map(Type::unwrap_poly),
+            Some(Type::Enum(..))
+        )
+    }
+
     pub(crate) fn get_kind(&self, name: &NameId) -> TypeKind {
         *self.kind.get(name).unwrap()
     }
@@ -96,4 +177,47 @@ impl Ctx {
         self.types.insert(name, ty);
         self.kind.insert(name, kind);
     }
+
+    pub(crate) fn get_trait(&self, name: &NameId) -> Option<&Trait> {
+        self.traits.get(name)
+    }
+
+    pub(crate) fn insert_trait(&mut self, name: NameId, trait_def: Trait) {
+        self.traits.insert(name, trait_def);
+    }
+
+    pub(crate) fn insert_impl(&mut self, trait_name: NameId, class: NameId) {
+        self.impls.entry(trait_name).or_default().insert(class);
+    }
+
+    pub(crate) fn get_type_param(&self, tv: &TypeVar) -> Option<&TypeParamBounds> {
+        self.type_params.get(tv)
+    }
+
+    pub(crate) fn insert_type_param(&mut self, tv: TypeVar, type_param: TypeParamBounds) {
+        self.type_params.insert(tv, type_param);
+    }
+
+    /// Whether the type implements the trait, either through an impl of its class or of
+    /// one of its superclasses or through a bound of a type param
+    pub(crate) fn implements(&self, ty: &Type, trait_name: &NameId) -> bool {
+        match ty {
+            Type::Poly(_, ty) => self.implements(ty, trait_name),
+            Type::Class {
+                name, superclass, ..
+            } => {
+                self.impls
+                    .get(trait_name)
+                    .map_or(false, |classes| classes.contains(name))
+                    || superclass
+                        .as_ref()
+                        .map_or(false, |superclass| self.implements(superclass, trait_name))
+            }
+            Type::Var(tv) => self
+                .type_params
+                .get(tv)
+                .map_or(false, |type_param| type_param.bounds.contains(trait_name)),
+            _ => false,
+        }
+    }
 }
@@ -0,0 +1,505 @@
+//! Checks that the arms of a match cover every value of the matched expression
+//! and that every arm can be reached.
+//! This is the usefulness algorithm from "Warnings for pattern matching" by Luc Maranget.
+//! A pattern is useful if it matches a value that none of the rows above it match.
+//! An arm is unreachable if its patterns are not useful and a match is exhaustive if a
+//! wildcard after the last arm would not be useful.
+use super::{infer::InferDataCollector, Type, TypeCon};
+use crate::{
+    hir::{self, FunctionAstMap, LiteralId, MatchArm, NameId, PatId},
+    HirDatabase,
+};
+use salsa::InternKey;
+use std::collections::HashMap;
+
+/// How many of the missing patterns are listed before the rest are counted
+const MAX_WITNESSES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Constructor {
+    Tuple(usize),
+    Bool(bool),
+    /// Numbers and strings have too many values to list
+    Literal(LiteralId),
+    Variant {
+        def: NameId,
+        variant: NameId,
+        arity: usize,
+    },
+    /// A class pattern with one sub pattern per field, in the order of the field names
+    Record {
+        def: NameId,
+        arity: usize,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum Pat {
+    Wild,
+    Constructor(Constructor, Vec<Pat>),
+}
+
+type Row = Vec<Pat>;
+
+impl Constructor {
+    fn arity(&self) -> usize {
+        match self {
+            Constructor::Tuple(arity)
+            | Constructor::Variant { arity, .. }
+            | Constructor::Record { arity, .. } => *arity,
+            Constructor::Bool(_) | Constructor::Literal(_) => 0,
+        }
+    }
+
+    /// The types of the patterns that the constructor holds
+    fn sub_types(&self, ty: &Type) -> Vec<Type> {
+        // A type without any constructors, used when the type of a sub pattern isn't known
+        let unknown = Type::Con(TypeCon::Void);
+
+        match (self, ty) {
+            (Constructor::Tuple(arity), Type::Tuple(types)) if types.len() == *arity => {
+                types.clone()
+            }
+            (Constructor::Variant { variant, .. }, Type::Enum(_, _, variants)) => {
+                match variants.get(variant).and_then(|variant| variant.ty.clone()) {
+                    Some(ty) => vec![ty],
+                    None => vec![unknown; self.arity()],
+                }
+            }
+            (Constructor::Record { arity, .. }, Type::Class { fields, .. })
+                if fields.len() == *arity =>
+            {
+                sorted_fields(fields)
+                    .into_iter()
+                    .map(|(_, ty)| ty.clone())
+                    .collect()
+            }
+            _ => vec![unknown; self.arity()],
+        }
+    }
+}
+
+/// All the constructors of a type or `None` if there are too many to list.
+/// When the type isn't known we go by the constructors the patterns use
+fn all_constructors(ty: &Type, heads: &[Constructor]) -> Option<Vec<Constructor>> {
+    match ty {
+        Type::Con(TypeCon::Bool) => Some(vec![Constructor::Bool(true), Constructor::Bool(false)]),
+        Type::Tuple(types) => Some(vec![Constructor::Tuple(types.len())]),
+        Type::Enum(def, _, variants) => {
+            let mut variants = variants.iter().collect::<Vec<_>>();
+
+            variants.sort_by_key(|(_, variant)| variant.tag);
+
+            Some(
+                variants
+                    .into_iter()
+                    .map(|(name, variant)| Constructor::Variant {
+                        def: *def,
+                        variant: *name,
+                        arity: variant.ty.is_some() as usize,
+                    })
+                    .collect(),
+            )
+        }
+        Type::Poly(_, ty) => all_constructors(ty, heads),
+        _ => heads.iter().find_map(|head| match head {
+            Constructor::Tuple(_) | Constructor::Record { .. } => Some(vec![*head]),
+            Constructor::Bool(_) => Some(vec![Constructor::Bool(true), Constructor::Bool(false)]),
+            Constructor::Literal(_) | Constructor::Variant { .. } => None,
+        }),
+    }
+}
+
+/// The fields of a class ordered by their names so every record pattern
+/// lists its sub patterns in the same order
+fn sorted_fields<T>(fields: &HashMap<NameId, T>) -> Vec<(&NameId, &T)> {
+    let mut fields = fields.iter().collect::<Vec<_>>();
+
+    fields.sort_by_key(|(name, _)| name.as_intern_id());
+
+    fields
+}
+
+fn heads(rows: &[Row]) -> Vec<Constructor> {
+    rows.iter()
+        .filter_map(|row| match &row[0] {
+            Pat::Constructor(constructor, _) => Some(*constructor),
+            Pat::Wild => None,
+        })
+        .collect()
+}
+
+/// The rows that match the constructor with its sub patterns in place of the first column
+fn specialize(rows: &[Row], constructor: &Constructor) -> Vec<Row> {
+    rows.iter()
+        .filter_map(|row| {
+            let mut specialized = match &row[0] {
+                Pat::Constructor(head, pats) if head == constructor => pats.clone(),
+                Pat::Constructor(..) => return None,
+                Pat::Wild => vec![Pat::Wild; constructor.arity()],
+            };
+
+            specialized.extend_from_slice(&row[1..]);
+
+            Some(specialized)
+        })
+        .collect()
+}
+
+/// The rows that match any constructor that isn't listed in the first column
+fn default(rows: &[Row]) -> Vec<Row> {
+    rows.iter()
+        .filter(|row| match row[0] {
+            Pat::Wild => true,
+            Pat::Constructor(..) => false,
+        })
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+/// Is there a value that `row` matches but none of the `rows` do
+fn is_useful(rows: &[Row], row: &[Pat], types: &[Type]) -> bool {
+    let (ty, rest) = match types.split_first() {
+        Some(split) => split,
+        None => return rows.is_empty(),
+    };
+
+    let specialized_is_useful = |constructor: &Constructor, pats: Vec<Pat>| {
+        let mut types = constructor.sub_types(ty);
+        types.extend_from_slice(rest);
+
+        let mut specialized = pats;
+        specialized.extend_from_slice(&row[1..]);
+
+        is_useful(&specialize(rows, constructor), &specialized, &types)
+    };
+
+    match &row[0] {
+        Pat::Constructor(constructor, pats) => specialized_is_useful(constructor, pats.clone()),
+        Pat::Wild => {
+            let heads = heads(rows);
+
+            match all_constructors(ty, &heads) {
+                Some(all) if all.iter().all(|constructor| heads.contains(constructor)) => {
+                    all.iter().any(|constructor| {
+                        specialized_is_useful(constructor, vec![Pat::Wild; constructor.arity()])
+                    })
+                }
+                _ => is_useful(&default(rows), &row[1..], rest),
+            }
+        }
+    }
+}
+
+/// The values that none of the rows match
+fn witnesses(rows: &[Row], types: &[Type]) -> Vec<Row> {
+    let (ty, rest) = match types.split_first() {
+        Some(split) => split,
+        None if rows.is_empty() => return vec![vec![]],
+        None => return vec![],
+    };
+
+    let heads = heads(rows);
+
+    match all_constructors(ty, &heads) {
+        Some(all) => {
+            let mut missing = Vec::new();
+
+            for constructor in all {
+                let mut types = constructor.sub_types(ty);
+                types.extend_from_slice(rest);
+
+                for mut witness in witnesses(&specialize(rows, &constructor), &types) {
+                    let rest = witness.split_off(constructor.arity());
+
+                    let mut row = vec![Pat::Constructor(constructor, witness)];
+                    row.extend(rest);
+
+                    missing.push(row);
+                }
+            }
+
+            missing
+        }
+        None => witnesses(&default(rows), rest)
+            .into_iter()
+            .map(|witness| {
+                let mut row = vec![Pat::Wild];
+                row.extend(witness);
+                row
+            })
+            .collect(),
+    }
+}
+
+impl<'a, DB> InferDataCollector<&'a DB>
+where
+    DB: HirDatabase,
+{
+    /// Reports the values that the arms don't cover and warns about the arms that can't be reached
+    pub(crate) fn check_match(
+        &mut self,
+        ty: &Type,
+        arms: &[MatchArm],
+        ast_map: &FunctionAstMap,
+        span: (usize, usize),
+    ) {
+        let types = [self.apply(ty)];
+        let mut rows: Vec<Row> = Vec::new();
+
+        for arm in arms {
+            let mut reachable = false;
+
+            for pat in &arm.pats {
+                let row = vec![self.lower_pattern(&pat.item, ast_map)];
+
+                if is_useful(&rows, &row, &types) {
+                    reachable = true;
+                }
+
+                // The guard might not hold so the arm doesn't cover its patterns
+                if arm.guard.is_none() {
+                    rows.push(row);
+                }
+            }
+
+            if let (false, Some(first), Some(last)) = (reachable, arm.pats.first(), arm.pats.last())
+            {
+                self.reporter.warn(
+                    "Unreachable match arm",
+                    "",
+                    (first.start().to_usize(), last.end().to_usize()),
+                );
+            }
+        }
+
+        let missing = witnesses(&rows, &types);
+
+        if missing.is_empty() {
+            return;
+        }
+
+        let mut listed = missing
+            .iter()
+            .take(MAX_WITNESSES)
+            .map(|witness| format!("`{}`", self.display_pattern(&witness[0])))
+            .collect::<Vec<_>>();
+
+        if missing.len() > MAX_WITNESSES {
+            listed.push(format!("{} more", missing.len() - MAX_WITNESSES));
+        }
+
+        let last = listed.pop().unwrap();
+
+        let msg = if listed.is_empty() {
+            format!("non-exhaustive match: {} not covered", last)
+        } else {
+            format!(
+                "non-exhaustive match: {} and {} not covered",
+                listed.join(", "),
+                last
+            )
+        };
+
+        self.reporter.error(msg, "", span);
+    }
+
+    fn lower_pattern(&self, pat: &PatId, ast_map: &FunctionAstMap) -> Pat {
+        match ast_map.pat(pat) {
+            hir::Pattern::Bind { .. }
+            | hir::Pattern::Placeholder
+            | hir::Pattern::Missing
+            | hir::Pattern::Rest => Pat::Wild,
+            hir::Pattern::Tuple(pats) => {
+                let rest = pats
+                    .iter()
+                    .position(|pat| ast_map.pat(&pat.item) == &hir::Pattern::Rest);
+
+                let mut sub_pats = pats
+                    .iter()
+                    .filter(|pat| ast_map.pat(&pat.item) != &hir::Pattern::Rest)
+                    .map(|pat| self.lower_pattern(&pat.item, ast_map))
+                    .collect::<Vec<_>>();
+
+                if let Some(rest) = rest {
+                    // The type checker reported an error if the tuple is too short
+                    let len = match self.pattern_type(pat) {
+                        Some(Type::Tuple(types)) if types.len() >= sub_pats.len() => types.len(),
+                        _ => return Pat::Wild,
+                    };
+
+                    let wilds = vec![Pat::Wild; len - sub_pats.len()];
+
+                    sub_pats.splice(rest..rest, wilds);
+                }
+
+                Pat::Constructor(Constructor::Tuple(sub_pats.len()), sub_pats)
+            }
+            hir::Pattern::Literal(literal) => {
+                let constructor = match self.db.lookup_intern_literal(*literal) {
+                    hir::Literal::True => Constructor::Bool(true),
+                    hir::Literal::False => Constructor::Bool(false),
+                    _ => Constructor::Literal(*literal),
+                };
+
+                Pat::Constructor(constructor, vec![])
+            }
+            hir::Pattern::Variant {
+                def,
+                variant,
+                pat: sub_pat,
+            } => {
+                let sub_pats = match sub_pat {
+                    Some(sub_pat) => vec![self.lower_pattern(&sub_pat.item, ast_map)],
+                    // `Foo::A` matches `A` whatever it holds
+                    None => match self.pattern_type(pat) {
+                        Some(Type::Enum(_, _, variants)) => variants
+                            .get(&variant.item)
+                            .and_then(|variant| variant.ty.as_ref())
+                            .map(|_| vec![Pat::Wild])
+                            .unwrap_or_default(),
+                        _ => vec![],
+                    },
+                };
+
+                Pat::Constructor(
+                    Constructor::Variant {
+                        def: def.item,
+                        variant: variant.item,
+                        arity: sub_pats.len(),
+                    },
+                    sub_pats,
+                )
+            }
+            hir::Pattern::Record { def, fields, .. } => {
+                let class_fields = match self.pattern_type(pat) {
+                    Some(Type::Class { fields, .. }) => fields,
+                    _ => return Pat::Wild,
+                };
+
+                let sub_pats = sorted_fields(&class_fields)
+                    .into_iter()
+                    .map(
+                        |(name, _)| match fields.iter().find(|(field, _)| field.item == *name) {
+                            Some((_, pat)) => self.lower_pattern(&pat.item, ast_map),
+                            None => Pat::Wild,
+                        },
+                    )
+                    .collect::<Vec<_>>();
+
+                Pat::Constructor(
+                    Constructor::Record {
+                        def: def.item,
+                        arity: sub_pats.len(),
+                    },
+                    sub_pats,
+                )
+            }
+        }
+    }
+
+    fn pattern_type(&self, pat: &PatId) -> Option<Type> {
+        self.type_map
+            .pat_to_type
+            .get(pat)
+            .map(|ty| match self.apply(ty) {
+                Type::Poly(_, ty) => *ty,
+                ty => ty,
+            })
+    }
+
+    fn display_pattern(&self, pat: &Pat) -> String {
+        let pats = |pats: &[Pat]| {
+            pats.iter()
+                .map(|pat| self.display_pattern(pat))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        match pat {
+            Pat::Wild => "_".into(),
+            Pat::Constructor(Constructor::Tuple(_), sub_pats) => format!("({})", pats(sub_pats)),
+            Pat::Constructor(Constructor::Bool(b), _) => b.to_string(),
+            Pat::Constructor(Constructor::Literal(literal), _) => {
+                match self.db.lookup_intern_literal(*literal) {
+                    hir::Literal::String(s) => format!("{:?}", s),
+                    hir::Literal::Char(c) => format!("{:?}", c),
+                    hir::Literal::Int(i) | hir::Literal::Int64(i) => i.to_string(),
+                    hir::Literal::Float(bits) | hir::Literal::Float64(bits) => {
+                        hir::Literal::float_value(bits).to_string()
+                    }
+                    hir::Literal::Nil => "nil".into(),
+                    hir::Literal::True => "true".into(),
+                    hir::Literal::False => "false".into(),
+                }
+            }
+            Pat::Constructor(Constructor::Variant { def, variant, .. }, sub_pats) => {
+                let name = format!(
+                    "{}::{}",
+                    self.db.lookup_intern_name(*def),
+                    self.db.lookup_intern_name(*variant)
+                );
+
+                if sub_pats.is_empty() {
+                    name
+                } else {
+                    format!("{}({})", name, pats(sub_pats))
+                }
+            }
+            Pat::Constructor(Constructor::Record { def, .. }, sub_pats) => {
+                let names = match self.ctx.get_type(def) {
+                    Some(Type::Poly(_, ty)) => match *ty {
+                        Type::Class { fields, .. } => sorted_fields(&fields)
+                            .into_iter()
+                            .map(|(name, _)| self.db.lookup_intern_name(*name))
+                            .collect(),
+                        _ => vec![],
+                    },
+                    _ => vec![],
+                };
+
+                let fields = names
+                    .iter()
+                    .zip(sub_pats)
+                    .map(|(name, pat)| format!("{}: {}", name, self.display_pattern(pat)))
+                    .collect::<Vec<_>>();
+
+                format!(
+                    "{} {{ {} }}",
+                    self.db.lookup_intern_name(*def),
+                    fields.join(", ")
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{resolver::tests::MockDatabaseImpl, HirDatabase};
+    use errors::db::FileDatabase;
+    use std::io::{self, Write};
+
+    #[test]
+    fn lists_missing_variants() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("main.tox");
+
+        write!(
+            &mut std::fs::File::create(&path)?,
+            "enum Foo {{ A, B(i32), C, D }} fn main() {{ let foo = Foo::A; match foo {{}} }}"
+        )?;
+
+        let db = MockDatabaseImpl::default();
+        let handle = db.intern_file(path);
+
+        let errors = db.infer(handle).unwrap_err();
+
+        assert_eq!(
+            errors[0].message,
+            "non-exhaustive match: `Foo::A`, `Foo::B(_)`, `Foo::C` and 1 more not covered"
+        );
+
+        Ok(())
+    }
+}
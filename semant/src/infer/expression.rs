@@ -0,0 +1,1215 @@
+use super::{infer::InferDataCollector, Type, TypeCon, TypeParamBounds};
+use crate::{
+    hir::{self, BinOp, BlockId, Expr, ExprId, FunctionAstMap, NameId, Stmt, UnaryOp},
+    util, HirDatabase,
+};
+use std::collections::HashMap;
+
+/// The types accepted by the arithmetic operators
+const NUMBERS: &[TypeCon] = &[
+    TypeCon::Int,
+    TypeCon::Int64,
+    TypeCon::Float,
+    TypeCon::Float64,
+];
+
+impl<'a, DB> InferDataCollector<&'a DB>
+where
+    DB: HirDatabase,
+{
+    pub(crate) fn infer_expr(&mut self, id: &ExprId, ast_map: &FunctionAstMap) -> Type {
+        let expr = ast_map.expr(id);
+        let range = ast_map.expr_span(id);
+        let span = (range.start().to_usize(), range.end().to_usize());
+
+        let ty = match expr {
+            Expr::Array(exprs) => {
+                let mut ty = self.fresh_var();
+
+                for expr in exprs {
+                    let expr_ty = self.infer_expr(expr, ast_map);
+                    let range = ast_map.expr_span(expr);
+
+                    ty = self.join(
+                        &ty,
+                        &expr_ty,
+                        (range.start().to_usize(), range.end().to_usize()),
+                    );
+                }
+
+                Type::Con(TypeCon::Array {
+                    ty: Box::new(ty),
+                    size: Some(exprs.len()),
+                })
+            }
+            Expr::ArrayRepeat { value, count } => Type::Con(TypeCon::Array {
+                ty: Box::new(self.infer_expr(value, ast_map)),
+                size: Some(*count),
+            }),
+            Expr::Binary { lhs, op, rhs } => {
+                let lhs_ty = self.infer_expr(lhs, ast_map);
+                let rhs_ty = self.infer_expr(rhs, ast_map);
+
+                match op {
+                    // The value that is assigned can be a subclass of the target's class
+                    BinOp::Equal => {
+                        let _ = self.coerce(&lhs_ty, &rhs_ty, span);
+                    }
+                    BinOp::EqualEqual | BinOp::NotEqual => {
+                        self.join(&lhs_ty, &rhs_ty, span);
+                    }
+                    _ => {
+                        let _ = self.unify(&lhs_ty, &rhs_ty, span);
+                    }
+                }
+
+                match op {
+                    BinOp::Plus | BinOp::PlusEqual => {
+                        self.expect_operand(
+                            op,
+                            &lhs_ty,
+                            &[
+                                TypeCon::Int,
+                                TypeCon::Int64,
+                                TypeCon::Float,
+                                TypeCon::Float64,
+                                TypeCon::Str,
+                            ],
+                            span,
+                        );
+                        if *op == BinOp::Plus {
+                            lhs_ty
+                        } else {
+                            Type::Con(TypeCon::Void)
+                        }
+                    }
+                    BinOp::Minus | BinOp::Mult | BinOp::Div | BinOp::Rem => {
+                        self.expect_operand(op, &lhs_ty, NUMBERS, span);
+                        lhs_ty
+                    }
+                    BinOp::MinusEqual | BinOp::MultEqual | BinOp::DivEqual | BinOp::RemEqual => {
+                        self.expect_operand(op, &lhs_ty, NUMBERS, span);
+                        Type::Con(TypeCon::Void)
+                    }
+                    BinOp::LessThan
+                    | BinOp::GreaterThan
+                    | BinOp::LessThanEqual
+                    | BinOp::GreaterThanEqual => {
+                        self.expect_operand(
+                            op,
+                            &lhs_ty,
+                            &[
+                                TypeCon::Int,
+                                TypeCon::Int64,
+                                TypeCon::Float,
+                                TypeCon::Float64,
+                                TypeCon::Char,
+                                TypeCon::Str,
+                            ],
+                            span,
+                        );
+                        Type::Con(TypeCon::Bool)
+                    }
+                    BinOp::BitAnd
+                    | BinOp::BitOr
+                    | BinOp::BitXor
+                    | BinOp::ShiftLeft
+                    | BinOp::ShiftRight => {
+                        self.expect_operand(op, &lhs_ty, &[TypeCon::Int, TypeCon::Int64], span);
+                        lhs_ty
+                    }
+                    BinOp::Range | BinOp::RangeInclusive => {
+                        self.expect_operand(op, &lhs_ty, &[TypeCon::Int], span);
+                        Type::Con(TypeCon::Range)
+                    }
+                    BinOp::EqualEqual | BinOp::NotEqual => Type::Con(TypeCon::Bool),
+                    BinOp::And | BinOp::Or | BinOp::Excl => {
+                        let _ = self.unify(&Type::Con(TypeCon::Bool), &lhs_ty, span);
+                        Type::Con(TypeCon::Bool)
+                    }
+                    BinOp::Equal => {
+                        if let Expr::Index { base, .. } = ast_map.expr(lhs) {
+                            let base_ty = self.type_map.expr(base).cloned();
+
+                            if base_ty.map(|ty| self.apply(&ty)) == Some(Type::Con(TypeCon::Range))
+                            {
+                                self.reporter.error(
+                                    "The elements of a range can't be assigned to",
+                                    "",
+                                    span,
+                                );
+                            }
+                        }
+
+                        Type::Con(TypeCon::Void)
+                    }
+                }
+            }
+            Expr::Block(block_id) => {
+                let block = ast_map.block(block_id);
+
+                self.locals.begin_scope();
+
+                for stmt in &block.0 {
+                    self.infer_statement(stmt, ast_map);
+                }
+
+                self.locals.end_scope();
+
+                Type::Con(TypeCon::Void)
+            }
+            Expr::Break { label, expr } => {
+                let label = label.as_ref().map(|label| label.item);
+
+                let break_ty = self
+                    .loops
+                    .iter()
+                    .rev()
+                    .find(|(loop_label, _)| label.is_none() || *loop_label == label)
+                    .and_then(|(_, ty)| ty.clone());
+
+                let ty = match expr {
+                    Some(expr) => self.infer_expr(expr, ast_map),
+                    None => Type::Con(TypeCon::Void),
+                };
+
+                // The resolver reports a `break` with a value out of a `while`
+                if let Some(break_ty) = break_ty {
+                    let _ = self.unify(&break_ty, &ty, span);
+                }
+
+                Type::Con(TypeCon::Void)
+            }
+            Expr::Continue { .. } => Type::Con(TypeCon::Void),
+            // The parser has already reported the error
+            Expr::Missing => self.fresh_var(),
+            Expr::Call {
+                callee,
+                args,
+                type_args,
+            } => match ast_map.expr(callee) {
+                // `Foo::Bar(value)`, the resolver has checked that a single value is given
+                Expr::Path { def, name } if self.ctx.is_enum(&def.item) => {
+                    let ty = self.infer_variant(def, name, args.first(), ast_map, span);
+
+                    self.type_map.expr_to_type.insert(*callee, ty.clone());
+
+                    ty
+                }
+                _ => self.infer_call(callee, args, type_args, ast_map, span),
+            },
+            Expr::MethodCall {
+                receiver,
+                method,
+                args,
+                type_args,
+            } => {
+                let receiver_ty = self.infer_expr(receiver, ast_map);
+                // A local with a class type annotation holds the class's poly type
+                let applied = self.apply(&receiver_ty);
+
+                let receiver_ty = self.instantiate(applied);
+
+                let method_ty = match self.declared_class(receiver_ty, span) {
+                    Type::Class {
+                        name,
+                        methods,
+                        instance_methods,
+                        ..
+                    } => match methods.get(&method.item) {
+                        Some(ty) if instance_methods.contains(&method.item) => {
+                            Some(self.instantiate_with(ty.clone(), type_args, method.item))
+                        }
+                        Some(_) => {
+                            let msg = format!(
+                                "`{}::{}` doesn't take `self`",
+                                self.db.lookup_intern_name(name),
+                                self.db.lookup_intern_name(method.item)
+                            );
+
+                            let note = format!(
+                                "Call it as `{}::{}()`",
+                                self.db.lookup_intern_name(name),
+                                self.db.lookup_intern_name(method.item)
+                            );
+
+                            self.reporter.error(msg, note, method.as_reporter_span());
+
+                            None
+                        }
+                        None => {
+                            let msg = format!(
+                                "Unknown method `{}`",
+                                self.db.lookup_intern_name(method.item)
+                            );
+
+                            self.reporter.error(msg, "", method.as_reporter_span());
+
+                            None
+                        }
+                    },
+                    Type::Var(tv) => match self.ctx.get_type_param(&tv).cloned() {
+                        Some(type_param) => self.bound_method(&type_param, method, type_args),
+                        None => None,
+                    },
+                    Type::Con(TypeCon::Optional(inner)) => {
+                        let range = ast_map.expr_span(receiver);
+
+                        self.might_be_nil(
+                            &inner,
+                            (range.start().to_usize(), range.end().to_usize()),
+                        );
+
+                        None
+                    }
+                    ty => {
+                        let msg = format!(
+                            "`{}` doesn't have the method `{}`",
+                            self.display(&ty),
+                            self.db.lookup_intern_name(method.item)
+                        );
+
+                        self.reporter.error(msg, "", method.as_reporter_span());
+
+                        None
+                    }
+                };
+
+                let args = args
+                    .iter()
+                    .map(|arg| self.infer_expr(arg, ast_map))
+                    .collect::<Vec<_>>();
+
+                match method_ty {
+                    Some(method_ty) => self.check_call(&method_ty, args, span),
+                    None => self.fresh_var(),
+                }
+            }
+            Expr::Path { def, name } if self.ctx.is_enum(&def.item) => {
+                self.infer_variant(def, name, None, ast_map, span)
+            }
+            Expr::Path { def, name } => {
+                let class_ty = match self.ctx.get_type(&def.item) {
+                    Some(ty) => self.instantiate(ty),
+                    None => self.fresh_var(),
+                };
+
+                match class_ty {
+                    Type::Class { methods, .. } => match methods.get(&name.item) {
+                        Some(ty) => self.instantiate(ty.clone()),
+                        None => self.fresh_var(),
+                    },
+                    _ => self.fresh_var(),
+                }
+            }
+            Expr::Cast { expr, ty } => {
+                let expr_ty = self.infer_expr(expr, ast_map);
+                let cast_ty = self.infer_type(ty);
+
+                self.check_cast(&expr_ty, &cast_ty, span);
+
+                cast_ty
+            }
+            Expr::Closure {
+                params,
+                returns,
+                body,
+            } => {
+                self.locals.begin_scope();
+
+                let mut signature = Vec::new();
+
+                for param in params {
+                    let ty = match &param.ty {
+                        Some(ty) => self.infer_type(ty),
+                        None => self.fresh_var(),
+                    };
+
+                    self.infer_pattern(&param.pat, &ty, ast_map);
+
+                    signature.push(ty);
+                }
+
+                // Without an annotation the return type comes from the body's return statements
+                let ret = match returns {
+                    Some(returns) => self.infer_type(returns),
+                    None => self.fresh_var(),
+                };
+
+                let outer_return_ty = std::mem::replace(&mut self.return_ty, ret.clone());
+                let outer_loops = std::mem::take(&mut self.loops);
+
+                self.infer_expr(body, ast_map);
+
+                self.return_ty = outer_return_ty;
+                self.loops = outer_loops;
+
+                self.locals.end_scope();
+
+                signature.push(ret);
+
+                Type::App(signature)
+            }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                let cond_ty = self.infer_expr(cond, ast_map);
+                let range = ast_map.expr_span(cond);
+
+                let _ = self.unify(
+                    &Type::Con(TypeCon::Bool),
+                    &cond_ty,
+                    (range.start().to_usize(), range.end().to_usize()),
+                );
+
+                // `if x != nil` lets the then branch use `x` as the type it wraps
+                // and `if x == nil` lets the else branch
+                let narrowed = self.narrowed_local(cond, ast_map);
+
+                self.locals.begin_scope();
+
+                if let Some((name, ty, true)) = &narrowed {
+                    self.locals.insert(*name, ty.clone());
+                }
+
+                let then_ty = self.infer_expr(then_branch, ast_map);
+
+                self.locals.end_scope();
+
+                let ty = match else_branch {
+                    Some(else_branch) => {
+                        self.locals.begin_scope();
+
+                        if let Some((name, ty, false)) = &narrowed {
+                            self.locals.insert(*name, ty.clone());
+                        }
+
+                        let else_ty = self.infer_expr(else_branch, ast_map);
+
+                        self.locals.end_scope();
+
+                        self.join(&then_ty, &else_ty, span)
+                    }
+                    None => then_ty,
+                };
+
+                // When the branch where `x` is `nil` always leaves, the code after the `if`
+                // can only be reached when it isn't
+                if let Some((name, inner, not_nil_when_true)) = narrowed {
+                    let nil_branch = if not_nil_when_true {
+                        else_branch.as_ref()
+                    } else {
+                        Some(then_branch)
+                    };
+
+                    if nil_branch.map_or(false, |branch| diverges(branch, ast_map)) {
+                        self.locals.insert(name, inner);
+                    }
+                }
+
+                ty
+            }
+            Expr::Ident(name) => {
+                if let Some(ty) = self.locals.get(&name.item) {
+                    ty.clone()
+                } else if let Some(ty) = self.ctx.get_type(&name.item) {
+                    self.instantiate(ty)
+                } else {
+                    self.fresh_var()
+                }
+            }
+            Expr::Index { base, index } => {
+                let base_ty = self.infer_expr(base, ast_map);
+                let index_ty = self.infer_expr(index, ast_map);
+
+                let range = ast_map.expr_span(base);
+
+                let ty = if self.apply(&base_ty) == Type::Con(TypeCon::Range) {
+                    Type::Con(TypeCon::Int)
+                } else {
+                    let ty = self.fresh_var();
+
+                    let _ = self.unify(
+                        &Type::Con(TypeCon::Array {
+                            ty: Box::new(ty.clone()),
+                            size: None,
+                        }),
+                        &base_ty,
+                        (range.start().to_usize(), range.end().to_usize()),
+                    );
+
+                    ty
+                };
+
+                let range = ast_map.expr_span(index);
+
+                let _ = self.unify(
+                    &Type::Con(TypeCon::Int),
+                    &index_ty,
+                    (range.start().to_usize(), range.end().to_usize()),
+                );
+
+                ty
+            }
+            Expr::While {
+                label,
+                cond,
+                body,
+                step,
+            } => {
+                let cond_ty = self.infer_expr(cond, ast_map);
+                let range = ast_map.expr_span(cond);
+
+                let _ = self.unify(
+                    &Type::Con(TypeCon::Bool),
+                    &cond_ty,
+                    (range.start().to_usize(), range.end().to_usize()),
+                );
+
+                self.infer_loop_body(label, None, body, ast_map);
+
+                if let Some(step) = step {
+                    self.infer_expr(step, ast_map);
+                }
+
+                Type::Con(TypeCon::Void)
+            }
+            Expr::DoWhile { label, body, cond } => {
+                self.infer_loop_body(label, None, body, ast_map);
+
+                let cond_ty = self.infer_expr(cond, ast_map);
+                let range = ast_map.expr_span(cond);
+
+                let _ = self.unify(
+                    &Type::Con(TypeCon::Bool),
+                    &cond_ty,
+                    (range.start().to_usize(), range.end().to_usize()),
+                );
+
+                Type::Con(TypeCon::Void)
+            }
+            Expr::Loop { label, body } => {
+                let ty = self.fresh_var();
+
+                self.infer_loop_body(label, Some(ty.clone()), body, ast_map);
+
+                ty
+            }
+            Expr::Literal(literal) => self.infer_literal(*literal),
+            Expr::Next(iter) => {
+                let item = self.fresh_var();
+
+                let option = self.db.intern_name(hir::Name::new("Option"));
+
+                let option = match self.ctx.get_type(&option) {
+                    Some(Type::Poly(tvs, inner)) => {
+                        let mut mappings = HashMap::new();
+                        mappings.insert(tvs[0], item.clone());
+                        inner.subst(&mappings)
+                    }
+                    _ => self.fresh_var(),
+                };
+
+                let state = self
+                    .locals
+                    .get(&iter.item)
+                    .cloned()
+                    .unwrap_or_else(|| self.fresh_var());
+
+                let iterable = self.fresh_var();
+
+                let _ = self.unify(
+                    &Type::Tuple(vec![iterable.clone(), Type::Con(TypeCon::Int)]),
+                    &state,
+                    iter.as_reporter_span(),
+                );
+
+                self.infer_iterable(&iterable, &item, &option, iter.as_reporter_span());
+
+                option
+            }
+            Expr::Paren(expr) => self.infer_expr(expr, ast_map),
+            Expr::Try(expr) => {
+                let expr_ty = self.infer_expr(expr, ast_map);
+                let range = ast_map.expr_span(expr);
+
+                self.infer_try(
+                    &expr_ty,
+                    (range.start().to_usize(), range.end().to_usize()),
+                    span,
+                )
+            }
+            Expr::Tuple(exprs) => Type::Tuple(
+                exprs
+                    .iter()
+                    .map(|expr| self.infer_expr(expr, ast_map))
+                    .collect(),
+            ),
+            Expr::Unary { op, expr } => {
+                let ty = self.infer_expr(expr, ast_map);
+
+                match op {
+                    UnaryOp::Minus => {
+                        self.expect_operand(op, &ty, NUMBERS, span);
+                        ty
+                    }
+                    UnaryOp::Excl => {
+                        let _ = self.unify(&Type::Con(TypeCon::Bool), &ty, span);
+                        Type::Con(TypeCon::Bool)
+                    }
+                }
+            }
+            Expr::Field { base, fields } => {
+                let mut ty = self.infer_expr(base, ast_map);
+
+                let range = ast_map.expr_span(base);
+                let mut receiver_span = (range.start().to_usize(), range.end().to_usize());
+
+                for field in fields {
+                    // A local with a class type annotation holds the class's poly type
+                    let applied = self.apply(&ty);
+
+                    let instantiated = self.instantiate(applied);
+
+                    ty = match self.declared_class(instantiated, field.as_reporter_span()) {
+                        Type::Class {
+                            name,
+                            fields: def_fields,
+                            methods,
+                            ..
+                        } => {
+                            if let Some(ty) = def_fields.get(&field.item) {
+                                ty.clone()
+                            } else if let Some(ty) = methods.get(&field.item) {
+                                self.instantiate(ty.clone())
+                            } else {
+                                let field_name = self.db.lookup_intern_name(field.item);
+
+                                let msg = format!(
+                                    "Unknown field `{}` on `{}`",
+                                    field_name,
+                                    self.db.lookup_intern_name(name)
+                                );
+
+                                let names = def_fields
+                                    .keys()
+                                    .map(|name| self.db.lookup_intern_name(*name))
+                                    .collect::<Vec<_>>();
+
+                                let note = util::closest_name(
+                                    field_name.as_str(),
+                                    names.iter().map(|name| name.as_str()),
+                                )
+                                .map(|closest| format!("Did you mean `{}`?", closest))
+                                .unwrap_or_default();
+
+                                self.reporter.error(msg, note, field.as_reporter_span());
+
+                                self.fresh_var()
+                            }
+                        }
+                        Type::Var(_) => self.fresh_var(),
+                        Type::Con(TypeCon::Optional(inner)) => {
+                            self.might_be_nil(&inner, receiver_span);
+
+                            self.fresh_var()
+                        }
+                        ty => {
+                            let msg = format!(
+                                "`{}` doesn't have the field `{}`",
+                                self.display(&ty),
+                                self.db.lookup_intern_name(field.item)
+                            );
+
+                            self.reporter.error(msg, "", field.as_reporter_span());
+
+                            self.fresh_var()
+                        }
+                    };
+
+                    receiver_span = field.as_reporter_span();
+                }
+
+                ty
+            }
+            Expr::Return(expr) => {
+                let ty = if let Some(expr) = expr {
+                    self.infer_expr(expr, ast_map)
+                } else {
+                    Type::Con(TypeCon::Void)
+                };
+
+                let return_ty = self.return_ty.clone();
+
+                let _ = self.coerce(&return_ty, &ty, span);
+
+                Type::Con(TypeCon::Void)
+            }
+            Expr::Match { expr, arms } => {
+                let expr_ty = self.infer_expr(expr, ast_map);
+
+                let mut ty = self.fresh_var();
+
+                for arm in arms {
+                    self.locals.begin_scope();
+
+                    for (i, pat) in arm.pats.iter().enumerate() {
+                        if i == 0 {
+                            self.infer_pattern(pat, &expr_ty, ast_map);
+                            continue;
+                        }
+
+                        // A name bound by every alternative must have the same type in each of them
+                        let bindings = ast_map.pattern_bindings(&pat.item);
+
+                        let previous = bindings
+                            .iter()
+                            .map(|name| self.locals.get(&name.item).cloned())
+                            .collect::<Vec<_>>();
+
+                        self.infer_pattern(pat, &expr_ty, ast_map);
+
+                        for (name, previous) in bindings.iter().zip(previous) {
+                            if let (Some(previous), Some(ty)) =
+                                (previous, self.locals.get(&name.item).cloned())
+                            {
+                                let _ = self.unify(&previous, &ty, name.as_reporter_span());
+                            }
+                        }
+                    }
+
+                    if let Some(guard) = &arm.guard {
+                        let guard_ty = self.infer_expr(guard, ast_map);
+                        let range = ast_map.expr_span(guard);
+
+                        let _ = self.unify(
+                            &Type::Con(TypeCon::Bool),
+                            &guard_ty,
+                            (range.start().to_usize(), range.end().to_usize()),
+                        );
+                    }
+
+                    let arm_ty = self.infer_expr(&arm.expr, ast_map);
+                    let range = ast_map.expr_span(&arm.expr);
+
+                    ty = self.join(
+                        &ty,
+                        &arm_ty,
+                        (range.start().to_usize(), range.end().to_usize()),
+                    );
+
+                    self.locals.end_scope();
+                }
+
+                let range = ast_map.expr_span(expr);
+
+                self.check_match(
+                    &expr_ty,
+                    arms,
+                    ast_map,
+                    (range.start().to_usize(), range.end().to_usize()),
+                );
+
+                ty
+            }
+            Expr::RecordLiteral { def, fields } => {
+                let class_ty = match self.ctx.get_type(&def.item) {
+                    Some(ty) => self.instantiate(ty),
+                    None => self.fresh_var(),
+                };
+
+                for (field, expr) in fields {
+                    let expr_ty = self.infer_expr(expr, ast_map);
+
+                    if let Type::Class {
+                        fields: def_fields, ..
+                    } = &class_ty
+                    {
+                        if let Some(field_ty) = def_fields.get(&field.item) {
+                            let range = ast_map.expr_span(expr);
+
+                            let _ = self.coerce(
+                                field_ty,
+                                &expr_ty,
+                                (range.start().to_usize(), range.end().to_usize()),
+                            );
+                        }
+                    }
+                }
+
+                class_ty
+            }
+        };
+
+        self.record_bound_checks(span);
+
+        self.type_map.expr_to_type.insert(*id, ty.clone());
+
+        ty
+    }
+
+    fn infer_call(
+        &mut self,
+        callee: &ExprId,
+        args: &[ExprId],
+        type_args: &util::Span<Vec<util::Span<hir::TypeId>>>,
+        ast_map: &FunctionAstMap,
+        span: (usize, usize),
+    ) -> Type {
+        let callee_ty = match ast_map.expr(callee) {
+            Expr::Ident(name)
+                if !type_args.item.is_empty() && self.locals.get(&name.item).is_none() =>
+            {
+                let ty = match self.ctx.get_type(&name.item) {
+                    Some(ty) => self.instantiate_with(ty, type_args, name.item),
+                    None => self.fresh_var(),
+                };
+
+                self.type_map.expr_to_type.insert(*callee, ty.clone());
+
+                ty
+            }
+            _ => self.infer_expr(callee, ast_map),
+        };
+
+        let args = args
+            .iter()
+            .map(|arg| self.infer_expr(arg, ast_map))
+            .collect::<Vec<_>>();
+
+        self.check_call(&callee_ty, args, span)
+    }
+
+    /// The type that calling a function of the type `callee` evaluates to. Each argument is
+    /// checked against its own parameter so that it can be a subclass of the parameter's class
+    fn check_call(&mut self, callee: &Type, mut args: Vec<Type>, span: (usize, usize)) -> Type {
+        let callee = match self.apply(callee) {
+            ty @ Type::Poly(_, _) => self.instantiate(ty),
+            ty => ty,
+        };
+
+        match callee {
+            Type::App(mut params) if params.len() == args.len() + 1 => {
+                let ret = params.pop().unwrap();
+
+                for (param, arg) in params.iter().zip(&args) {
+                    let _ = self.coerce(param, arg, span);
+                }
+
+                ret
+            }
+            callee => {
+                let ret = self.fresh_var();
+
+                args.push(ret.clone());
+
+                let _ = self.unify(&callee, &Type::App(args), span);
+
+                ret
+            }
+        }
+    }
+
+    /// The type of the enum that `Foo::Bar` or `Foo::Bar(expr)` creates
+    fn infer_variant(
+        &mut self,
+        def: &util::Span<NameId>,
+        variant: &util::Span<NameId>,
+        expr: Option<&ExprId>,
+        ast_map: &FunctionAstMap,
+        span: (usize, usize),
+    ) -> Type {
+        let enum_ty = match self.ctx.get_type(&def.item) {
+            Some(ty) => self.instantiate(ty),
+            None => self.fresh_var(),
+        };
+
+        let inner = match &enum_ty {
+            Type::Enum(_, _, variants) => variants
+                .get(&variant.item)
+                .and_then(|variant| variant.ty.clone()),
+            _ => None,
+        };
+
+        match (inner, expr) {
+            (Some(inner), Some(expr)) => {
+                let expr_ty = self.infer_expr(expr, ast_map);
+                let range = ast_map.expr_span(expr);
+
+                let _ = self.coerce(
+                    &inner,
+                    &expr_ty,
+                    (range.start().to_usize(), range.end().to_usize()),
+                );
+            }
+            (None, Some(expr)) => {
+                self.infer_expr(expr, ast_map);
+
+                let msg = format!(
+                    "The enum variant `{}` doesn't take a value",
+                    self.db.lookup_intern_name(variant.item)
+                );
+
+                self.reporter.error(msg, "", span);
+            }
+            (Some(_), None) | (None, None) => {}
+        }
+
+        enum_ty
+    }
+
+    /// Infer the body of a loop, `break_ty` is the type that its `break`s have to agree on
+    fn infer_loop_body(
+        &mut self,
+        label: &Option<util::Span<NameId>>,
+        break_ty: Option<Type>,
+        body: &BlockId,
+        ast_map: &FunctionAstMap,
+    ) {
+        let block = ast_map.block(body);
+
+        self.loops
+            .push((label.as_ref().map(|label| label.item), break_ty));
+        self.locals.begin_scope();
+
+        for stmt in &block.0 {
+            self.infer_statement(stmt, ast_map);
+        }
+
+        self.locals.end_scope();
+        self.loops.pop();
+    }
+
+    /// Checks that a `for` loop can iterate over the value and that it hands out `item`s.
+    /// A class is iterated by calling its `next(self)` method until it gives `Option::None`
+    fn infer_iterable(
+        &mut self,
+        iterable: &Type,
+        item: &Type,
+        option: &Type,
+        span: (usize, usize),
+    ) {
+        let next = self.db.intern_name(hir::Name::new("next"));
+
+        let applied = self.apply(iterable);
+        let instantiated = self.instantiate(applied);
+
+        match self.declared_class(instantiated, span) {
+            Type::Var(_) => {
+                let _ = self.unify(
+                    &Type::Con(TypeCon::Array {
+                        ty: Box::new(item.clone()),
+                        size: None,
+                    }),
+                    iterable,
+                    span,
+                );
+            }
+            Type::Con(TypeCon::Array { ty, .. }) => {
+                let _ = self.unify(item, &ty, span);
+            }
+            Type::Con(TypeCon::Range) => {
+                let _ = self.unify(item, &Type::Con(TypeCon::Int), span);
+            }
+            Type::Class {
+                name,
+                methods,
+                instance_methods,
+                ..
+            } => match methods.get(&next) {
+                Some(ty) if instance_methods.contains(&next) => {
+                    let ty = self.instantiate(ty.clone());
+
+                    let _ = self.unify(&ty, &Type::App(vec![option.clone()]), span);
+                }
+                _ => {
+                    self.reporter.error(
+                        format!("`{}` can't be iterated", self.db.lookup_intern_name(name)),
+                        "Add a `next(self)` method that returns an `Option`",
+                        span,
+                    );
+                }
+            },
+            ty => {
+                self.reporter.error(
+                    format!("`{}` can't be iterated", self.display(&ty)),
+                    "Only arrays, ranges and classes with a `next(self)` method can be iterated",
+                    span,
+                );
+            }
+        }
+    }
+
+    /// The local that the condition of an `if` compares with `nil`, the type it holds when it
+    /// isn't `nil` and whether it isn't `nil` when the condition is true (`!=`) or false (`==`)
+    fn narrowed_local(
+        &mut self,
+        cond: &ExprId,
+        ast_map: &FunctionAstMap,
+    ) -> Option<(NameId, Type, bool)> {
+        let mut cond = ast_map.expr(cond);
+
+        while let Expr::Paren(expr) = cond {
+            cond = ast_map.expr(expr);
+        }
+
+        let (lhs, rhs, not_nil_when_true) = match cond {
+            Expr::Binary {
+                lhs,
+                op: BinOp::NotEqual,
+                rhs,
+            } => (ast_map.expr(lhs), ast_map.expr(rhs), true),
+            Expr::Binary {
+                lhs,
+                op: BinOp::EqualEqual,
+                rhs,
+            } => (ast_map.expr(lhs), ast_map.expr(rhs), false),
+            _ => return None,
+        };
+
+        let name = match (lhs, rhs) {
+            (Expr::Ident(name), Expr::Literal(literal))
+            | (Expr::Literal(literal), Expr::Ident(name))
+                if self.db.lookup_intern_literal(*literal) == hir::Literal::Nil =>
+            {
+                name.item
+            }
+            _ => return None,
+        };
+
+        let ty = self.locals.get(&name)?.clone();
+
+        match self.apply(&ty) {
+            Type::Con(TypeCon::Optional(inner)) => Some((name, *inner, not_nil_when_true)),
+            _ => None,
+        }
+    }
+
+    /// Reports using a field or method of an optional that holds an `inner`
+    fn might_be_nil(&mut self, inner: &Type, span: (usize, usize)) {
+        self.reporter.error(
+            format!("`{}?` might be `nil`", self.display(inner)),
+            "Check that it isn't `nil` with `if x != nil` first",
+            span,
+        );
+    }
+
+    /// The `Ok` type of the `Result` that `?` is used on.
+    /// The `Err` type has to be the one of the `Result` the function returns
+    fn infer_try(
+        &mut self,
+        expr_ty: &Type,
+        expr_span: (usize, usize),
+        span: (usize, usize),
+    ) -> Type {
+        let result = self.db.intern_name(hir::Name::new("Result"));
+
+        let (tvs, inner) = match self.ctx.get_type(&result) {
+            Some(Type::Poly(tvs, inner)) => (tvs, inner),
+            _ => return self.fresh_var(),
+        };
+
+        let ok = self.fresh_var();
+        let err = self.fresh_var();
+
+        let mut mappings = HashMap::new();
+        mappings.insert(tvs[0], ok.clone());
+        mappings.insert(tvs[1], err.clone());
+
+        let expected = inner.subst(&mappings);
+
+        match self.apply(expr_ty) {
+            Type::Var(_) => {}
+            Type::Enum(name, ..) if name == result => {}
+            ty => {
+                self.reporter.error(
+                    "`?` can only be used on a `Result`",
+                    format!("Found `{}`", self.display(&ty)),
+                    expr_span,
+                );
+
+                return ok;
+            }
+        }
+
+        let _ = self.unify(&expected, expr_ty, expr_span);
+
+        let return_ty = self.return_ty.clone();
+
+        match self.apply(&return_ty) {
+            Type::Var(_) => {}
+            Type::Enum(name, ..) if name == result => {}
+            ty => {
+                self.reporter.error(
+                    "`?` can only be used in a function that returns a `Result`",
+                    format!("The function returns `{}`", self.display(&ty)),
+                    span,
+                );
+
+                return ok;
+            }
+        }
+
+        let mut mappings = HashMap::new();
+        mappings.insert(tvs[0], self.fresh_var());
+        mappings.insert(tvs[1], err);
+
+        let _ = self.unify(&return_ty, &inner.subst(&mappings), span);
+
+        ok
+    }
+
+    /// The type of a method called on a value of a type param, which can only come from
+    /// one of the traits that bound it
+    fn bound_method(
+        &mut self,
+        type_param: &TypeParamBounds,
+        method: &util::Span<NameId>,
+        type_args: &util::Span<Vec<util::Span<hir::TypeId>>>,
+    ) -> Option<Type> {
+        let found = type_param.bounds.iter().find_map(|bound| {
+            let trait_def = self.ctx.get_trait(bound)?;
+            let ty = trait_def.methods.get(&method.item)?;
+
+            Some((
+                *bound,
+                ty.clone(),
+                trait_def.instance_methods.contains(&method.item),
+            ))
+        });
+
+        match found {
+            Some((_, ty, true)) => Some(self.instantiate_with(ty, type_args, method.item)),
+            Some((bound, _, false)) => {
+                let msg = format!(
+                    "`{}::{}` doesn't take `self`",
+                    self.db.lookup_intern_name(bound),
+                    self.db.lookup_intern_name(method.item)
+                );
+
+                self.reporter.error(msg, "", method.as_reporter_span());
+
+                None
+            }
+            None => {
+                let msg = format!(
+                    "No trait bound of `{}` has the method `{}`",
+                    self.db.lookup_intern_name(type_param.name),
+                    self.db.lookup_intern_name(method.item)
+                );
+
+                self.reporter.error(msg, "", method.as_reporter_span());
+
+                None
+            }
+        }
+    }
+
+    /// A class that is only known by its fields, as in the signatures of its own methods,
+    /// gets its methods from the declaration of the class
+    fn declared_class(&mut self, class: Type, span: (usize, usize)) -> Type {
+        let name = match &class {
+            Type::Class { name, .. } => *name,
+            _ => return class,
+        };
+
+        let declared = match self.ctx.get_type(&name) {
+            Some(declared) => self.instantiate(declared),
+            None => return class,
+        };
+
+        match declared {
+            Type::Class { .. } if self.unify(&declared, &class, span).is_ok() => declared,
+            _ => class,
+        }
+    }
+
+    /// Check that the operand of an operator is one of the `expected` types
+    fn expect_operand(
+        &mut self,
+        op: &impl std::fmt::Display,
+        ty: &Type,
+        expected: &[TypeCon],
+        span: (usize, usize),
+    ) {
+        match self.apply(ty) {
+            Type::Var(_) => {}
+            Type::Con(ref con) if expected.contains(con) => {}
+            ty => {
+                let msg = format!(
+                    "Cannot use `{}` with the operator `{}`",
+                    self.display(&ty),
+                    op
+                );
+                self.reporter.error(msg, "", span);
+            }
+        }
+    }
+
+    /// The numeric types can be cast into each other, `bool` and `char` widen into `i32` or `i64`
+    /// and any primitive can be cast into a `string`
+    fn check_cast(&mut self, from: &Type, to: &Type, span: (usize, usize)) {
+        let (from, to) = (self.apply(from), self.apply(to));
+
+        let allowed = match (&from, &to) {
+            (Type::Var(_), _) | (_, Type::Var(_)) => true,
+            (Type::Con(TypeCon::Array { .. }), _) | (_, Type::Con(TypeCon::Array { .. })) => {
+                from == to
+            }
+            (Type::Con(TypeCon::Optional(_)), _) | (_, Type::Con(TypeCon::Optional(_))) => {
+                from == to
+            }
+            (Type::Con(TypeCon::Void), _) | (_, Type::Con(TypeCon::Void)) => false,
+            (Type::Con(_), Type::Con(TypeCon::Str)) => true,
+            (Type::Con(from), Type::Con(to)) => match (from, to) {
+                (from, to) if NUMBERS.contains(from) && NUMBERS.contains(to) => true,
+                (TypeCon::Bool, TypeCon::Int)
+                | (TypeCon::Bool, TypeCon::Int64)
+                | (TypeCon::Char, TypeCon::Int)
+                | (TypeCon::Char, TypeCon::Int64) => true,
+                (from, to) => from == to,
+            },
+            _ => false,
+        };
+
+        if !allowed {
+            let msg = format!(
+                "Cannot cast `{}` to `{}`",
+                self.display(&from),
+                self.display(&to)
+            );
+
+            self.reporter.error(
+                msg,
+                "Casts are allowed between numbers, from `bool` or `char` to an integer and from any primitive to `string`",
+                span,
+            );
+        }
+    }
+}
+
+/// Whether evaluating the expression always returns, breaks or continues
+fn diverges(id: &ExprId, ast_map: &FunctionAstMap) -> bool {
+    match ast_map.expr(id) {
+        Expr::Return(_) | Expr::Break { .. } | Expr::Continue { .. } => true,
+        Expr::Paren(expr) => diverges(expr, ast_map),
+        Expr::Block(block) => ast_map
+            .block(block)
+            .0
+            .iter()
+            .any(|stmt| match ast_map.stmt(stmt) {
+                Stmt::Expr(expr) => diverges(expr, ast_map),
+                Stmt::Let { initializer, .. } => initializer
+                    .as_ref()
+                    .map_or(false, |initializer| diverges(initializer, ast_map)),
+            }),
+        Expr::If {
+            then_branch,
+            else_branch: Some(else_branch),
+            ..
+        } => diverges(then_branch, ast_map) && diverges(else_branch, ast_map),
+        _ => false,
+    }
+}
@@ -1,13 +1,842 @@
-use crate::HirDatabase;
-use errors::{FileId, WithError};
+use super::{StackedMap, Type, TypeCon, TypeDisplay, TypeVar, Variant};
+use crate::{
+    hir::{self, ExprId, Function, FunctionAstMap, NameId, PatId, StmtId},
+    resolver::TypeKind,
+    util, Ctx, HirDatabase,
+};
+use errors::{Diagnostic, FileId, Reporter, Severity, WithError};
+use std::{collections::HashMap, sync::Arc};
 
-pub fn infer_query(db: &impl HirDatabase, file: FileId) -> WithError<()> {
-    let program = db.lower(file)?;
-    let _ = db.resolve_source_file(file)?;
+/// The types inferred for a single function
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TypeMap {
+    pub(crate) expr_to_type: HashMap<ExprId, Type>,
+    pub(crate) pat_to_type: HashMap<PatId, Type>,
+}
+
+/// The types of every function and method within a file keyed by the class
+/// that the function is a method of and the name of the function
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InferResult {
+    pub(crate) functions: HashMap<(Option<NameId>, NameId), TypeMap>,
+    /// The warnings found while checking, such as unreachable match arms
+    pub(crate) warnings: Vec<Diagnostic<FileId>>,
+    /// The type errors found while checking. The types of the expressions with an error
+    /// might be left as type vars
+    pub(crate) errors: Vec<Diagnostic<FileId>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct InferDataCollector<DB> {
+    pub(crate) db: DB,
+    pub(crate) ctx: Ctx,
+    pub(crate) reporter: Reporter,
+    /// The substitution built up by the unifier for the current function
+    pub(crate) subst: HashMap<TypeVar, Type>,
+    /// The types of the local variables that are in scope
+    pub(crate) locals: StackedMap<NameId, Type>,
+    pub(crate) type_map: TypeMap,
+    pub(crate) return_ty: Type,
+    /// The labels of the loops around the current expression and the type of their `break`
+    /// values, innermost last. Only a `loop` has a type for its breaks
+    pub(crate) loops: Vec<(Option<NameId>, Option<Type>)>,
+    /// The types given to bounded type params that were instantiated by the current expression
+    pub(crate) instantiated_bounds: Vec<(Type, NameId)>,
+    /// The traits that the types of instantiated type params have to implement, which are
+    /// checked once the whole function has been unified
+    pub(crate) bound_checks: Vec<(Type, NameId, (usize, usize))>,
+}
+
+impl TypeMap {
+    pub fn expr(&self, id: &ExprId) -> Option<&Type> {
+        self.expr_to_type.get(id)
+    }
+
+    pub fn pat(&self, id: &PatId) -> Option<&Type> {
+        self.pat_to_type.get(id)
+    }
+}
+
+impl InferResult {
+    /// The types of a function, `class` is the class that a method belongs to
+    pub fn function(&self, class: Option<NameId>, name: NameId) -> Option<&TypeMap> {
+        self.functions.get(&(class, name))
+    }
+
+    pub fn warnings(&self) -> &[Diagnostic<FileId>] {
+        &self.warnings
+    }
+}
+
+impl<'a, DB> InferDataCollector<&'a DB>
+where
+    DB: HirDatabase,
+{
+    pub(crate) fn finish(self) -> Reporter {
+        self.reporter
+    }
+
+    /// Shows a type for an error message with its type params by name
+    pub(crate) fn display<'b>(&'b self, ty: &'b Type) -> TypeDisplay<'b, DB> {
+        ty.display(self.db).with_type_params(&self.ctx)
+    }
+
+    /// Replace all the type vars that have been solved with their types
+    pub(crate) fn apply(&self, ty: &Type) -> Type {
+        match ty {
+            Type::Var(tv) => match self.subst.get(tv) {
+                Some(ty) => self.apply(ty),
+                None => Type::Var(*tv),
+            },
+            Type::App(types) => Type::App(types.iter().map(|ty| self.apply(ty)).collect()),
+            Type::Tuple(types) => Type::Tuple(types.iter().map(|ty| self.apply(ty)).collect()),
+            Type::Con(TypeCon::Array { ty, size }) => Type::Con(TypeCon::Array {
+                ty: Box::new(self.apply(ty)),
+                size: *size,
+            }),
+            Type::Con(TypeCon::Optional(ty)) => {
+                Type::Con(TypeCon::Optional(Box::new(self.apply(ty))))
+            }
+            Type::Con(con) => Type::Con(con.clone()),
+            Type::Poly(tvs, ty) => Type::Poly(tvs.clone(), Box::new(self.apply(ty))),
+            Type::Enum(name, type_args, variants) => Type::Enum(
+                *name,
+                type_args.iter().map(|ty| self.apply(ty)).collect(),
+                variants
+                    .iter()
+                    .map(|(name, variant)| {
+                        (
+                            *name,
+                            Variant {
+                                tag: variant.tag,
+                                ty: variant.ty.as_ref().map(|ty| self.apply(ty)),
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
+            Type::Class {
+                name,
+                type_args,
+                fields,
+                methods,
+                instance_methods,
+                superclass,
+            } => Type::Class {
+                name: *name,
+                type_args: type_args.iter().map(|ty| self.apply(ty)).collect(),
+                fields: fields
+                    .iter()
+                    .map(|(name, ty)| (*name, self.apply(ty)))
+                    .collect(),
+                methods: methods
+                    .iter()
+                    .map(|(name, ty)| (*name, self.apply(ty)))
+                    .collect(),
+                instance_methods: instance_methods.clone(),
+                superclass: superclass
+                    .as_ref()
+                    .map(|superclass| Box::new(self.apply(superclass))),
+            },
+        }
+    }
+
+    /// Replaces the type vars of a poly type with fresh type vars
+    pub(crate) fn instantiate(&mut self, ty: Type) -> Type {
+        match ty {
+            Type::Poly(tvs, inner) => {
+                let mut mappings = HashMap::new();
+
+                for tv in tvs {
+                    let fresh = self.ctx.type_var();
+
+                    if let Some(type_param) = self.ctx.get_type_param(&tv) {
+                        for bound in &type_param.bounds {
+                            self.instantiated_bounds.push((Type::Var(fresh), *bound));
+                        }
+                    }
+
+                    mappings.insert(tv, Type::Var(fresh));
+                }
+
+                inner.subst(&mappings)
+            }
+            ty => ty,
+        }
+    }
+
+    /// Replaces the type vars of a poly type with the type args given to it, such as in
+    /// `id::<i32>(1)`. Without type args the type vars are replaced with fresh type vars
+    pub(crate) fn instantiate_with(
+        &mut self,
+        ty: Type,
+        type_args: &util::Span<Vec<util::Span<hir::TypeId>>>,
+        name: NameId,
+    ) -> Type {
+        let (tvs, inner) = match ty {
+            Type::Poly(tvs, inner) if !type_args.item.is_empty() => (tvs, inner),
+            ty => return self.instantiate(ty),
+        };
+
+        if tvs.is_empty() {
+            let msg = format!(
+                "`{}` doesn't take type arguments",
+                self.db.lookup_intern_name(name)
+            );
+
+            self.reporter.error(msg, "", type_args.as_reporter_span());
+
+            return *inner;
+        }
+
+        if tvs.len() != type_args.item.len() {
+            self.reporter.error(
+                format!(
+                    "Wrong number of type arguments for `{}`",
+                    self.db.lookup_intern_name(name)
+                ),
+                format!("Expected {} but found {}", tvs.len(), type_args.item.len()),
+                type_args.as_reporter_span(),
+            );
+
+            return self.instantiate(Type::Poly(tvs, inner));
+        }
+
+        let mut mappings = HashMap::new();
+
+        for (tv, arg) in tvs.into_iter().zip(&type_args.item) {
+            let arg = self.infer_type(arg);
+
+            if let Some(type_param) = self.ctx.get_type_param(&tv) {
+                for bound in &type_param.bounds {
+                    self.instantiated_bounds.push((arg.clone(), *bound));
+                }
+            }
+
+            mappings.insert(tv, arg);
+        }
+
+        inner.subst(&mappings)
+    }
+
+    pub(crate) fn fresh_var(&mut self) -> Type {
+        Type::Var(self.ctx.type_var())
+    }
+
+    pub(crate) fn infer_function(
+        &mut self,
+        function: &Function,
+        signature: Type,
+        class_type_params: &[(util::Span<NameId>, TypeVar)],
+        class_ty: Option<&Type>,
+    ) -> TypeMap {
+        self.ctx.begin_scope();
+        self.locals.begin_scope();
+
+        for (name, tv) in class_type_params {
+            self.ctx
+                .insert_type(name.item, Type::Var(*tv), TypeKind::Type);
+        }
+
+        let (poly_tvs, signature) = match signature {
+            Type::Poly(tvs, inner) => (tvs, *inner),
+            ty => (Vec::new(), ty),
+        };
+
+        for (type_param, tv) in function.type_params.iter().zip(poly_tvs) {
+            let type_param = function.ast_map.type_param(&type_param.item);
+
+            self.ctx
+                .insert_type(type_param.name.item, Type::Var(tv), TypeKind::Type);
+        }
+
+        let mut signature = match signature {
+            Type::App(signature) => signature,
+            _ => vec![Type::Con(TypeCon::Void)],
+        };
+
+        self.return_ty = signature.pop().unwrap_or(Type::Con(TypeCon::Void));
+
+        if let (Some(self_param), Some(class_ty)) = (&function.self_param, class_ty) {
+            self.locals.insert(self_param.item, class_ty.clone());
+        }
+
+        for (param, ty) in function.params.iter().zip(signature) {
+            let param = function.ast_map.param(&param.item);
+
+            self.infer_pattern(&param.pat, &ty, &function.ast_map);
+        }
+
+        if let Some(body) = &function.body {
+            for stmt in body {
+                self.infer_statement(stmt, &function.ast_map);
+            }
+        }
+
+        self.record_bound_checks(function.name.as_reporter_span());
+        self.check_bounds();
+
+        self.locals.end_scope();
+        self.ctx.end_scope();
+
+        let type_map = std::mem::replace(&mut self.type_map, TypeMap::default());
+
+        let type_map = TypeMap {
+            expr_to_type: type_map
+                .expr_to_type
+                .into_iter()
+                .map(|(id, ty)| (id, self.apply(&ty)))
+                .collect(),
+            pat_to_type: type_map
+                .pat_to_type
+                .into_iter()
+                .map(|(id, ty)| (id, self.apply(&ty)))
+                .collect(),
+        };
+
+        self.subst.clear();
+
+        type_map
+    }
+
+    /// The bounds of the type params instantiated since the last call are checked at `span`
+    pub(crate) fn record_bound_checks(&mut self, span: (usize, usize)) {
+        for (ty, bound) in self.instantiated_bounds.drain(..) {
+            self.bound_checks.push((ty, bound, span));
+        }
+    }
+
+    fn check_bounds(&mut self) {
+        // A generic class can be instantiated several times for the same value
+        let mut reported = Vec::new();
+
+        for (ty, bound, span) in std::mem::take(&mut self.bound_checks) {
+            let ty = self.apply(&ty);
+
+            // The type is still unknown
+            if let Type::Var(tv) = ty {
+                if self.ctx.get_type_param(&tv).is_none() {
+                    continue;
+                }
+            }
+
+            if !self.ctx.implements(&ty, &bound) && !reported.contains(&(ty.clone(), bound)) {
+                reported.push((ty.clone(), bound));
+
+                let msg = format!(
+                    "`{}` doesn't implement `{}`",
+                    self.display(&ty),
+                    self.db.lookup_intern_name(bound)
+                );
+
+                self.reporter.error(msg, "", span);
+            }
+        }
+    }
+
+    pub(crate) fn infer_statement(&mut self, stmt: &StmtId, ast_map: &FunctionAstMap) {
+        let stmt = ast_map.stmt(stmt);
+
+        match stmt {
+            hir::Stmt::Let {
+                pat,
+                initializer,
+                ascribed_type,
+            } => {
+                let mut ty = if let Some(expr) = initializer {
+                    self.infer_expr(expr, ast_map)
+                } else {
+                    self.fresh_var()
+                };
+
+                if let Some(ascribed_type) = ascribed_type {
+                    let expected = self.infer_type(ascribed_type);
+
+                    let span = if let Some(expr) = initializer {
+                        let range = ast_map.expr_span(expr);
+                        (range.start().to_usize(), range.end().to_usize())
+                    } else {
+                        pat.as_reporter_span()
+                    };
+
+                    let _ = self.coerce(&expected, &ty, span);
+
+                    ty = expected;
+                }
+
+                self.infer_pattern(pat, &ty, ast_map);
+            }
+            hir::Stmt::Expr(expr) => {
+                self.infer_expr(expr, ast_map);
+            }
+        }
+    }
+
+    pub(crate) fn infer_pattern(
+        &mut self,
+        pat_id: &util::Span<PatId>,
+        ty: &Type,
+        ast_map: &FunctionAstMap,
+    ) {
+        let pat = ast_map.pat(&pat_id.item);
+
+        match pat {
+            hir::Pattern::Bind { name } => self.locals.insert(name.item, ty.clone()),
+            hir::Pattern::Placeholder | hir::Pattern::Missing | hir::Pattern::Rest => {}
+            hir::Pattern::Tuple(patterns) => {
+                if let Some(rest) = patterns
+                    .iter()
+                    .position(|pat| ast_map.pat(&pat.item) == &hir::Pattern::Rest)
+                {
+                    self.infer_rest_pattern(pat_id, patterns, rest, ty, ast_map);
+                    self.type_map.pat_to_type.insert(pat_id.item, ty.clone());
+                    return;
+                }
+
+                let types = patterns
+                    .iter()
+                    .map(|_| self.fresh_var())
+                    .collect::<Vec<_>>();
+
+                let _ = self.unify(ty, &Type::Tuple(types.clone()), pat_id.as_reporter_span());
+
+                for (pat, ty) in patterns.iter().zip(types.iter()) {
+                    self.infer_pattern(pat, ty, ast_map)
+                }
+            }
+            hir::Pattern::Literal(literal) => {
+                let literal_ty = self.infer_literal(*literal);
+
+                let _ = self.unify(ty, &literal_ty, pat_id.as_reporter_span());
+            }
+            hir::Pattern::Variant { def, variant, pat } => {
+                let enum_ty = match self.ctx.get_type(&def.item) {
+                    Some(ty) => self.instantiate(ty),
+                    None => self.fresh_var(),
+                };
+
+                let _ = self.unify(ty, &enum_ty, pat_id.as_reporter_span());
+
+                let inner = match &enum_ty {
+                    Type::Enum(_, _, variants) => variants
+                        .get(&variant.item)
+                        .and_then(|variant| variant.ty.clone()),
+                    _ => None,
+                };
+
+                match (inner, pat) {
+                    (Some(inner), Some(pat)) => self.infer_pattern(pat, &inner, ast_map),
+                    (None, Some(pat)) => {
+                        let fresh = self.fresh_var();
+                        self.infer_pattern(pat, &fresh, ast_map);
+
+                        let msg = format!(
+                            "The enum variant `{}` doesn't take a value",
+                            self.db.lookup_intern_name(variant.item)
+                        );
+
+                        self.reporter.error(msg, "", pat_id.as_reporter_span());
+                    }
+                    (Some(_), None) | (None, None) => {}
+                }
+            }
+            hir::Pattern::Record { def, fields, .. } => {
+                let class_ty = match self.ctx.get_type(&def.item) {
+                    Some(ty) => self.instantiate(ty),
+                    None => self.fresh_var(),
+                };
+
+                let _ = self.unify(ty, &class_ty, pat_id.as_reporter_span());
+
+                for (field, pat) in fields {
+                    let field_ty = match &class_ty {
+                        Type::Class {
+                            fields: def_fields, ..
+                        } => def_fields.get(&field.item).cloned(),
+                        _ => None,
+                    };
+
+                    let field_ty = field_ty.unwrap_or_else(|| self.fresh_var());
+
+                    self.infer_pattern(pat, &field_ty, ast_map);
+                }
+            }
+        }
+
+        self.record_bound_checks(pat_id.as_reporter_span());
+
+        self.type_map.pat_to_type.insert(pat_id.item, ty.clone());
+    }
+
+    /// A tuple pattern with `..` matches tuples of any length that fit the other patterns
+    /// so the length comes from the type of the tuple
+    fn infer_rest_pattern(
+        &mut self,
+        pat_id: &util::Span<PatId>,
+        patterns: &[util::Span<PatId>],
+        rest: usize,
+        ty: &Type,
+        ast_map: &FunctionAstMap,
+    ) {
+        let (before, after) = (&patterns[..rest], &patterns[rest + 1..]);
+
+        match self.apply(ty) {
+            Type::Tuple(types) if types.len() >= before.len() + after.len() => {
+                for (pat, ty) in before.iter().zip(types.iter()) {
+                    self.infer_pattern(pat, ty, ast_map)
+                }
+
+                for (pat, ty) in after.iter().rev().zip(types.iter().rev()) {
+                    self.infer_pattern(pat, ty, ast_map)
+                }
+            }
+            ty => {
+                let msg = format!(
+                    "Expected a tuple with at least {} elements instead found `{}`",
+                    before.len() + after.len(),
+                    self.display(&ty)
+                );
+
+                self.reporter.error(msg, "", pat_id.as_reporter_span());
+            }
+        }
+    }
+
+    pub(crate) fn infer_literal(&mut self, literal: hir::LiteralId) -> Type {
+        match self.db.lookup_intern_literal(literal) {
+            hir::Literal::String(_) => Type::Con(TypeCon::Str),
+            hir::Literal::Char(_) => Type::Con(TypeCon::Char),
+            hir::Literal::Nil => Type::Con(TypeCon::Optional(Box::new(self.fresh_var()))),
+            hir::Literal::True | hir::Literal::False => Type::Con(TypeCon::Bool),
+            hir::Literal::Int(_) => Type::Con(TypeCon::Int),
+            hir::Literal::Int64(_) => Type::Con(TypeCon::Int64),
+            hir::Literal::Float(_) => Type::Con(TypeCon::Float),
+            hir::Literal::Float64(_) => Type::Con(TypeCon::Float64),
+        }
+    }
+
+    /// Convert a hir type into an inference type.
+    /// The resolver has already checked that the type exists
+    pub(crate) fn infer_type(&mut self, id: &util::Span<hir::TypeId>) -> Type {
+        let ty = self.db.lookup_intern_type(id.item);
+
+        match ty {
+            hir::Type::ParenType(types) => {
+                Type::Tuple(types.iter().map(|ty| self.infer_type(ty)).collect())
+            }
+            hir::Type::ArrayType { ty, size } => Type::Con(TypeCon::Array {
+                ty: Box::new(self.infer_type(&ty)),
+                size,
+            }),
+            hir::Type::Optional(ty) => Type::Con(TypeCon::Optional(Box::new(self.infer_type(&ty)))),
+            hir::Type::FnType { params, ret } => {
+                let mut signature = params
+                    .iter()
+                    .map(|ty| self.infer_type(ty))
+                    .collect::<Vec<_>>();
 
-    for _ in &program.functions {
-        //
+                if let Some(ret) = &ret {
+                    signature.push(self.infer_type(ret))
+                } else {
+                    signature.push(Type::Con(TypeCon::Void))
+                }
+
+                Type::App(signature)
+            }
+            hir::Type::Poly { name, type_args } => match self.ctx.get_type(&name) {
+                // The resolver has checked that there is a type arg for each type param
+                Some(Type::Poly(tvs, inner)) => {
+                    let mut mappings = HashMap::new();
+
+                    for (tv, arg) in tvs.iter().zip(type_args.iter()) {
+                        let arg = self.infer_type(arg);
+                        mappings.insert(*tv, arg);
+                    }
+
+                    inner.subst(&mappings)
+                }
+                Some(ty) => ty,
+                None => self.fresh_var(),
+            },
+            hir::Type::Ident(name) => match self.ctx.get_type(&name) {
+                Some(ty) => self.instantiate(ty),
+                None => self.fresh_var(),
+            },
+            hir::Type::Missing => self.fresh_var(),
+        }
+    }
+}
+
+pub fn infer_query(db: &impl HirDatabase, file: FileId) -> WithError<Arc<InferResult>> {
+    let result = db.infer_partial(file)?;
+
+    if result.errors.is_empty() {
+        Ok(result)
+    } else {
+        Err(result
+            .errors
+            .iter()
+            .chain(&result.warnings)
+            .cloned()
+            .collect())
+    }
+}
+
+/// Infers the types of a file even when some of its functions have type errors, so that the
+/// types can still be shown in an editor. Only a file that fails to resolve has no types
+pub fn infer_partial_query(db: &impl HirDatabase, file: FileId) -> WithError<Arc<InferResult>> {
+    let program = db.lower(file);
+    let resolver = db.resolve_source_file(file)?;
+
+    let reporter = Reporter::new(file);
+
+    let mut collector = InferDataCollector {
+        db,
+        ctx: resolver.ctx.clone(),
+        reporter,
+        subst: HashMap::new(),
+        locals: StackedMap::new(),
+        type_map: TypeMap::default(),
+        return_ty: Type::Con(TypeCon::Void),
+        loops: Vec::new(),
+        instantiated_bounds: Vec::new(),
+        bound_checks: Vec::new(),
+    };
+
+    let mut result = InferResult::default();
+
+    for function in &program.functions {
+        let signature = match collector.ctx.get_type(&function.name.item) {
+            Some(signature) => signature,
+            None => continue,
+        };
+
+        let type_map = collector.infer_function(function, signature, &[], None);
+
+        result
+            .functions
+            .insert((None, function.name.item), type_map);
+    }
+
+    for class in &program.classes {
+        let (class_tvs, class_ty) = match collector.ctx.get_type(&class.name.item) {
+            Some(Type::Poly(tvs, inner)) => (tvs, *inner),
+            _ => continue,
+        };
+
+        let methods = match &class_ty {
+            Type::Class { methods, .. } => methods.clone(),
+            _ => continue,
+        };
+
+        let class_type_params = class.type_param_names().zip(class_tvs).collect::<Vec<_>>();
+
+        for method in &class.methods {
+            let signature = match methods.get(&method.name.item) {
+                Some(signature) => signature.clone(),
+                None => continue,
+            };
+
+            // `self` has the class type with the class's own type params
+            let type_map =
+                collector.infer_function(method, signature, &class_type_params, Some(&class_ty));
+
+            result
+                .functions
+                .insert((Some(class.name.item), method.name.item), type_map);
+        }
+    }
+
+    for impl_def in &program.impls {
+        let (class, class_tvs, class_ty) = match db.lookup_intern_type(impl_def.target.item) {
+            hir::Type::Ident(name) => match collector.ctx.get_type(&name) {
+                Some(Type::Poly(tvs, inner)) => (name, tvs, *inner),
+                _ => continue,
+            },
+            _ => continue,
+        };
+
+        let methods = match &class_ty {
+            Type::Class { methods, .. } => methods.clone(),
+            _ => continue,
+        };
+
+        // The methods can name the type params of the class like the ones in its body.
+        // An imported class has no definition here so its type params can't be named
+        let class_type_params = program
+            .classes
+            .iter()
+            .find(|class_def| class_def.name.item == class)
+            .map_or_else(Vec::new, |class_def| {
+                class_def.type_param_names().zip(class_tvs).collect()
+            });
+
+        for method in &impl_def.methods {
+            let signature = match methods.get(&method.name.item) {
+                Some(signature) => signature.clone(),
+                None => continue,
+            };
+
+            let type_map =
+                collector.infer_function(method, signature, &class_type_params, Some(&class_ty));
+
+            result
+                .functions
+                .insert((Some(class), method.name.item), type_map);
+        }
     }
 
-    Ok(())
+    let (errors, warnings) = collector
+        .finish()
+        .finish()
+        .into_iter()
+        .partition(|diagnostic| diagnostic.severity >= Severity::Error);
+
+    result.errors = errors;
+    result.warnings = warnings;
+
+    Ok(Arc::new(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::create_infer_test;
+
+    create_infer_test!(basic_function);
+
+    create_infer_test!(generic_function);
+
+    create_infer_test!(class_method);
+
+    create_infer_test!(let_mismatch, is_err);
+
+    create_infer_test!(return_mismatch, is_err);
+
+    create_infer_test!(call_arg_mismatch, is_err);
+
+    create_infer_test!(if_cond_not_bool, is_err);
+
+    create_infer_test!(infinite_type, is_err);
+
+    create_infer_test!(tuple_mismatch, is_err);
+
+    create_infer_test!(closure_call);
+
+    create_infer_test!(closure_arg_mismatch, is_err);
+
+    create_infer_test!(match_exhaustive);
+
+    create_infer_test!(match_non_exhaustive, is_err);
+
+    create_infer_test!(match_unreachable_arm);
+
+    create_infer_test!(match_variant_patterns);
+
+    create_infer_test!(match_variant_non_exhaustive, is_err);
+
+    create_infer_test!(match_guard_non_exhaustive, is_err);
+
+    create_infer_test!(or_pattern_mismatch, is_err);
+
+    create_infer_test!(range_operators);
+
+    create_infer_test!(assign_range_element, is_err);
+
+    create_infer_test!(bitwise_operand_mismatch, is_err);
+
+    create_infer_test!(char_mismatch, is_err);
+
+    create_infer_test!(match_char_non_exhaustive, is_err);
+
+    create_infer_test!(sized_arrays);
+
+    create_infer_test!(array_size_mismatch, is_err);
+
+    create_infer_test!(numeric_casts);
+
+    create_infer_test!(mixed_integer_widths, is_err);
+
+    create_infer_test!(cast_class_to_int, is_err);
+
+    create_infer_test!(cast_int_to_class, is_err);
+
+    create_infer_test!(do_while_cond_mismatch, is_err);
+
+    create_infer_test!(loop_break_value);
+
+    create_infer_test!(loop_break_mismatch, is_err);
+
+    create_infer_test!(for_in_patterns);
+
+    create_infer_test!(for_in_non_array, is_err);
+
+    create_infer_test!(iterate_without_next, is_err);
+
+    create_infer_test!(iterator_next_not_option, is_err);
+
+    create_infer_test!(subclass_as_superclass);
+
+    create_infer_test!(superclass_as_subclass, is_err);
+
+    create_infer_test!(join_subclasses);
+
+    create_infer_test!(join_unrelated_classes, is_err);
+
+    create_infer_test!(array_of_subclass_as_superclass, is_err);
+
+    create_infer_test!(instance_call_of_static_method, is_err);
+
+    create_infer_test!(method_arg_mismatch, is_err);
+
+    create_infer_test!(unknown_method, is_err);
+
+    create_infer_test!(field_chain);
+
+    create_infer_test!(unknown_field, is_err);
+
+    create_infer_test!(unknown_field_of_call, is_err);
+
+    create_infer_test!(bounded_generic_call);
+
+    create_infer_test!(bound_not_satisfied, is_err);
+
+    create_infer_test!(method_not_in_bound, is_err);
+
+    create_infer_test!(explicit_type_args);
+
+    create_infer_test!(call_type_arg_count, is_err);
+
+    create_infer_test!(call_type_args_on_non_generic, is_err);
+
+    create_infer_test!(call_type_arg_mismatch, is_err);
+
+    create_infer_test!(type_arg_substituted, is_err);
+
+    create_infer_test!(optional_narrowing);
+
+    create_infer_test!(nil_not_optional, is_err);
+
+    create_infer_test!(optional_method_call, is_err);
+
+    create_infer_test!(optional_field, is_err);
+
+    create_infer_test!(optional_narrowed_after_return);
+
+    create_infer_test!(optional_else_narrowing);
+
+    create_infer_test!(optional_nil_branch_falls_through, is_err);
+
+    create_infer_test!(cast_optional_to_string, is_err);
+
+    create_infer_test!(fn_type_optional_param, is_err);
+
+    create_infer_test!(try_operator);
+
+    create_infer_test!(try_on_non_result, is_err);
+
+    create_infer_test!(try_outside_result, is_err);
+
+    create_infer_test!(try_error_mismatch, is_err);
+
+    create_infer_test!(impl_generic_class);
 }
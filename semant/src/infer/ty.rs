@@ -1,8 +1,12 @@
-use crate::hir::NameId;
-use std::collections::HashMap;
+use super::Ctx;
+use crate::{hir::NameId, HirDatabase};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
 /// A type var represent a variable that could be a type
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TypeVar(pub(crate) u32);
 /// A unique identifier that is used to distinguish to types with the exact some fields
 /// i.e struct Foo {} && struct Bar {} we treat them differently
@@ -13,10 +17,20 @@ pub struct Unique(pub(crate) u32);
 pub enum TypeCon {
     Bool,
     Float,
+    Float64,
     Int,
+    Int64,
+    Char,
     Str,
     Void,
-    Array { ty: Box<Type>, size: Option<usize> },
+    Array {
+        ty: Box<Type>,
+        size: Option<usize>,
+    },
+    /// `start..end`, its ints are produced as it is iterated
+    Range,
+    /// `T?`, either a `T` or `nil`
+    Optional(Box<Type>),
 }
 
 /// All of of our base types
@@ -32,10 +46,18 @@ pub enum Type {
     Poly(Vec<TypeVar>, Box<Type>),
     Var(TypeVar),
     Con(TypeCon),
-    Enum(HashMap<NameId, Variant>),
+    /// The name of the enum, the types it was given for its type params and its variants
+    Enum(NameId, Vec<Type>, HashMap<NameId, Variant>),
+    /// The fields and methods include the ones inherited from the superclass
     Class {
+        name: NameId,
+        /// The types given for the type params of the class
+        type_args: Vec<Type>,
         fields: HashMap<NameId, Type>,
         methods: HashMap<NameId, Type>,
+        /// The methods that take `self`, the others are static methods
+        instance_methods: HashSet<NameId>,
+        superclass: Option<Box<Type>>,
     },
 }
 
@@ -53,8 +75,261 @@ pub struct Variant {
     pub ty: Option<Type>,
 }
 
+impl Type {
+    /// The type a poly type is generic over, such as the enum of `Option<T>`
+    pub(crate) fn unwrap_poly(self) -> Type {
+        match self {
+            Type::Poly(_, ty) => *ty,
+            ty => ty,
+        }
+    }
+
+    /// Replaces the type vars found in `mappings` with their corresponding types
+    pub(crate) fn subst(&self, mappings: &HashMap<TypeVar, Type>) -> Type {
+        match self {
+            Type::App(types) => Type::App(types.iter().map(|ty| ty.subst(mappings)).collect()),
+            Type::Tuple(types) => Type::Tuple(types.iter().map(|ty| ty.subst(mappings)).collect()),
+            Type::Poly(tvs, ty) => {
+                // type vars bound by the poly shadow the outer mappings
+                let mut mappings = mappings.clone();
+
+                for tv in tvs {
+                    mappings.remove(tv);
+                }
+
+                Type::Poly(tvs.clone(), Box::new(ty.subst(&mappings)))
+            }
+            Type::Var(tv) => mappings.get(tv).cloned().unwrap_or(Type::Var(*tv)),
+            Type::Con(TypeCon::Array { ty, size }) => Type::Con(TypeCon::Array {
+                ty: Box::new(ty.subst(mappings)),
+                size: *size,
+            }),
+            Type::Con(TypeCon::Optional(ty)) => {
+                Type::Con(TypeCon::Optional(Box::new(ty.subst(mappings))))
+            }
+            Type::Con(con) => Type::Con(con.clone()),
+            Type::Enum(name, type_args, variants) => Type::Enum(
+                *name,
+                type_args.iter().map(|ty| ty.subst(mappings)).collect(),
+                variants
+                    .iter()
+                    .map(|(name, variant)| {
+                        (
+                            *name,
+                            Variant {
+                                tag: variant.tag,
+                                ty: variant.ty.as_ref().map(|ty| ty.subst(mappings)),
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
+            Type::Class {
+                name,
+                type_args,
+                fields,
+                methods,
+                instance_methods,
+                superclass,
+            } => Type::Class {
+                name: *name,
+                type_args: type_args.iter().map(|ty| ty.subst(mappings)).collect(),
+                fields: fields
+                    .iter()
+                    .map(|(name, ty)| (*name, ty.subst(mappings)))
+                    .collect(),
+                methods: methods
+                    .iter()
+                    .map(|(name, ty)| (*name, ty.subst(mappings)))
+                    .collect(),
+                instance_methods: instance_methods.clone(),
+                superclass: superclass
+                    .as_ref()
+                    .map(|superclass| Box::new(superclass.subst(mappings))),
+            },
+        }
+    }
+}
+
+impl fmt::Display for TypeVar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'t{}", self.0)
+    }
+}
+
+/// Helper struct to print a type with the names looked up from the database
+pub struct TypeDisplay<'a, DB> {
+    ty: &'a Type,
+    db: &'a DB,
+    /// Used to show the type vars of type params by the name of the param
+    ctx: Option<&'a Ctx>,
+}
+
+impl Type {
+    pub fn display<'a, DB: HirDatabase>(&'a self, db: &'a DB) -> TypeDisplay<'a, DB> {
+        TypeDisplay {
+            ty: self,
+            db,
+            ctx: None,
+        }
+    }
+}
+
+impl<'a, DB> TypeDisplay<'a, DB>
+where
+    DB: HirDatabase,
+{
+    /// Shows the type vars of the type params known to `ctx` by the name of the param
+    pub(crate) fn with_type_params(self, ctx: &'a Ctx) -> Self {
+        Self {
+            ctx: Some(ctx),
+            ..self
+        }
+    }
+
+    fn of(&self, ty: &'a Type) -> Self {
+        Self {
+            ty,
+            db: self.db,
+            ctx: self.ctx,
+        }
+    }
+
+    fn write_list(&self, f: &mut fmt::Formatter, types: &'a [Type]) -> fmt::Result {
+        for (i, ty) in types.iter().enumerate() {
+            if i + 1 == types.len() {
+                write!(f, "{}", self.of(ty))?;
+            } else {
+                write!(f, "{},", self.of(ty))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The name of an enum or a class followed by its type args
+    fn write_named(
+        &self,
+        f: &mut fmt::Formatter,
+        name: NameId,
+        type_args: &'a [Type],
+    ) -> fmt::Result {
+        write!(f, "{}", self.db.lookup_intern_name(name))?;
+
+        if !type_args.is_empty() {
+            write!(f, "<")?;
+            self.write_list(f, type_args)?;
+            write!(f, ">")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, DB> fmt::Display for TypeDisplay<'a, DB>
+where
+    DB: HirDatabase,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.ty {
+            Type::App(signature) => {
+                write!(f, "fn(")?;
+
+                if let Some((ret, params)) = signature.split_last() {
+                    self.write_list(f, params)?;
+                    write!(f, ") -> {}", self.of(ret))
+                } else {
+                    write!(f, ")")
+                }
+            }
+            Type::Tuple(types) => {
+                write!(f, "(")?;
+                self.write_list(f, types)?;
+                write!(f, ")")
+            }
+            Type::Poly(_, ty) => write!(f, "{}", self.of(ty)),
+            Type::Var(tv) => match self.ctx.and_then(|ctx| ctx.get_type_param(tv)) {
+                Some(type_param) => write!(f, "{}", self.db.lookup_intern_name(type_param.name)),
+                None => write!(f, "{}", tv),
+            },
+            Type::Con(con) => match con {
+                TypeCon::Bool => write!(f, "bool"),
+                TypeCon::Float => write!(f, "f32"),
+                TypeCon::Float64 => write!(f, "f64"),
+                TypeCon::Int => write!(f, "i32"),
+                TypeCon::Int64 => write!(f, "i64"),
+                TypeCon::Char => write!(f, "char"),
+                TypeCon::Str => write!(f, "string"),
+                TypeCon::Void => write!(f, "void"),
+                TypeCon::Range => write!(f, "range"),
+                TypeCon::Array { ty, size } => {
+                    if let Some(size) = size {
+                        write!(f, "[{};{}]", self.of(ty), size)
+                    } else {
+                        write!(f, "[{}]", self.of(ty))
+                    }
+                }
+                TypeCon::Optional(ty) => write!(f, "{}?", self.of(ty)),
+            },
+            Type::Enum(name, type_args, _) => self.write_named(f, *name, type_args),
+            Type::Class {
+                name, type_args, ..
+            } => self.write_named(f, *name, type_args),
+        }
+    }
+}
+
 impl From<u32> for TypeVar {
     fn from(i: u32) -> Self {
         Self(i)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Type, TypeCon, TypeVar};
+    use crate::{db::InternDatabase, hir::Name, resolver::tests::MockDatabaseImpl};
+    use std::collections::HashMap;
+
+    #[test]
+    fn display_types() {
+        let db = MockDatabaseImpl::default();
+
+        let ty = Type::App(vec![
+            Type::Con(TypeCon::Int),
+            Type::Tuple(vec![Type::Con(TypeCon::Str), Type::Var(TypeVar(0))]),
+            Type::Con(TypeCon::Array {
+                ty: Box::new(Type::Con(TypeCon::Bool)),
+                size: Some(2),
+            }),
+        ]);
+
+        assert_eq!(
+            ty.display(&db).to_string(),
+            "fn(i32,(string,'t0)) -> [bool;2]"
+        );
+    }
+
+    #[test]
+    fn display_named_types() {
+        let db = MockDatabaseImpl::default();
+
+        let option = Type::Enum(
+            db.intern_name(Name::new("Option")),
+            vec![Type::Con(TypeCon::Int)],
+            HashMap::new(),
+        );
+
+        let class = Type::Class {
+            name: db.intern_name(Name::new("Dog")),
+            type_args: Vec::new(),
+            fields: HashMap::new(),
+            methods: HashMap::new(),
+            instance_methods: Default::default(),
+            superclass: None,
+        };
+
+        assert_eq!(option.display(&db).to_string(), "Option<i32>");
+        assert_eq!(class.display(&db).to_string(), "Dog");
+    }
+}
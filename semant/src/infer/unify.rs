@@ -1,6 +1,380 @@
-use super::Type;
-use crate::HirDatabase;
+use super::{infer::InferDataCollector, Type, TypeCon, TypeVar};
+use crate::{hir::NameId, HirDatabase};
 
-pub fn _unify_query(_db: &impl HirDatabase, _lhs: &Type, _rhs: &Type) -> Result<(), ()> {
-    Ok(())
+impl<'a, DB> InferDataCollector<&'a DB>
+where
+    DB: HirDatabase,
+{
+    /// Unify two types, recording any type vars that are solved in the substitution.
+    /// If the two types can't be unified an error is reported at `span`
+    pub(crate) fn unify(
+        &mut self,
+        expected: &Type,
+        found: &Type,
+        span: (usize, usize),
+    ) -> Result<(), ()> {
+        let expected = self.apply(expected);
+        let found = self.apply(found);
+
+        match (&expected, &found) {
+            (Type::Var(lhs), Type::Var(rhs)) if lhs == rhs => Ok(()),
+            (Type::Var(tv), ty) | (ty, Type::Var(tv)) => self.bind(*tv, ty, span),
+            (Type::App(lhs), Type::App(rhs)) | (Type::Tuple(lhs), Type::Tuple(rhs))
+                if lhs.len() == rhs.len() =>
+            {
+                let mut result = Ok(());
+
+                for (lhs, rhs) in lhs.iter().zip(rhs.iter()) {
+                    if self.unify(lhs, rhs, span).is_err() {
+                        result = Err(());
+                    }
+                }
+
+                result
+            }
+            (
+                Type::Con(TypeCon::Array {
+                    ty: lhs,
+                    size: l_size,
+                }),
+                Type::Con(TypeCon::Array {
+                    ty: rhs,
+                    size: r_size,
+                }),
+            ) => {
+                if let (Some(l_size), Some(r_size)) = (l_size, r_size) {
+                    if l_size != r_size {
+                        self.mismatch(&expected, &found, span);
+                        return Err(());
+                    }
+                }
+
+                self.unify(lhs, rhs, span)
+            }
+            (Type::Enum(l_name, l_args, lhs), Type::Enum(r_name, r_args, rhs))
+                if l_name == r_name =>
+            {
+                let mut result = Ok(());
+
+                // A type param that none of the variants use is only known through the args
+                for (lhs, rhs) in l_args.iter().zip(r_args) {
+                    if self.unify(lhs, rhs, span).is_err() {
+                        result = Err(());
+                    }
+                }
+
+                for (name, l_variant) in lhs {
+                    let r_variant = match rhs.get(name) {
+                        Some(variant) if variant.tag == l_variant.tag => variant,
+                        _ => {
+                            self.mismatch(&expected, &found, span);
+                            return Err(());
+                        }
+                    };
+
+                    match (&l_variant.ty, &r_variant.ty) {
+                        (Some(lhs), Some(rhs)) => {
+                            if self.unify(lhs, rhs, span).is_err() {
+                                result = Err(());
+                            }
+                        }
+                        (None, None) => {}
+                        _ => {
+                            self.mismatch(&expected, &found, span);
+                            return Err(());
+                        }
+                    }
+                }
+
+                result
+            }
+            (
+                Type::Class {
+                    name: l_name,
+                    fields: l_fields,
+                    methods: l_methods,
+                    ..
+                },
+                Type::Class {
+                    name: r_name,
+                    fields: r_fields,
+                    methods: r_methods,
+                    ..
+                },
+            ) if l_name == r_name && l_fields.len() == r_fields.len() => {
+                let mut result = Ok(());
+
+                // The signatures of a class's methods only know the class by its fields
+                let methods = l_methods
+                    .iter()
+                    .filter_map(|lhs| Some((lhs, Some(r_methods.get(lhs.0)?))));
+
+                for (lhs, rhs) in l_fields
+                    .iter()
+                    .map(|lhs| (lhs, r_fields.get(lhs.0)))
+                    .chain(methods)
+                {
+                    let rhs = match rhs {
+                        Some(rhs) => rhs,
+                        None => {
+                            self.mismatch(&expected, &found, span);
+                            return Err(());
+                        }
+                    };
+
+                    if self.unify(lhs.1, rhs, span).is_err() {
+                        result = Err(());
+                    }
+                }
+
+                result
+            }
+            (Type::Poly(_, _), _) => {
+                let expected = self.instantiate(expected.clone());
+                self.unify(&expected, &found, span)
+            }
+            (_, Type::Poly(_, _)) => {
+                let found = self.instantiate(found.clone());
+                self.unify(&expected, &found, span)
+            }
+            (Type::Con(TypeCon::Optional(lhs)), Type::Con(TypeCon::Optional(rhs))) => {
+                self.unify(lhs, rhs, span)
+            }
+            (Type::Con(lhs), Type::Con(rhs)) if lhs == rhs => Ok(()),
+            (_, _) => {
+                self.mismatch(&expected, &found, span);
+                Err(())
+            }
+        }
+    }
+
+    /// Check that a value of type `found` can be used where `expected` is expected, as in an
+    /// assignment, an argument or a return. A subclass can be used where one of its superclasses
+    /// is expected, but the types inside of arrays, tuples and generics still have to be equal
+    /// as a `[Dog]` can't be used as an `[Animal]`
+    pub(crate) fn coerce(
+        &mut self,
+        expected: &Type,
+        found: &Type,
+        span: (usize, usize),
+    ) -> Result<(), ()> {
+        let expected = self.apply(expected);
+        let found = self.apply(found);
+
+        match (&expected, &found) {
+            (Type::Poly(_, _), _) => {
+                let expected = self.instantiate(expected.clone());
+                self.coerce(&expected, &found, span)
+            }
+            (_, Type::Poly(_, _)) => {
+                let found = self.instantiate(found.clone());
+                self.coerce(&expected, &found, span)
+            }
+            (
+                Type::Class { name, .. },
+                Type::Class {
+                    name: found_name, ..
+                },
+            ) if name != found_name => match superclass_named(&found, *name) {
+                Some(superclass) => self.unify(&expected, superclass, span),
+                None => {
+                    self.mismatch(&expected, &found, span);
+                    Err(())
+                }
+            },
+            // A value can be used where an optional of its type is expected
+            (Type::Con(TypeCon::Optional(inner)), _)
+                if !matches!(found, Type::Var(_) | Type::Con(TypeCon::Optional(_))) =>
+            {
+                self.coerce(inner, &found, span)
+            }
+            _ => self.unify(&expected, &found, span),
+        }
+    }
+
+    /// The type of a value that is either `lhs` or `rhs`, such as the branches of an `if` or the
+    /// elements of an array. Two different classes meet at their closest common superclass
+    pub(crate) fn join(&mut self, lhs: &Type, rhs: &Type, span: (usize, usize)) -> Type {
+        let lhs = match self.apply(lhs) {
+            ty @ Type::Poly(_, _) => self.instantiate(ty),
+            ty => ty,
+        };
+
+        let rhs = match self.apply(rhs) {
+            ty @ Type::Poly(_, _) => self.instantiate(ty),
+            ty => ty,
+        };
+
+        match (&lhs, &rhs) {
+            (Type::Class { name: l_name, .. }, Type::Class { name: r_name, .. })
+                if l_name != r_name =>
+            {
+                match common_superclass(&lhs, &rhs) {
+                    Some(common) => common.clone(),
+                    None => {
+                        self.mismatch(&lhs, &rhs, span);
+                        lhs.clone()
+                    }
+                }
+            }
+            // A value and an optional meet at the optional
+            (Type::Con(TypeCon::Optional(inner)), other)
+            | (other, Type::Con(TypeCon::Optional(inner)))
+                if !matches!(other, Type::Var(_) | Type::Con(TypeCon::Optional(_))) =>
+            {
+                let inner = self.join(inner, other, span);
+
+                Type::Con(TypeCon::Optional(Box::new(inner)))
+            }
+            _ => {
+                let _ = self.unify(&lhs, &rhs, span);
+
+                self.apply(&lhs)
+            }
+        }
+    }
+
+    /// Record that `tv` is equal to `ty` in the substitution.
+    /// Binding a type var to a type that contains itself would create an infinite type
+    fn bind(&mut self, tv: TypeVar, ty: &Type, span: (usize, usize)) -> Result<(), ()> {
+        if occurs(tv, ty) {
+            self.reporter.error(
+                format!("Infinite type `{}` found in `{}`", tv, self.display(ty)),
+                "",
+                span,
+            );
+
+            return Err(());
+        }
+
+        self.subst.insert(tv, ty.clone());
+
+        Ok(())
+    }
+
+    fn mismatch(&mut self, expected: &Type, found: &Type, span: (usize, usize)) {
+        // `nil` on its own is an optional of a type that isn't known yet
+        let (found, note) = match found {
+            Type::Con(TypeCon::Optional(inner)) if matches!(**inner, Type::Var(_)) => (
+                "nil".to_string(),
+                format!(
+                    "Only an optional such as `{}?` can hold `nil`",
+                    self.display(expected)
+                ),
+            ),
+            Type::Con(TypeCon::Optional(_)) => (
+                self.display(found).to_string(),
+                "Check that it isn't `nil` with `if x != nil` first".to_string(),
+            ),
+            _ => (self.display(found).to_string(), String::new()),
+        };
+
+        self.reporter.error(
+            format!("expected `{}`, found `{}`", self.display(expected), found),
+            note,
+            span,
+        );
+    }
+}
+
+/// Checks if the type var appears anywhere inside of `ty`
+fn occurs(tv: TypeVar, ty: &Type) -> bool {
+    match ty {
+        Type::Var(other) => tv == *other,
+        Type::App(types) | Type::Tuple(types) => types.iter().any(|ty| occurs(tv, ty)),
+        Type::Poly(tvs, ty) => !tvs.contains(&tv) && occurs(tv, ty),
+        Type::Con(TypeCon::Array { ty, .. }) | Type::Con(TypeCon::Optional(ty)) => occurs(tv, ty),
+        Type::Con(_) => false,
+        Type::Enum(_, _, variants) => variants
+            .values()
+            .any(|variant| variant.ty.as_ref().map_or(false, |ty| occurs(tv, ty))),
+        Type::Class {
+            fields, methods, ..
+        } => fields
+            .values()
+            .chain(methods.values())
+            .any(|ty| occurs(tv, ty)),
+    }
+}
+
+/// Walks up the superclasses of `class` looking for the one called `name`
+fn superclass_named(class: &Type, name: NameId) -> Option<&Type> {
+    match class {
+        Type::Class {
+            superclass: Some(superclass),
+            ..
+        } => match **superclass {
+            Type::Class {
+                name: superclass_name,
+                ..
+            } if superclass_name == name => Some(superclass),
+            _ => superclass_named(superclass, name),
+        },
+        _ => None,
+    }
+}
+
+/// The closest class that both `lhs` and `rhs` are or inherit from
+fn common_superclass<'t>(lhs: &'t Type, rhs: &Type) -> Option<&'t Type> {
+    let mut class = lhs;
+
+    loop {
+        match class {
+            Type::Class {
+                name, superclass, ..
+            } => {
+                let shared = match rhs {
+                    Type::Class { name: r_name, .. } => r_name == name,
+                    _ => false,
+                };
+
+                if shared || superclass_named(rhs, *name).is_some() {
+                    return Some(class);
+                }
+
+                class = superclass.as_deref()?;
+            }
+            _ => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{resolver::tests::MockDatabaseImpl, HirDatabase};
+    use errors::db::FileDatabase;
+    use std::io::{self, Write};
+
+    #[test]
+    fn mismatch_shows_type_names() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("main.tox");
+
+        write!(
+            &mut std::fs::File::create(&path)?,
+            "class Animal {{ legs: i32; }} class Dog extends Animal {{ breed: string; }} fn breed(dog: Dog) -> string {{ return dog.breed; }} fn main() -> string {{ let cat = Animal {{ legs: 4 }}; let n: i32 = Option::Some(1); return breed(cat); }}"
+        )?;
+
+        let db = MockDatabaseImpl::default();
+        let handle = db.intern_file(path);
+
+        let mut errors = db
+            .infer(handle)
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.message)
+            .collect::<Vec<_>>();
+
+        errors.sort();
+
+        assert_eq!(
+            errors,
+            vec![
+                "expected `Dog`, found `Animal`",
+                "expected `i32`, found `Option<i32>`"
+            ]
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,72 @@
+mod eval;
+mod expression;
+mod value;
+
+pub use eval::{interpret, INTERPRETER_STACK_SIZE};
+pub use value::Value;
+
+#[macro_export]
+macro_rules! create_interpreter_test {
+    ($filename:ident, is_err) => {
+        #[test]
+        fn $filename() -> std::io::Result<()> {
+            $crate::__interpreter_test_thread!({
+                let (db, handle, _dir) = $crate::__interpreter_test_db!($filename);
+
+                assert!($crate::interpret(&db, handle).is_err());
+                Ok(())
+            })
+        }
+    };
+    ($filename:ident, $expected:expr) => {
+        #[test]
+        fn $filename() -> std::io::Result<()> {
+            $crate::__interpreter_test_thread!({
+                let (db, handle, _dir) = $crate::__interpreter_test_db!($filename);
+
+                match $crate::interpret(&db, handle) {
+                    Ok(value) => assert_eq!(value.display(&db).to_string(), $expected),
+                    Err(errors) => panic!("{:?}", errors),
+                }
+                Ok(())
+            })
+        }
+    };
+}
+
+/// Runs the test on a thread with the stack that the interpreter needs
+#[macro_export]
+macro_rules! __interpreter_test_thread {
+    ($body:block) => {{
+        std::thread::Builder::new()
+            .stack_size($crate::INTERPRETER_STACK_SIZE)
+            .spawn(move || -> std::io::Result<()> { $body })?
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }};
+}
+
+#[macro_export]
+macro_rules! __interpreter_test_db {
+    ($filename:ident) => {{
+        use errors::db::FileDatabase;
+
+        let dir = tempfile::tempdir()?;
+
+        let structure = $crate::resolver::tests::load_file(&format!(
+            "{}/src/interpreter/tests/{}.ron",
+            env!("CARGO_MANIFEST_DIR"),
+            stringify!($filename)
+        ));
+
+        let mut file_names = Vec::new();
+
+        $crate::resolver::tests::create_structure(&dir.path(), &structure, &mut file_names)?;
+
+        let db = $crate::resolver::tests::MockDatabaseImpl::default();
+
+        let handle = db.intern_file(file_names.remove(0));
+
+        (db, handle, dir)
+    }};
+}
@@ -0,0 +1,596 @@
+use super::Value;
+use crate::{
+    hir::{self, ExprId, Function, FunctionAstMap, Name, NameId, PatId, StmtId},
+    infer::{InferResult, StackedMap, Type, TypeCon},
+    resolver::Resolver,
+    util, HirDatabase,
+};
+use errors::{FileId, Reporter, WithError};
+use std::{collections::HashMap, sync::Arc};
+
+/// The size of the stack that `interpret` should be run on.
+/// Calls are evaluated recursively so this bounds how deeply the calls of a program can nest
+pub const INTERPRETER_STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// The part of the stack that is kept free for the innermost call and for reporting the overflow
+const STACK_RED_ZONE: usize = 8 * 1024 * 1024;
+
+/// Used to unwind the interpreter out of loops and function calls
+#[derive(Debug)]
+pub(crate) enum ControlFlow {
+    /// The label of the loop to break out of, or the innermost loop, and the loop's value
+    Break(Option<NameId>, Value),
+    Continue(Option<NameId>),
+    Return(Value),
+    /// A runtime error occurred and it has already been reported
+    Error,
+}
+
+pub(crate) type EvalResult<T> = Result<T, ControlFlow>;
+
+#[derive(Debug)]
+pub(crate) struct Interpreter<DB> {
+    pub(crate) db: DB,
+    pub(crate) reporter: Reporter,
+    pub(crate) functions: HashMap<NameId, Arc<Function>>,
+    pub(crate) classes: HashMap<NameId, Arc<hir::Class>>,
+    /// The methods that the impl blocks of each class define
+    pub(crate) impl_methods: HashMap<NameId, Vec<Arc<Function>>>,
+    /// Used to look up the locals that a closure captures
+    pub(crate) resolver: Arc<Resolver>,
+    /// The types that were inferred for each function
+    pub(crate) types: Arc<InferResult>,
+    /// The function that is currently executing
+    pub(crate) function: Option<Arc<Function>>,
+    /// The class that the function that is currently executing is a method of
+    pub(crate) class: Option<NameId>,
+    /// The local variables of the function that is currently executing
+    pub(crate) locals: StackedMap<NameId, Value>,
+    /// The address of the top of the stack when the interpreter started
+    pub(crate) stack_start: usize,
+}
+
+impl<'a, DB> Interpreter<&'a DB>
+where
+    DB: HirDatabase,
+{
+    pub(crate) fn error(&mut self, msg: impl Into<String>, span: (usize, usize)) -> ControlFlow {
+        self.reporter.error(msg, "", span);
+        ControlFlow::Error
+    }
+
+    pub(crate) fn span(&self, ast_map: &FunctionAstMap, id: &ExprId) -> (usize, usize) {
+        let range = ast_map.expr_span(id);
+        (range.start().to_usize(), range.end().to_usize())
+    }
+
+    /// Whether another call would grow the stack past the part that is kept free
+    fn stack_exhausted(&self) -> bool {
+        self.stack_start.saturating_sub(stack_pointer()) > INTERPRETER_STACK_SIZE - STACK_RED_ZONE
+    }
+
+    /// The type that was inferred for an expression of the function that is currently executing
+    pub(crate) fn expr_type(&self, id: &ExprId) -> Option<&Type> {
+        let function = self.function.as_ref()?;
+
+        self.types
+            .function(self.class, function.name.item)?
+            .expr(id)
+    }
+
+    /// Whether the expression is an `i32`. They are stored as `i64`s so the results
+    /// of arithmetic on them have to be range checked
+    pub(crate) fn is_i32(&self, id: &ExprId) -> bool {
+        self.expr_type(id) == Some(&Type::Con(TypeCon::Int))
+    }
+
+    /// Looks for the method in the class and its impls and then in each of its superclasses.
+    /// The class that defines the method is returned along with it
+    fn find_method(&self, class: NameId, name: NameId) -> Option<(NameId, Arc<Function>)> {
+        let mut class = self.classes.get(&class)?;
+
+        loop {
+            let impl_methods = self
+                .impl_methods
+                .get(&class.name.item)
+                .map_or(&[][..], Vec::as_slice);
+
+            if let Some(method) = class
+                .methods
+                .iter()
+                .chain(impl_methods)
+                .find(|method| method.name.item == name)
+            {
+                return Some((class.name.item, method.clone()));
+            }
+
+            let superclass = class.superclass.as_ref()?;
+            let superclass = self.db.lookup_intern_type(superclass.item).name()?;
+
+            class = self.classes.get(&superclass)?;
+        }
+    }
+
+    pub(crate) fn call_function(
+        &mut self,
+        class: Option<NameId>,
+        name: NameId,
+        args: Vec<Value>,
+        span: (usize, usize),
+    ) -> EvalResult<Value> {
+        self.call_with_receiver(class, name, args, span)
+            .map(|(value, _)| value)
+    }
+
+    /// Calls a function and also gives the value `self` has once a method returns,
+    /// which is how the changes a method makes to its receiver are kept
+    pub(crate) fn call_with_receiver(
+        &mut self,
+        class: Option<NameId>,
+        name: NameId,
+        args: Vec<Value>,
+        span: (usize, usize),
+    ) -> EvalResult<(Value, Option<Value>)> {
+        let function = match class {
+            Some(class) => self
+                .find_method(class, name)
+                .map(|(class, method)| (Some(class), method)),
+            None => self
+                .functions
+                .get(&name)
+                .map(|function| (None, function.clone())),
+        };
+
+        let (class, function) = match function {
+            Some(function) => function,
+            None => {
+                let msg = format!("Unknown function `{}`", self.db.lookup_intern_name(name));
+                return Err(self.error(msg, span));
+            }
+        };
+
+        // The receiver of a method call is passed before the args
+        let receivers = function.self_param.is_some() as usize;
+
+        if function.params.len() + receivers != args.len() {
+            let msg = format!(
+                "`{}` expected {} arguments but {} were supplied",
+                self.db.lookup_intern_name(name),
+                function.params.len(),
+                args.len().saturating_sub(receivers)
+            );
+            return Err(self.error(msg, span));
+        }
+
+        if self.stack_exhausted() {
+            return Err(self.error("Stack overflow", span));
+        }
+
+        let caller_locals = std::mem::replace(&mut self.locals, StackedMap::new());
+        let caller = std::mem::replace(&mut self.function, Some(function.clone()));
+        let caller_class = std::mem::replace(&mut self.class, class);
+
+        self.locals.begin_scope();
+
+        let mut args = args.into_iter();
+
+        if let Some(self_param) = &function.self_param {
+            self.locals.insert(self_param.item, args.next().unwrap());
+        }
+
+        for (param, arg) in function.params.iter().zip(args) {
+            let param = function.ast_map.param(&param.item);
+
+            self.bind_pattern(&param.pat.item, arg, &function.ast_map);
+        }
+
+        let mut result = Ok(Value::Nil);
+
+        if let Some(body) = &function.body {
+            for stmt in body {
+                if let Err(flow) = self.exec_statement(stmt, &function.ast_map) {
+                    result = match flow {
+                        ControlFlow::Return(value) => Ok(value),
+                        ControlFlow::Error => Err(ControlFlow::Error),
+                        ControlFlow::Break(..) | ControlFlow::Continue(_) => Ok(Value::Nil),
+                    };
+                    break;
+                }
+            }
+        }
+
+        let receiver = function
+            .self_param
+            .as_ref()
+            .and_then(|self_param| self.locals.get(&self_param.item).cloned());
+
+        self.locals = caller_locals;
+        self.function = caller;
+        self.class = caller_class;
+
+        result.map(|value| (value, receiver))
+    }
+
+    pub(crate) fn call_closure(
+        &mut self,
+        function: Arc<Function>,
+        class: Option<NameId>,
+        expr: ExprId,
+        captures: Vec<(NameId, Value)>,
+        args: Vec<Value>,
+        span: (usize, usize),
+    ) -> EvalResult<Value> {
+        let (params, body) = match function.ast_map.expr(&expr) {
+            hir::Expr::Closure { params, body, .. } => (params, body),
+            _ => unreachable!(),
+        };
+
+        if params.len() != args.len() {
+            let msg = format!(
+                "The closure expected {} arguments but {} were supplied",
+                params.len(),
+                args.len()
+            );
+            return Err(self.error(msg, span));
+        }
+
+        if self.stack_exhausted() {
+            return Err(self.error("Stack overflow", span));
+        }
+
+        let caller_locals = std::mem::replace(&mut self.locals, StackedMap::new());
+        let caller = std::mem::replace(&mut self.function, Some(function.clone()));
+        let caller_class = std::mem::replace(&mut self.class, class);
+
+        self.locals.begin_scope();
+
+        for (name, value) in captures {
+            self.locals.insert(name, value);
+        }
+
+        for (param, arg) in params.iter().zip(args) {
+            self.bind_pattern(&param.pat.item, arg, &function.ast_map);
+        }
+
+        let result = match self.eval_expr(body, &function.ast_map) {
+            Ok(_) | Err(ControlFlow::Break(..)) | Err(ControlFlow::Continue(_)) => Ok(Value::Nil),
+            Err(ControlFlow::Return(value)) => Ok(value),
+            Err(ControlFlow::Error) => Err(ControlFlow::Error),
+        };
+
+        self.locals = caller_locals;
+        self.function = caller;
+        self.class = caller_class;
+
+        result
+    }
+
+    pub(crate) fn exec_statement(
+        &mut self,
+        stmt: &StmtId,
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<()> {
+        match ast_map.stmt(stmt) {
+            hir::Stmt::Let {
+                pat, initializer, ..
+            } => {
+                let value = if let Some(expr) = initializer {
+                    self.eval_expr(expr, ast_map)?
+                } else {
+                    Value::Nil
+                };
+
+                self.bind_pattern(&pat.item, value, ast_map);
+            }
+            hir::Stmt::Expr(expr) => {
+                self.eval_expr(expr, ast_map)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the statements within a new scope.
+    /// The scope is always exited even if we are unwinding
+    pub(crate) fn exec_block(
+        &mut self,
+        stmts: &[StmtId],
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<()> {
+        self.locals.begin_scope();
+
+        let mut result = Ok(());
+
+        for stmt in stmts {
+            result = self.exec_statement(stmt, ast_map);
+
+            if result.is_err() {
+                break;
+            }
+        }
+
+        self.locals.end_scope();
+
+        result
+    }
+
+    pub(crate) fn bind_pattern(&mut self, pat: &PatId, value: Value, ast_map: &FunctionAstMap) {
+        match (ast_map.pat(pat), value) {
+            (hir::Pattern::Bind { name }, value) => self.locals.insert(name.item, value),
+            (hir::Pattern::Tuple(patterns), Value::Tuple(values)) => {
+                for (pat, value) in tuple_elements(patterns, values, ast_map) {
+                    self.bind_pattern(&pat.item, value, ast_map)
+                }
+            }
+            (
+                hir::Pattern::Variant { pat: Some(pat), .. },
+                Value::Enum {
+                    value: Some(value), ..
+                },
+            ) => self.bind_pattern(&pat.item, *value, ast_map),
+            (hir::Pattern::Record { fields, .. }, Value::Class { fields: values, .. }) => {
+                for (field, pat) in fields {
+                    if let Some(value) = values.get(&field.item) {
+                        self.bind_pattern(&pat.item, value.clone(), ast_map)
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks if a value can be destructured by a pattern
+    pub(crate) fn matches(&self, pat: &PatId, value: &Value, ast_map: &FunctionAstMap) -> bool {
+        match ast_map.pat(pat) {
+            hir::Pattern::Bind { .. } | hir::Pattern::Placeholder | hir::Pattern::Rest => true,
+            hir::Pattern::Missing => false,
+            hir::Pattern::Tuple(patterns) => match value {
+                Value::Tuple(values) => {
+                    let has_rest = patterns
+                        .iter()
+                        .any(|pat| ast_map.pat(&pat.item) == &hir::Pattern::Rest);
+
+                    (has_rest || values.len() == patterns.len())
+                        && tuple_elements(patterns, values.iter().collect(), ast_map)
+                            .all(|(pat, value)| self.matches(&pat.item, value, ast_map))
+                }
+                _ => false,
+            },
+            hir::Pattern::Literal(literal) => &self.literal(*literal) == value,
+            hir::Pattern::Variant { variant, pat, .. } => match value {
+                Value::Enum {
+                    variant: value_variant,
+                    value,
+                    ..
+                } if *value_variant == variant.item => match (pat, value) {
+                    (Some(pat), Some(value)) => self.matches(&pat.item, value, ast_map),
+                    (Some(_), None) => false,
+                    (None, _) => true,
+                },
+                _ => false,
+            },
+            hir::Pattern::Record { fields, .. } => match value {
+                Value::Class { fields: values, .. } => {
+                    fields
+                        .iter()
+                        .all(|(field, pat)| match values.get(&field.item) {
+                            Some(value) => self.matches(&pat.item, value, ast_map),
+                            None => false,
+                        })
+                }
+                _ => false,
+            },
+        }
+    }
+
+    pub(crate) fn literal(&self, literal: hir::LiteralId) -> Value {
+        match self.db.lookup_intern_literal(literal) {
+            hir::Literal::String(s) => Value::Str(s.to_string()),
+            hir::Literal::Char(c) => Value::Char(c),
+            hir::Literal::Nil => Value::Nil,
+            hir::Literal::True => Value::Bool(true),
+            hir::Literal::False => Value::Bool(false),
+            hir::Literal::Int(i) | hir::Literal::Int64(i) => Value::Int(i),
+            hir::Literal::Float(bits) | hir::Literal::Float64(bits) => {
+                Value::Float(hir::Literal::float_value(bits))
+            }
+        }
+    }
+}
+
+/// The address of the top of the stack, which grows downwards
+fn stack_pointer() -> usize {
+    let marker = 0u8;
+    &marker as *const u8 as usize
+}
+
+/// Runs the exported `main` function of a file.
+/// It should be run on a thread with a stack of `INTERPRETER_STACK_SIZE`
+pub fn interpret(db: &impl HirDatabase, file: FileId) -> WithError<Value> {
+    // The lowered program has holes where the syntax errors are
+    let (_, diagnostics) = db.parse(file);
+
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    let program = db.lower(file);
+    let resolver = db.resolve_source_file(file)?;
+    let types = db.infer(file)?;
+
+    let mut interpreter = Interpreter {
+        db,
+        reporter: Reporter::new(file),
+        functions: HashMap::new(),
+        classes: HashMap::new(),
+        impl_methods: HashMap::new(),
+        resolver,
+        types,
+        function: None,
+        class: None,
+        locals: StackedMap::new(),
+        stack_start: stack_pointer(),
+    };
+
+    for function in &program.functions {
+        interpreter
+            .functions
+            .insert(function.name.item, function.clone());
+    }
+
+    for class in &program.classes {
+        interpreter.classes.insert(class.name.item, class.clone());
+    }
+
+    for impl_def in &program.impls {
+        if let Some(class) = db.lookup_intern_type(impl_def.target.item).name() {
+            interpreter
+                .impl_methods
+                .entry(class)
+                .or_insert_with(Vec::new)
+                .extend(impl_def.methods.iter().cloned());
+        }
+    }
+
+    let main_name = db.intern_name(Name::new("main"));
+
+    let main = program
+        .functions
+        .iter()
+        .find(|function| function.exported && function.name.item == main_name);
+
+    let result = match main {
+        Some(main) => interpreter.call_function(
+            None,
+            main.name.item,
+            Vec::new(),
+            main.name.as_reporter_span(),
+        ),
+        None => {
+            interpreter.reporter.error(
+                "Couldn't find an exported `main` function",
+                "",
+                (0usize, 0usize),
+            );
+            Err(ControlFlow::Error)
+        }
+    };
+
+    match result {
+        Ok(value) => Ok(value),
+        Err(_) => Err(interpreter.reporter.finish()),
+    }
+}
+
+/// Pairs the patterns of a tuple pattern with the elements they match.
+/// The patterns after a `..` match the last elements of the tuple
+fn tuple_elements<'a, T>(
+    patterns: &'a [util::Span<PatId>],
+    values: Vec<T>,
+    ast_map: &FunctionAstMap,
+) -> impl Iterator<Item = (&'a util::Span<PatId>, T)> {
+    let rest = patterns
+        .iter()
+        .position(|pat| ast_map.pat(&pat.item) == &hir::Pattern::Rest)
+        .unwrap_or(patterns.len());
+
+    let skipped = values
+        .len()
+        .saturating_sub(patterns.len().saturating_sub(1));
+
+    let (before, after) = (&patterns[..rest], patterns.get(rest + 1..).unwrap_or(&[]));
+
+    values
+        .into_iter()
+        .enumerate()
+        .filter_map(move |(i, value)| {
+            if i < before.len() {
+                Some((&before[i], value))
+            } else if i >= before.len() + skipped {
+                after
+                    .get(i - before.len() - skipped)
+                    .map(|pat| (pat, value))
+            } else {
+                None
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::create_interpreter_test;
+
+    create_interpreter_test!(recursion, "610");
+
+    create_interpreter_test!(for_loop, "45");
+
+    create_interpreter_test!(while_break, "5");
+
+    create_interpreter_test!(classes, "3");
+
+    create_interpreter_test!(enums, "Shape::Circle(10)");
+
+    create_interpreter_test!(match_literal, "two");
+
+    create_interpreter_test!(tuples, "(1,ab)");
+
+    create_interpreter_test!(closures, "21");
+
+    create_interpreter_test!(destructuring, "(4,5,0,7,9)");
+
+    create_interpreter_test!(match_guards, "(10,4,0,3,1,2)");
+
+    create_interpreter_test!(operators, "(2,7,5,16,64,8,2,true,7)");
+
+    create_interpreter_test!(strings, "(say \"hi\" H,a \"raw\" \\n,',6,true)");
+
+    create_interpreter_test!(numbers, "(255,10,15,1000000,0.0015,3,10,2000,10)");
+
+    create_interpreter_test!(arrays, "(10,7,3,[],[[true,false],[false,false]])");
+
+    create_interpreter_test!(casts, "(3.5,3,1,65,1.5,7!,-14)");
+    create_interpreter_test!(cast_to_alias, "(3,2!)");
+
+    create_interpreter_test!(
+        wide_numbers,
+        "(10000000000,-2147483648,-9223372036854775808,25000000000,1410065408,7,-1.5,16)"
+    );
+
+    create_interpreter_test!(loops, "(5,1,5,12)");
+
+    create_interpreter_test!(labelled_loops, "(14,35,3,two)");
+
+    create_interpreter_test!(for_loops, "(13,xy,82,3,3)");
+
+    create_interpreter_test!(for_continue, "(40,33)");
+
+    create_interpreter_test!(ranges, "(3,14,3,2..6)");
+
+    create_interpreter_test!(field_of_expression, "(1,3,4,4,1)");
+
+    create_interpreter_test!(iterators, "(431,ab,10)");
+
+    create_interpreter_test!(if_let, "(12,9,0,9,7)");
+
+    create_interpreter_test!(inheritance, "(Rex,11,pug,1,Bit)");
+
+    create_interpreter_test!(methods, "(8,7,counter,stepper,5)");
+
+    create_interpreter_test!(traits, "(3,10,point,line)");
+
+    create_interpreter_test!(optionals, "(7,0,true,6,negative)");
+
+    create_interpreter_test!(missing_main, is_err);
+
+    create_interpreter_test!(deep_recursion, "4501500");
+
+    create_interpreter_test!(stack_overflow, is_err);
+
+    create_interpreter_test!(negate_overflow, is_err);
+
+    create_interpreter_test!(i32_overflow, is_err);
+
+    create_interpreter_test!(i32_negate_overflow, is_err);
+
+    create_interpreter_test!(int_widths, "(2147483648,2147483646,-2147483648)");
+}
@@ -0,0 +1,810 @@
+use super::{
+    eval::{ControlFlow, EvalResult, Interpreter},
+    Value,
+};
+use crate::{
+    hir::{self, BinOp, BlockId, Expr, ExprId, FunctionAstMap, NameId, UnaryOp},
+    infer::{Type, TypeCon},
+    util, HirDatabase,
+};
+use indexmap::IndexMap;
+use std::{convert::TryFrom, rc::Rc};
+
+impl<'a, DB> Interpreter<&'a DB>
+where
+    DB: HirDatabase,
+{
+    pub(crate) fn eval_expr(&mut self, id: &ExprId, ast_map: &FunctionAstMap) -> EvalResult<Value> {
+        // Each arm is handled by its own method to keep the stack frame of this
+        // function small as it is re-entered for every nested expression
+        match ast_map.expr(id) {
+            Expr::Array(exprs) => Ok(Value::Array(Rc::new(self.eval_exprs(exprs, ast_map)?))),
+            Expr::ArrayRepeat { value, count } => {
+                let value = self.eval_expr(value, ast_map)?;
+
+                Ok(Value::Array(Rc::new(vec![value; *count])))
+            }
+            Expr::Binary { lhs, op, rhs } => self.eval_binary(id, lhs, *op, rhs, ast_map),
+            Expr::Block(block) => {
+                let block = ast_map.block(block);
+
+                self.exec_block(&block.0, ast_map)?;
+
+                Ok(Value::Nil)
+            }
+            Expr::Break { label, expr } => self.eval_break(label, expr, ast_map),
+            Expr::Continue { label } => Err(ControlFlow::Continue(
+                label.as_ref().map(|label| label.item),
+            )),
+            Expr::Missing => Err(self.error("Missing expression", self.span(ast_map, id))),
+            Expr::Call { callee, args, .. } => self.eval_call(id, callee, args, ast_map),
+            Expr::MethodCall {
+                receiver,
+                method,
+                args,
+                ..
+            } => self.eval_method_call(id, receiver, method, args, ast_map),
+            Expr::Path { def, name } if self.resolver.ctx.is_enum(&def.item) => Ok(Value::Enum {
+                def: def.item,
+                variant: name.item,
+                value: None,
+            }),
+            Expr::Path { def, name } => Ok(Value::Function {
+                class: Some(def.item),
+                name: name.item,
+            }),
+            Expr::Cast { expr, .. } => {
+                let value = self.eval_expr(expr, ast_map)?;
+
+                Ok(self.cast(value, id))
+            }
+            Expr::Closure { .. } => {
+                let function = self.function.clone().unwrap();
+
+                let captures = self
+                    .resolver
+                    .captures(&function.name.item, id)
+                    .iter()
+                    .filter_map(|name| Some((*name, self.locals.get(name)?.clone())))
+                    .collect();
+
+                Ok(Value::Closure {
+                    function,
+                    class: self.class,
+                    expr: *id,
+                    captures,
+                })
+            }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                if self.eval_expr(cond, ast_map)?.is_truthy() {
+                    self.eval_expr(then_branch, ast_map)
+                } else if let Some(else_branch) = else_branch {
+                    self.eval_expr(else_branch, ast_map)
+                } else {
+                    Ok(Value::Nil)
+                }
+            }
+            Expr::Ident(name) => self.eval_ident(name),
+            Expr::Index { base, index } => self.eval_index(id, base, index, ast_map),
+            Expr::While {
+                label,
+                cond,
+                body,
+                step,
+            } => self.eval_while(label, cond, body, step, ast_map),
+            Expr::DoWhile { label, body, cond } => self.eval_do_while(label, body, cond, ast_map),
+            Expr::Loop { label, body } => self.eval_loop(label, body, ast_map),
+            Expr::Literal(literal) => Ok(self.literal(*literal)),
+            Expr::Next(iter) => self.eval_next(iter),
+            Expr::Paren(expr) => self.eval_expr(expr, ast_map),
+            Expr::Try(expr) => self.eval_try(expr, ast_map),
+            Expr::Tuple(exprs) => Ok(Value::Tuple(self.eval_exprs(exprs, ast_map)?)),
+            Expr::Unary { op, expr } => {
+                let value = self.eval_expr(expr, ast_map)?;
+                let is_i32 = self.is_i32(expr);
+
+                self.unary_op(*op, value, is_i32, self.span(ast_map, id))
+            }
+            Expr::Field { base, fields } => self.eval_field(base, fields, ast_map),
+            Expr::Return(expr) => {
+                let value = if let Some(expr) = expr {
+                    self.eval_expr(expr, ast_map)?
+                } else {
+                    Value::Nil
+                };
+
+                Err(ControlFlow::Return(value))
+            }
+            Expr::Match { expr, arms } => self.eval_match(id, expr, arms, ast_map),
+            Expr::RecordLiteral { def, fields } => {
+                let mut values = IndexMap::new();
+
+                for (name, expr) in fields {
+                    values.insert(name.item, self.eval_expr(expr, ast_map)?);
+                }
+
+                Ok(Value::Class {
+                    def: def.item,
+                    fields: values,
+                })
+            }
+        }
+    }
+
+    /// Unwraps an `Ok` or returns the `Err` from the function
+    fn eval_try(&mut self, expr: &ExprId, ast_map: &FunctionAstMap) -> EvalResult<Value> {
+        let err = self.db.intern_name(hir::Name::new("Err"));
+
+        match self.eval_expr(expr, ast_map)? {
+            Value::Enum {
+                variant,
+                value: Some(value),
+                ..
+            } if variant != err => Ok(*value),
+            // The type checker only allows `?` on a `Result`
+            value => Err(ControlFlow::Return(value)),
+        }
+    }
+
+    fn eval_exprs(&mut self, exprs: &[ExprId], ast_map: &FunctionAstMap) -> EvalResult<Vec<Value>> {
+        let mut values = Vec::with_capacity(exprs.len());
+
+        for expr in exprs {
+            values.push(self.eval_expr(expr, ast_map)?);
+        }
+
+        Ok(values)
+    }
+
+    fn eval_break(
+        &mut self,
+        label: &Option<util::Span<NameId>>,
+        expr: &Option<ExprId>,
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<Value> {
+        let value = match expr {
+            Some(expr) => self.eval_expr(expr, ast_map)?,
+            None => Value::Nil,
+        };
+
+        Err(ControlFlow::Break(
+            label.as_ref().map(|label| label.item),
+            value,
+        ))
+    }
+
+    fn eval_while(
+        &mut self,
+        label: &Option<util::Span<NameId>>,
+        cond: &ExprId,
+        body: &BlockId,
+        step: &Option<ExprId>,
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<Value> {
+        while self.eval_expr(cond, ast_map)?.is_truthy() {
+            if self.exec_loop_body(label, body, ast_map)?.is_some() {
+                break;
+            }
+
+            if let Some(step) = step {
+                self.eval_expr(step, ast_map)?;
+            }
+        }
+
+        Ok(Value::Nil)
+    }
+
+    fn eval_do_while(
+        &mut self,
+        label: &Option<util::Span<NameId>>,
+        body: &BlockId,
+        cond: &ExprId,
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<Value> {
+        loop {
+            if self.exec_loop_body(label, body, ast_map)?.is_some()
+                || !self.eval_expr(cond, ast_map)?.is_truthy()
+            {
+                return Ok(Value::Nil);
+            }
+        }
+    }
+
+    /// A `loop` evaluates to the value of the `break` that stops it
+    fn eval_loop(
+        &mut self,
+        label: &Option<util::Span<NameId>>,
+        body: &BlockId,
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<Value> {
+        loop {
+            if let Some(value) = self.exec_loop_body(label, body, ast_map)? {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Runs one iteration of a loop and returns the value of the `break` that stopped it.
+    /// A `break` or `continue` that targets an outer loop is passed on
+    fn exec_loop_body(
+        &mut self,
+        label: &Option<util::Span<NameId>>,
+        body: &BlockId,
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<Option<Value>> {
+        let label = label.as_ref().map(|label| label.item);
+        let targets = |target: Option<NameId>| target.is_none() || target == label;
+
+        match self.exec_block(&ast_map.block(body).0, ast_map) {
+            Ok(()) => Ok(None),
+            Err(ControlFlow::Continue(target)) if targets(target) => Ok(None),
+            Err(ControlFlow::Break(target, value)) if targets(target) => Ok(Some(value)),
+            Err(flow) => Err(flow),
+        }
+    }
+
+    fn eval_binary(
+        &mut self,
+        id: &ExprId,
+        lhs: &ExprId,
+        op: BinOp,
+        rhs: &ExprId,
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<Value> {
+        let span = self.span(ast_map, id);
+
+        match op {
+            BinOp::And | BinOp::Or => {
+                let lhs = self.eval_expr(lhs, ast_map)?.is_truthy();
+
+                if (op == BinOp::And && !lhs) || (op == BinOp::Or && lhs) {
+                    return Ok(Value::Bool(lhs));
+                }
+
+                Ok(Value::Bool(self.eval_expr(rhs, ast_map)?.is_truthy()))
+            }
+            BinOp::Equal => {
+                let value = self.eval_expr(rhs, ast_map)?;
+
+                self.assign(lhs, value, ast_map)?;
+
+                Ok(Value::Nil)
+            }
+            BinOp::PlusEqual
+            | BinOp::MinusEqual
+            | BinOp::MultEqual
+            | BinOp::DivEqual
+            | BinOp::RemEqual => {
+                let op = match op {
+                    BinOp::PlusEqual => BinOp::Plus,
+                    BinOp::MinusEqual => BinOp::Minus,
+                    BinOp::MultEqual => BinOp::Mult,
+                    BinOp::DivEqual => BinOp::Div,
+                    _ => BinOp::Rem,
+                };
+
+                let current = self.eval_expr(lhs, ast_map)?;
+                let rhs = self.eval_expr(rhs, ast_map)?;
+
+                let value = self.binary_op(op, current, rhs, self.is_i32(lhs), span)?;
+
+                self.assign(lhs, value, ast_map)?;
+
+                Ok(Value::Nil)
+            }
+            _ => {
+                let is_i32 = self.is_i32(lhs);
+                let lhs = self.eval_expr(lhs, ast_map)?;
+                let rhs = self.eval_expr(rhs, ast_map)?;
+
+                self.binary_op(op, lhs, rhs, is_i32, span)
+            }
+        }
+    }
+
+    fn eval_call(
+        &mut self,
+        id: &ExprId,
+        callee: &ExprId,
+        args: &[ExprId],
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<Value> {
+        let span = self.span(ast_map, id);
+
+        // `Foo::Bar(value)`, the resolver has checked that a single value is given
+        if let Expr::Path { def, name } = ast_map.expr(callee) {
+            if self.resolver.ctx.is_enum(&def.item) {
+                let value = self.eval_exprs(args, ast_map)?.pop().map(Box::new);
+
+                return Ok(Value::Enum {
+                    def: def.item,
+                    variant: name.item,
+                    value,
+                });
+            }
+        }
+
+        let callee = self.eval_expr(callee, ast_map)?;
+        let args = self.eval_exprs(args, ast_map)?;
+
+        match callee {
+            Value::Function { class, name } => self.call_function(class, name, args, span),
+            Value::Closure {
+                function,
+                class,
+                expr,
+                captures,
+            } => self.call_closure(function, class, expr, captures, args, span),
+            value => {
+                let msg = format!("`{}` is not a function", value.display(self.db));
+                Err(self.error(msg, span))
+            }
+        }
+    }
+
+    /// Calls the method of the receiver's class with the receiver as `self`
+    fn eval_method_call(
+        &mut self,
+        id: &ExprId,
+        receiver: &ExprId,
+        method: &util::Span<NameId>,
+        args: &[ExprId],
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<Value> {
+        let span = self.span(ast_map, id);
+        let receiver = self.eval_expr(receiver, ast_map)?;
+
+        let class = match &receiver {
+            Value::Class { def, .. } => *def,
+            value => {
+                let msg = format!(
+                    "`{}` doesn't have the method `{}`",
+                    value.display(self.db),
+                    self.db.lookup_intern_name(method.item)
+                );
+                return Err(self.error(msg, span));
+            }
+        };
+
+        let mut values = vec![receiver];
+
+        values.extend(self.eval_exprs(args, ast_map)?);
+
+        self.call_function(Some(class), method.item, values, span)
+    }
+
+    /// Converts the value into the type that was inferred for the cast `id`,
+    /// which has the aliases in the spelled type resolved
+    fn cast(&self, value: Value, id: &ExprId) -> Value {
+        let target = match self.expr_type(id) {
+            Some(Type::Con(target)) => target,
+            _ => return value,
+        };
+
+        match (target, value) {
+            (TypeCon::Int, Value::Int(i)) => Value::Int(i64::from(i as i32)),
+            (TypeCon::Int, Value::Float(f)) => Value::Int(i64::from(f as i32)),
+            (TypeCon::Int64, Value::Float(f)) => Value::Int(f as i64),
+            (TypeCon::Int, Value::Bool(b)) | (TypeCon::Int64, Value::Bool(b)) => {
+                Value::Int(b as i64)
+            }
+            (TypeCon::Int, Value::Char(c)) | (TypeCon::Int64, Value::Char(c)) => {
+                Value::Int(c as i64)
+            }
+            (TypeCon::Float, Value::Int(i)) => Value::Float(f64::from(i as f32)),
+            (TypeCon::Float, Value::Float(f)) => Value::Float(f64::from(f as f32)),
+            (TypeCon::Float64, Value::Int(i)) => Value::Float(i as f64),
+            (TypeCon::Str, value) => Value::Str(value.display(self.db).to_string()),
+            (_, value) => value,
+        }
+    }
+
+    fn eval_ident(&mut self, name: &util::Span<NameId>) -> EvalResult<Value> {
+        if let Some(value) = self.locals.get(&name.item) {
+            Ok(value.clone())
+        } else if self.functions.contains_key(&name.item) {
+            Ok(Value::Function {
+                class: None,
+                name: name.item,
+            })
+        } else {
+            let msg = format!(
+                "Unknown variable `{}`",
+                self.db.lookup_intern_name(name.item)
+            );
+            Err(self.error(msg, name.as_reporter_span()))
+        }
+    }
+
+    /// Steps the `(array, index)` pair that a `for` loop keeps in `iter`
+    fn eval_next(&mut self, iter: &util::Span<NameId>) -> EvalResult<Value> {
+        let next = match self.locals.get_mut(&iter.item) {
+            Some(Value::Tuple(state)) => match state.as_mut_slice() {
+                [Value::Array(values), Value::Int(index)] => {
+                    let next = values.get(*index as usize).cloned();
+                    *index += 1;
+                    next
+                }
+                [Value::Range { start, end }, Value::Int(index)] => {
+                    let next = start.checked_add(*index).filter(|next| next < end);
+                    *index += 1;
+                    next.map(Value::Int)
+                }
+                [Value::Class { def, .. }, _] => {
+                    let def = *def;
+                    let iterator = state[0].clone();
+
+                    return self.eval_next_method(iter, def, iterator);
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let variant = if next.is_some() { "Some" } else { "None" };
+
+        Ok(Value::Enum {
+            def: self.db.intern_name(hir::Name::new("Option")),
+            variant: self.db.intern_name(hir::Name::new(variant)),
+            value: next.map(Box::new),
+        })
+    }
+
+    /// Calls the `next` method of a class that is iterated over and keeps the
+    /// changes it makes to the iterator for the following call
+    fn eval_next_method(
+        &mut self,
+        iter: &util::Span<NameId>,
+        def: NameId,
+        iterator: Value,
+    ) -> EvalResult<Value> {
+        let next = self.db.intern_name(hir::Name::new("next"));
+
+        let (value, receiver) =
+            self.call_with_receiver(Some(def), next, vec![iterator], iter.as_reporter_span())?;
+
+        if let (Some(Value::Tuple(state)), Some(receiver)) =
+            (self.locals.get_mut(&iter.item), receiver)
+        {
+            state[0] = receiver;
+        }
+
+        Ok(value)
+    }
+
+    fn eval_index(
+        &mut self,
+        id: &ExprId,
+        base: &ExprId,
+        index: &ExprId,
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<Value> {
+        let base = self.eval_expr(base, ast_map)?;
+        let index_span = self.span(ast_map, index);
+        let index = self.eval_expr(index, ast_map)?;
+
+        match (base, index) {
+            (Value::Array(values), Value::Int(i)) => {
+                if i < 0 || i as usize >= values.len() {
+                    let msg = format!(
+                        "Index out of bounds: the length is {} but the index is {}",
+                        values.len(),
+                        i
+                    );
+                    return Err(self.error(msg, index_span));
+                }
+
+                Ok(values[i as usize].clone())
+            }
+            (Value::Range { start, end }, Value::Int(i)) => {
+                let len = end.saturating_sub(start).max(0);
+
+                if i < 0 || i >= len {
+                    let msg = format!(
+                        "Index out of bounds: the length is {} but the index is {}",
+                        len, i
+                    );
+                    return Err(self.error(msg, index_span));
+                }
+
+                Ok(Value::Int(start + i))
+            }
+            (base, _) => {
+                let msg = format!("Cannot index into `{}`", base.display(self.db));
+                Err(self.error(msg, self.span(ast_map, id)))
+            }
+        }
+    }
+
+    fn unary_op(
+        &mut self,
+        op: UnaryOp,
+        value: Value,
+        is_i32: bool,
+        span: (usize, usize),
+    ) -> EvalResult<Value> {
+        match (op, value) {
+            (UnaryOp::Minus, Value::Int(i)) => match i.checked_neg().filter(|i| fits(*i, is_i32)) {
+                Some(i) => Ok(Value::Int(i)),
+                None => {
+                    let msg = format!("Attempt to negate `{}` with overflow", i);
+                    Err(self.error(msg, span))
+                }
+            },
+            (UnaryOp::Minus, Value::Float(f)) => Ok(Value::Float(-f)),
+            (UnaryOp::Excl, value) => Ok(Value::Bool(!value.is_truthy())),
+            (op, value) => {
+                let msg = format!("Cannot apply `{}` to `{}`", op, value.display(self.db));
+                Err(self.error(msg, span))
+            }
+        }
+    }
+
+    fn eval_field(
+        &mut self,
+        base: &ExprId,
+        fields: &[util::Span<NameId>],
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<Value> {
+        let mut value = self.eval_expr(base, ast_map)?;
+
+        for field in fields {
+            value = match value {
+                Value::Class {
+                    def,
+                    fields: mut class_fields,
+                } => {
+                    if let Some(value) = class_fields.remove(&field.item) {
+                        value
+                    } else {
+                        Value::Function {
+                            class: Some(def),
+                            name: field.item,
+                        }
+                    }
+                }
+                value => {
+                    let msg = format!(
+                        "`{}` doesn't have the field `{}`",
+                        value.display(self.db),
+                        self.db.lookup_intern_name(field.item)
+                    );
+                    return Err(self.error(msg, field.as_reporter_span()));
+                }
+            };
+        }
+
+        Ok(value)
+    }
+
+    fn eval_match(
+        &mut self,
+        id: &ExprId,
+        expr: &ExprId,
+        arms: &[hir::MatchArm],
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<Value> {
+        let value = self.eval_expr(expr, ast_map)?;
+
+        for arm in arms {
+            for pat in &arm.pats {
+                if !self.matches(&pat.item, &value, ast_map) {
+                    continue;
+                }
+
+                self.locals.begin_scope();
+
+                self.bind_pattern(&pat.item, value.clone(), ast_map);
+
+                let guard = match &arm.guard {
+                    Some(guard) => self.eval_expr(guard, ast_map),
+                    None => Ok(Value::Bool(true)),
+                };
+
+                let result = match guard {
+                    Ok(Value::Bool(true)) => self.eval_expr(&arm.expr, ast_map),
+                    // A guard that doesn't hold moves on to the next alternative
+                    Ok(_) => {
+                        self.locals.end_scope();
+                        continue;
+                    }
+                    Err(e) => Err(e),
+                };
+
+                self.locals.end_scope();
+
+                return result;
+            }
+        }
+
+        let msg = format!(
+            "No match arm matched the value `{}`",
+            value.display(self.db)
+        );
+
+        Err(self.error(msg, self.span(ast_map, id)))
+    }
+
+    /// Store a value into a local, a class field or an array element
+    fn assign(
+        &mut self,
+        target: &ExprId,
+        value: Value,
+        ast_map: &FunctionAstMap,
+    ) -> EvalResult<()> {
+        let span = self.span(ast_map, target);
+
+        match ast_map.expr(target) {
+            Expr::Ident(name) => {
+                if let Some(local) = self.locals.get_mut(&name.item) {
+                    *local = value;
+                    return Ok(());
+                }
+            }
+            Expr::Field { base, fields } => {
+                let mut local = match ast_map.expr(base) {
+                    Expr::Ident(name) => self.locals.get_mut(&name.item),
+                    _ => None,
+                };
+
+                for field in fields {
+                    local = match local {
+                        Some(Value::Class { fields, .. }) => fields.get_mut(&field.item),
+                        _ => None,
+                    };
+                }
+
+                if let Some(local) = local {
+                    *local = value;
+                    return Ok(());
+                }
+            }
+            Expr::Index { base, index } => {
+                let index_span = self.span(ast_map, index);
+                let index = self.eval_expr(index, ast_map)?;
+
+                if let (Expr::Ident(name), Value::Int(i)) = (ast_map.expr(base), index) {
+                    if let Some(Value::Array(values)) = self.locals.get_mut(&name.item) {
+                        if i < 0 || i as usize >= values.len() {
+                            let msg = format!(
+                                "Index out of bounds: the length is {} but the index is {}",
+                                values.len(),
+                                i
+                            );
+                            return Err(self.error(msg, index_span));
+                        }
+
+                        Rc::make_mut(values)[i as usize] = value;
+                        return Ok(());
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Err(self.error("Invalid assignment target", span))
+    }
+
+    fn binary_op(
+        &mut self,
+        op: BinOp,
+        lhs: Value,
+        rhs: Value,
+        is_i32: bool,
+        span: (usize, usize),
+    ) -> EvalResult<Value> {
+        let value = match (op, &lhs, &rhs) {
+            (BinOp::EqualEqual, lhs, rhs) => Value::Bool(lhs == rhs),
+            (BinOp::NotEqual, lhs, rhs) => Value::Bool(lhs != rhs),
+            (BinOp::Div, Value::Int(_), Value::Int(0)) => {
+                return Err(self.error("Attempt to divide by zero", span))
+            }
+            (BinOp::Rem, Value::Int(_), Value::Int(0)) => {
+                return Err(self.error(
+                    "Attempt to calculate the remainder with a divisor of zero",
+                    span,
+                ))
+            }
+            (op, Value::Int(l), Value::Int(r)) => {
+                let (l, r) = (*l, *r);
+
+                let value = match op {
+                    BinOp::Plus => l.checked_add(r).map(Value::Int),
+                    BinOp::Minus => l.checked_sub(r).map(Value::Int),
+                    BinOp::Mult => l.checked_mul(r).map(Value::Int),
+                    BinOp::Div => l.checked_div(r).map(Value::Int),
+                    BinOp::Rem => l.checked_rem(r).map(Value::Int),
+                    BinOp::BitAnd => Some(Value::Int(l & r)),
+                    BinOp::BitOr => Some(Value::Int(l | r)),
+                    BinOp::BitXor => Some(Value::Int(l ^ r)),
+                    BinOp::ShiftLeft => u32::try_from(r)
+                        .ok()
+                        .and_then(|r| l.checked_shl(r))
+                        .map(Value::Int),
+                    BinOp::ShiftRight => u32::try_from(r)
+                        .ok()
+                        .and_then(|r| l.checked_shr(r))
+                        .map(Value::Int),
+                    BinOp::Range => Some(Value::Range { start: l, end: r }),
+                    BinOp::RangeInclusive => {
+                        r.checked_add(1).map(|end| Value::Range { start: l, end })
+                    }
+                    BinOp::LessThan => Some(Value::Bool(l < r)),
+                    BinOp::GreaterThan => Some(Value::Bool(l > r)),
+                    BinOp::LessThanEqual => Some(Value::Bool(l <= r)),
+                    BinOp::GreaterThanEqual => Some(Value::Bool(l >= r)),
+                    _ => return Err(self.invalid_operands(op, &lhs, &rhs, span)),
+                };
+
+                let value = value.filter(|value| match value {
+                    Value::Int(i) => fits(*i, is_i32),
+                    _ => true,
+                });
+
+                match value {
+                    Some(value) => value,
+                    None => {
+                        let msg = format!("Attempt to `{} {} {}` with overflow", l, op, r);
+                        return Err(self.error(msg, span));
+                    }
+                }
+            }
+            (op, Value::Float(l), Value::Float(r)) => match op {
+                BinOp::Plus => Value::Float(l + r),
+                BinOp::Minus => Value::Float(l - r),
+                BinOp::Mult => Value::Float(l * r),
+                BinOp::Div => Value::Float(l / r),
+                BinOp::Rem => Value::Float(l % r),
+                BinOp::LessThan => Value::Bool(l < r),
+                BinOp::GreaterThan => Value::Bool(l > r),
+                BinOp::LessThanEqual => Value::Bool(l <= r),
+                BinOp::GreaterThanEqual => Value::Bool(l >= r),
+                _ => return Err(self.invalid_operands(op, &lhs, &rhs, span)),
+            },
+            (op, Value::Char(l), Value::Char(r)) => match op {
+                BinOp::LessThan => Value::Bool(l < r),
+                BinOp::GreaterThan => Value::Bool(l > r),
+                BinOp::LessThanEqual => Value::Bool(l <= r),
+                BinOp::GreaterThanEqual => Value::Bool(l >= r),
+                _ => return Err(self.invalid_operands(op, &lhs, &rhs, span)),
+            },
+            (op, Value::Str(l), Value::Str(r)) => match op {
+                BinOp::Plus => Value::Str(format!("{}{}", l, r)),
+                BinOp::LessThan => Value::Bool(l < r),
+                BinOp::GreaterThan => Value::Bool(l > r),
+                BinOp::LessThanEqual => Value::Bool(l <= r),
+                BinOp::GreaterThanEqual => Value::Bool(l >= r),
+                _ => return Err(self.invalid_operands(op, &lhs, &rhs, span)),
+            },
+            (op, _, _) => return Err(self.invalid_operands(op, &lhs, &rhs, span)),
+        };
+
+        Ok(value)
+    }
+
+    fn invalid_operands(
+        &mut self,
+        op: BinOp,
+        lhs: &Value,
+        rhs: &Value,
+        span: (usize, usize),
+    ) -> ControlFlow {
+        let msg = format!(
+            "Cannot apply `{}` to `{}` and `{}`",
+            op,
+            lhs.display(self.db),
+            rhs.display(self.db)
+        );
+
+        self.error(msg, span)
+    }
+}
+
+/// Whether the result of an operation fits into its type, ints are stored as `i64`s
+fn fits(value: i64, is_i32: bool) -> bool {
+    !is_i32 || i32::try_from(value).is_ok()
+}
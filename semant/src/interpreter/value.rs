@@ -0,0 +1,154 @@
+use crate::{
+    hir::{ExprId, Function, NameId},
+    HirDatabase,
+};
+use indexmap::IndexMap;
+use std::{
+    fmt::{self, Display},
+    rc::Rc,
+    sync::Arc,
+};
+
+/// A runtime value produced by the interpreter
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Char(char),
+    Str(String),
+    Nil,
+    Tuple(Vec<Value>),
+    /// Shared so that reading a local array or indexing into it doesn't copy the elements
+    Array(Rc<Vec<Value>>),
+    /// The ints from `start` up to but not including `end`
+    Range {
+        start: i64,
+        end: i64,
+    },
+    /// A function or a method on a class
+    Function {
+        class: Option<NameId>,
+        name: NameId,
+    },
+    /// A closure along with the values of the locals it captured when it was created
+    Closure {
+        function: Arc<Function>,
+        /// The class that `function` is a method of
+        class: Option<NameId>,
+        expr: ExprId,
+        captures: Vec<(NameId, Value)>,
+    },
+    Enum {
+        def: NameId,
+        variant: NameId,
+        value: Option<Box<Value>>,
+    },
+    Class {
+        def: NameId,
+        fields: IndexMap<NameId, Value>,
+    },
+}
+
+/// Helper struct to print a value with the names looked up from the database
+pub struct ValueDisplay<'a, DB> {
+    value: &'a Value,
+    db: &'a DB,
+}
+
+impl Value {
+    pub fn display<'a, DB: HirDatabase>(&'a self, db: &'a DB) -> ValueDisplay<'a, DB> {
+        ValueDisplay { value: self, db }
+    }
+
+    pub(crate) fn is_truthy(&self) -> bool {
+        match self {
+            Value::Bool(b) => *b,
+            Value::Nil => false,
+            _ => true,
+        }
+    }
+}
+
+impl<'a, DB> Display for ValueDisplay<'a, DB>
+where
+    DB: HirDatabase,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(float) => write!(f, "{}", float),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Char(c) => write!(f, "{}", c),
+            Value::Str(s) => write!(f, "{}", s),
+            Value::Nil => write!(f, "nil"),
+            Value::Tuple(values) => {
+                write!(f, "(")?;
+
+                for (i, value) in values.iter().enumerate() {
+                    if i + 1 == values.len() {
+                        write!(f, "{}", value.display(self.db))?;
+                    } else {
+                        write!(f, "{},", value.display(self.db))?;
+                    }
+                }
+
+                write!(f, ")")
+            }
+            Value::Array(values) => {
+                write!(f, "[")?;
+
+                for (i, value) in values.iter().enumerate() {
+                    if i + 1 == values.len() {
+                        write!(f, "{}", value.display(self.db))?;
+                    } else {
+                        write!(f, "{},", value.display(self.db))?;
+                    }
+                }
+
+                write!(f, "]")
+            }
+            Value::Range { start, end } => write!(f, "{}..{}", start, end),
+            Value::Function { name, .. } => {
+                write!(f, "<fn {}>", self.db.lookup_intern_name(*name))
+            }
+            Value::Closure { .. } => write!(f, "<closure>"),
+            Value::Enum {
+                def,
+                variant,
+                value,
+            } => {
+                write!(
+                    f,
+                    "{}::{}",
+                    self.db.lookup_intern_name(*def),
+                    self.db.lookup_intern_name(*variant)
+                )?;
+
+                if let Some(value) = value {
+                    write!(f, "({})", value.display(self.db))?;
+                }
+
+                Ok(())
+            }
+            Value::Class { def, fields } => {
+                write!(f, "{} {{", self.db.lookup_intern_name(*def))?;
+
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    write!(
+                        f,
+                        "{}:{}",
+                        self.db.lookup_intern_name(*name),
+                        value.display(self.db)
+                    )?;
+
+                    if i + 1 != fields.len() {
+                        write!(f, ",")?;
+                    }
+                }
+
+                write!(f, "}}")
+            }
+        }
+    }
+}
@@ -1,9 +1,12 @@
 #[macro_use]
 // mod ctx;
 
+mod compile;
 mod db;
 mod hir;
+pub mod ide;
 mod infer;
+mod interpreter;
 mod lower;
 
 mod util;
@@ -15,4 +18,5 @@ mod resolver;
 
 pub use db::{HirDatabase, HirDatabaseStorage, InternDatabaseStorage};
 pub use infer::Ctx;
+pub use interpreter::{interpret, Value, INTERPRETER_STACK_SIZE};
 pub use syntax::TextRange;
@@ -4,21 +4,23 @@ mod enums;
 mod function;
 mod imports;
 mod module;
+mod traits;
 
 use crate::{db::HirDatabase, hir};
-use errors::{FileId, WithError};
+use errors::FileId;
 use std::sync::Arc;
 use syntax::{
-    ClassDefOwner, EnumDefOwner, ExternImportDefOwner, FnDefOwner, ModuleDefOwner,
-    TypeAliasDefOwner,
+    ClassDefOwner, EnumDefOwner, ExternImportDefOwner, FnDefOwner, ImplDefOwner,
+    ImportSegmentOwner, ModuleDefOwner, NameOwner, TraitDefOwner, TypeAliasDefOwner,
 };
 
 pub(crate) use alias::lower_type_alias_query;
-pub(crate) use class::lower_class_query;
+pub(crate) use class::{lower_class_query, lower_impl_query};
 pub(crate) use enums::lower_enum_query;
 pub(crate) use function::lower_function_query;
 pub(crate) use imports::lower_import_query;
 pub(crate) use module::lower_module_query;
+pub(crate) use traits::lower_trait_query;
 
 #[macro_export]
 macro_rules! impl_collector {
@@ -28,12 +30,22 @@ macro_rules! impl_collector {
             DB: $crate::HirDatabase,
         {
             pub(crate) fn lower_type_param(&mut self, type_param: syntax::ast::TypeParam) {
-                let name = self.db.intern_name(type_param.name().unwrap().into());
+                // The parser has already reported a type param without a name
+                let name = match type_param.name() {
+                    Some(name) => name,
+                    None => return,
+                };
+
+                let bounds = type_param
+                    .bounds()
+                    .map(|bound| self.lower_type(bound))
+                    .collect();
 
                 self.add_type_param(
                     &type_param,
                     $crate::hir::TypeParam {
-                        name: util::Span::from_ast(name, &type_param),
+                        name: util::Span::from_ast(self.db.intern_name(name.clone().into()), &name),
+                        bounds,
                     },
                 );
             }
@@ -70,10 +82,18 @@ macro_rules! impl_collector {
                         self.db.intern_type($crate::hir::Type::ParenType(types))
                     }
                     syntax::ast::TypeRef::ArrayType(array_ty) => {
-                        let ty = self.lower_type(array_ty.type_ref().unwrap());
+                        let ty = self.lower_opt_type(array_ty.type_ref(), &array_ty);
+                        let size = array_ty
+                            .expr()
+                            .and_then(|size| self.lower_array_size(&size));
 
                         self.db
-                            .intern_type($crate::hir::Type::ArrayType { ty, size: None })
+                            .intern_type($crate::hir::Type::ArrayType { ty, size })
+                    }
+                    syntax::ast::TypeRef::OptionalType(optional_ty) => {
+                        let ty = self.lower_opt_type(optional_ty.type_ref(), &optional_ty);
+
+                        self.db.intern_type($crate::hir::Type::Optional(ty))
                     }
                     syntax::ast::TypeRef::IdentType(ident_ty) => {
                         if let Some(type_args) = ident_ty.type_args() {
@@ -115,46 +135,172 @@ macro_rules! impl_collector {
 
                 $crate::util::Span::from_range(id, range)
             }
+
+            /// Lowers a type that the parser might have failed to produce
+            /// A missing type spans the node it was expected in
+            pub(crate) fn lower_opt_type(
+                &mut self,
+                ty: Option<syntax::ast::TypeRef>,
+                parent: &impl syntax::AstNode,
+            ) -> $crate::util::Span<$crate::hir::TypeId> {
+                match ty {
+                    Some(ty) => self.lower_type(ty),
+                    None => $crate::util::Span::from_ast(
+                        self.db.intern_type($crate::hir::Type::Missing),
+                        parent,
+                    ),
+                }
+            }
+
+            /// Lowers the size of `[T; size]` or `[value; size]`, which has to be an integer literal
+            pub(crate) fn lower_array_size(&mut self, size: &syntax::ast::Expr) -> Option<usize> {
+                let msg = match size {
+                    syntax::ast::Expr::Literal(literal) => {
+                        match $crate::hir::Literal::from_token(literal.token_kind()) {
+                            Ok($crate::hir::Literal::Int(size))
+                            | Ok($crate::hir::Literal::Int64(size)) => return Some(size as usize),
+                            Ok(_) => "Expected an integer literal as the array size".into(),
+                            Err((msg, _)) => msg,
+                        }
+                    }
+                    _ => "Expected an integer literal as the array size".into(),
+                };
+
+                self.ast_map
+                    .insert_lowering_error($crate::util::Span::from_ast(msg, size));
+
+                None
+            }
         }
     };
 }
 
-pub(crate) fn lower_query(db: &impl HirDatabase, file: FileId) -> WithError<Arc<hir::SourceFile>> {
-    let source = db.parse(file)?;
+/// Lowers the tree even when it contains syntax errors.
+/// Items without a name are skipped as there is no way to refer to them.
+pub(crate) fn lower_query(db: &impl HirDatabase, file: FileId) -> Arc<hir::SourceFile> {
+    let (source, _) = db.parse(file);
     let mut program = hir::SourceFile::default();
 
     for import in source.imports() {
+        let mut segments = import.segments().peekable();
+
+        if segments.peek().is_none() || segments.any(|segment| segment.name().is_none()) {
+            continue;
+        }
+
         let id = db.intern_import(import);
         program.imports.push(db.lower_import(file, id));
     }
 
-    for module in source.modules() {
+    for module in source.modules().filter(|module| module.name().is_some()) {
         let id = db.intern_module(module);
         program.modules.push(db.lower_module(file, id));
     }
 
-    for type_alias in source.type_alias() {
+    for type_alias in source.type_alias().filter(|alias| alias.name().is_some()) {
         let id = db.intern_type_alias(type_alias);
 
         program.type_alias.push(db.lower_type_alias(id));
     }
 
-    for enum_def in source.enums() {
+    for enum_def in source.enums().filter(|enum_def| enum_def.name().is_some()) {
         let id = db.intern_enum(enum_def);
 
         program.enums.push(db.lower_enum(id))
     }
 
-    for class in source.classes() {
+    for class in source.classes().filter(|class| class.name().is_some()) {
         let id = db.intern_class(class);
 
         program.classes.push(db.lower_class(id));
     }
 
-    for function in source.functions() {
+    for trait_def in source
+        .traits()
+        .filter(|trait_def| trait_def.name().is_some())
+    {
+        let id = db.intern_trait(trait_def);
+
+        program.traits.push(db.lower_trait(id));
+    }
+
+    for impl_def in source.impls() {
+        let id = db.intern_impl(impl_def);
+
+        program.impls.push(db.lower_impl(id));
+    }
+
+    for function in source
+        .functions()
+        .filter(|function| function.name().is_some())
+    {
         let id = db.intern_function(function);
         program.functions.push(db.lower_function(id));
     }
 
-    Ok(Arc::new(program))
+    Arc::new(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hir, resolver::tests::MockDatabaseImpl, HirDatabase};
+    use errors::db::FileDatabase;
+    use parser::ParseDatabase;
+    use std::io::{self, Write};
+
+    #[test]
+    fn syntax_errors_keep_resolver_errors() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("main.tox");
+
+        write!(
+            &mut std::fs::File::create(&path)?,
+            "fn main() {{ let x = 1 + ; return y; }} fn foo() {{}}"
+        )?;
+
+        let db = MockDatabaseImpl::default();
+        let handle = db.intern_file(path);
+
+        let (_, syntax_errors) = db.parse(handle);
+        assert!(!syntax_errors.is_empty());
+
+        let program = db.lower(handle);
+        assert_eq!(program.functions.len(), 2);
+
+        let main = &program.functions[0];
+        assert!(main
+            .ast_map
+            .exprs()
+            .any(|(_, expr)| expr == &hir::Expr::Missing));
+
+        let errors = db.resolve_source_file(handle).unwrap_err();
+        assert!(errors
+            .iter()
+            .any(|error| error.message == "Use of undefined variable `y`"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn type_param_without_name() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("main.tox");
+
+        write!(
+            &mut std::fs::File::create(&path)?,
+            "fn foo<(x: i32) {{}} class Bar<(x: i32) {{}}"
+        )?;
+
+        let db = MockDatabaseImpl::default();
+        let handle = db.intern_file(path);
+
+        let (_, syntax_errors) = db.parse(handle);
+        assert!(!syntax_errors.is_empty());
+
+        let program = db.lower(handle);
+        assert_eq!(program.functions.len(), 1);
+        assert!(program.functions[0].type_params.is_empty());
+
+        Ok(())
+    }
 }
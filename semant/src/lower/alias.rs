@@ -59,7 +59,7 @@ pub(crate) fn lower_type_alias_query(
         }
     }
 
-    let ty = collector.lower_type(alias.type_ref().unwrap());
+    let ty = collector.lower_opt_type(alias.type_ref(), &alias);
     let span = alias.syntax().text_range();
     Arc::new(collector.finish(name, exported, ty, span))
 }
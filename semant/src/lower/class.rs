@@ -13,6 +13,7 @@ pub(crate) struct ClassDataCollector<DB> {
     db: DB,
     type_param_count: u64,
     type_params: Vec<util::Span<hir::TypeParamId>>,
+    superclass: Option<util::Span<hir::TypeId>>,
     methods: Vec<Arc<hir::Function>>,
     fields: Vec<util::Span<Field>>,
     ast_map: FunctionAstMap,
@@ -28,12 +29,14 @@ where
         let methods = self.methods;
         let ast_map = self.ast_map;
         let type_params = self.type_params;
+        let superclass = self.superclass;
         let fields = self.fields;
         Class {
             exported,
             name,
             ast_map,
             type_params,
+            superclass,
             fields,
             methods,
             span,
@@ -41,12 +44,12 @@ where
     }
 
     pub(crate) fn lower_field(&mut self, field: ast::NamedFieldDef) {
-        let property = util::Span::from_ast(
-            self.db.intern_name(field.name().unwrap().into()),
-            &field.name().unwrap(),
-        );
+        let property = match field.name() {
+            Some(name) => util::Span::from_ast(self.db.intern_name(name.clone().into()), &name),
+            None => return,
+        };
 
-        let ty = self.lower_type(field.ascribed_type().unwrap());
+        let ty = self.lower_opt_type(field.ascribed_type(), &field);
         self.fields
             .push(util::Span::from_ast(Field { property, ty }, &field));
     }
@@ -68,6 +71,7 @@ pub(crate) fn lower_class_query(db: &impl HirDatabase, class_id: hir::ClassId) -
         db,
         type_param_count: 0,
         type_params: Vec::new(),
+        superclass: None,
         methods: Vec::new(),
         fields: Vec::new(),
         ast_map: FunctionAstMap::default(),
@@ -81,11 +85,13 @@ pub(crate) fn lower_class_query(db: &impl HirDatabase, class_id: hir::ClassId) -
         }
     }
 
+    collector.superclass = class.superclass().map(|ty| collector.lower_type(ty));
+
     for field in class.fields() {
         collector.lower_field(field);
     }
 
-    for method in class.functions() {
+    for method in class.functions().filter(|method| method.name().is_some()) {
         let id = db.intern_function(method);
         collector.lower_method(db.lower_function(id));
     }
@@ -94,3 +100,36 @@ pub(crate) fn lower_class_query(db: &impl HirDatabase, class_id: hir::ClassId) -
 
     Arc::new(collector.finish(name, exported, span))
 }
+
+pub(crate) fn lower_impl_query(db: &impl HirDatabase, impl_id: hir::ImplId) -> Arc<hir::Impl> {
+    let impl_def = db.lookup_intern_impl(impl_id);
+
+    let mut collector = ClassDataCollector {
+        db,
+        type_param_count: 0,
+        type_params: Vec::new(),
+        superclass: None,
+        methods: Vec::new(),
+        fields: Vec::new(),
+        ast_map: FunctionAstMap::default(),
+    };
+
+    let trait_ref = collector.lower_opt_type(impl_def.trait_ref(), &impl_def);
+    let target = collector.lower_opt_type(impl_def.target(), &impl_def);
+
+    let methods = impl_def
+        .functions()
+        .filter(|method| method.name().is_some())
+        .map(|method| db.lower_function(db.intern_function(method)))
+        .collect();
+
+    let span = impl_def.syntax().text_range();
+
+    Arc::new(hir::Impl {
+        trait_ref,
+        target,
+        ast_map: collector.ast_map,
+        methods,
+        span,
+    })
+}
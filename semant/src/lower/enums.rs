@@ -40,8 +40,8 @@ where
     pub(crate) fn lower_variant(
         &mut self,
         variant: ast::EnumVariant,
-    ) -> util::Span<hir::EnumVariant> {
-        let name = self.db.intern_name(variant.name().unwrap().into());
+    ) -> Option<util::Span<hir::EnumVariant>> {
+        let name = self.db.intern_name(variant.name()?.into());
 
         let ty = if let Some(ty_ref) = variant.type_ref() {
             Some(self.lower_type(ty_ref))
@@ -49,7 +49,10 @@ where
             None
         };
 
-        util::Span::from_ast(hir::EnumVariant { name, ty }, &variant)
+        Some(util::Span::from_ast(
+            hir::EnumVariant { name, ty },
+            &variant,
+        ))
     }
 }
 
@@ -79,8 +82,9 @@ pub(crate) fn lower_enum_query(db: &impl HirDatabase, enum_id: hir::EnumId) -> A
 
     if let Some(variant_list) = enum_.variant_list() {
         for variant in variant_list.variants() {
-            let v = collector.lower_variant(variant);
-            collector.variants.push(v);
+            if let Some(v) = collector.lower_variant(variant) {
+                collector.variants.push(v);
+            }
         }
     }
 
@@ -4,8 +4,8 @@ use crate::{hir, impl_collector, util, TextRange};
 use std::sync::Arc;
 
 use syntax::{
-    ast, ArgListOwner, AstNode, LoopBodyOwner, NameOwner, TypeAscriptionOwner, TypeParamsOwner,
-    TypesOwner, VisibilityOwner,
+    ast, ArgListOwner, AstNode, AstPtr, ClassDefOwner, LabelOwner, LoopBodyOwner, NameOwner,
+    TypeAscriptionOwner, TypeParamsOwner, TypesOwner, VisibilityOwner,
 };
 
 #[derive(Debug)]
@@ -30,8 +30,10 @@ where
 {
     pub fn finish(
         self,
+        id: hir::FunctionId,
         exported: bool,
         name: util::Span<hir::NameId>,
+        self_param: Option<util::Span<hir::NameId>>,
         body: Option<Vec<hir::StmtId>>,
         returns: Option<util::Span<hir::TypeId>>,
         span: TextRange,
@@ -40,9 +42,11 @@ where
         let type_params = self.type_params;
         let ast_map = self.ast_map;
         hir::Function {
+            id,
             exported,
             name,
             ast_map,
+            self_param,
             params,
             type_params,
             returns,
@@ -63,13 +67,13 @@ where
         self.params.push(util::Span::from_ast(id, ast_node));
     }
 
-    fn add_pat(&mut self, ast_node: &ast::Pat, pat: hir::Pattern) -> util::Span<hir::PatId> {
+    fn add_pat(&mut self, range: TextRange, pat: hir::Pattern) -> util::Span<hir::PatId> {
         let current = self.pat_id_count;
         self.pat_id_count += 1;
         let id = hir::PatId(current);
         self.ast_map.insert_pat(id, pat);
 
-        util::Span::from_ast(id, ast_node)
+        util::Span::from_range(id, range)
     }
 
     pub fn add_stmt(&mut self, stmt: hir::Stmt) -> hir::StmtId {
@@ -96,14 +100,27 @@ where
         id
     }
 
-    pub fn add_expr(&mut self, expr: hir::Expr) -> hir::ExprId {
+    pub fn add_expr(&mut self, ast_node: &ast::Expr, expr: hir::Expr) -> hir::ExprId {
         let current = self.expr_id_count;
 
         self.expr_id_count += 1;
 
         let id = hir::ExprId(current);
 
-        self.ast_map.insert_expr(id, expr);
+        self.ast_map.insert_expr(id, expr, AstPtr::new(ast_node));
+
+        id
+    }
+
+    /// Adds an expression that the parser couldn't produce
+    pub fn add_missing_expr(&mut self, range: TextRange) -> hir::ExprId {
+        let current = self.expr_id_count;
+
+        self.expr_id_count += 1;
+
+        let id = hir::ExprId(current);
+
+        self.ast_map.insert_missing_expr(id, range);
 
         id
     }
@@ -122,17 +139,12 @@ where
 
     pub(crate) fn lower_pattern(&mut self, pat: ast::Pat) -> util::Span<hir::PatId> {
         let pattern = match &pat {
-            ast::Pat::BindPat(binding) => {
-                let name = self.db.intern_name(
-                    binding
-                        .name()
-                        .map(|n| n.into())
-                        .unwrap_or_else(crate::hir::Name::missing),
-                );
-
-                let name = util::Span::from_ast(name, &binding.name().unwrap());
-                crate::hir::Pattern::Bind { name }
-            }
+            ast::Pat::BindPat(binding) => match binding.name() {
+                Some(name) => crate::hir::Pattern::Bind {
+                    name: util::Span::from_ast(self.db.intern_name(name.clone().into()), &name),
+                },
+                None => crate::hir::Pattern::Missing,
+            },
             ast::Pat::PlaceholderPat(_) => crate::hir::Pattern::Placeholder,
             ast::Pat::TuplePat(variants) => crate::hir::Pattern::Tuple(
                 variants
@@ -140,18 +152,104 @@ where
                     .map(|pat| self.lower_pattern(pat))
                     .collect::<Vec<_>>(),
             ),
-            ast::Pat::LiteralPat(literal) => crate::hir::Pattern::Literal(self.db.intern_literal(
-                hir::Literal::from_token(literal.literal().unwrap().token_kind()),
-            )),
+            ast::Pat::LiteralPat(literal) => match literal.literal() {
+                Some(literal) => crate::hir::Pattern::Literal(self.lower_literal(&literal)),
+                None => crate::hir::Pattern::Missing,
+            },
+            ast::Pat::VariantPat(variant_pat) => {
+                let mut segments = variant_pat.segments();
+
+                match (segments.next(), segments.next()) {
+                    (Some(def), Some(variant)) => crate::hir::Pattern::Variant {
+                        def: util::Span::from_ast(self.db.intern_name(def.clone().into()), &def),
+                        variant: util::Span::from_ast(
+                            self.db.intern_name(variant.clone().into()),
+                            &variant,
+                        ),
+                        pat: variant_pat.pat().map(|pat| self.lower_pattern(pat)),
+                    },
+                    _ => crate::hir::Pattern::Missing,
+                }
+            }
+            ast::Pat::RecordPat(record_pat) => match record_pat.name() {
+                Some(def) => {
+                    let mut fields = Vec::new();
+
+                    for field in record_pat.fields() {
+                        let name = match field.name() {
+                            Some(name) => name,
+                            None => continue,
+                        };
+
+                        let field_name =
+                            util::Span::from_ast(self.db.intern_name(name.clone().into()), &name);
+
+                        let pat = match field.pat() {
+                            Some(pat) => self.lower_pattern(pat),
+                            None => self.add_pat(
+                                field.syntax().text_range(),
+                                crate::hir::Pattern::Bind { name: field_name },
+                            ),
+                        };
+
+                        fields.push((field_name, pat));
+                    }
+
+                    crate::hir::Pattern::Record {
+                        def: util::Span::from_ast(self.db.intern_name(def.clone().into()), &def),
+                        fields,
+                        rest: record_pat.rest_pat().is_some(),
+                    }
+                }
+                None => crate::hir::Pattern::Missing,
+            },
+            ast::Pat::RestPat(_) => crate::hir::Pattern::Rest,
         };
 
-        self.add_pat(&pat, pattern)
+        self.add_pat(pat.syntax().text_range(), pattern)
+    }
+
+    /// Lowers a pattern that the parser might have failed to produce
+    /// A missing pattern spans the node it was expected in
+    fn lower_opt_pattern<N: AstNode>(
+        &mut self,
+        pat: Option<ast::Pat>,
+        parent: &N,
+    ) -> util::Span<hir::PatId> {
+        match pat {
+            Some(pat) => self.lower_pattern(pat),
+            None => self.add_pat(parent.syntax().text_range(), hir::Pattern::Missing),
+        }
+    }
+
+    /// Lowers an expression that the parser might have failed to produce
+    /// A missing expression spans the node it was expected in
+    fn lower_opt_expr<N: AstNode>(&mut self, expr: Option<ast::Expr>, parent: &N) -> hir::ExprId {
+        match expr {
+            Some(expr) => self.lower_expr(expr),
+            None => self.add_missing_expr(parent.syntax().text_range()),
+        }
+    }
+
+    fn lower_label<N: LabelOwner>(&self, owner: &N) -> Option<util::Span<hir::NameId>> {
+        owner.label().map(|label| {
+            util::Span::from_range(
+                self.db.intern_name(hir::Name::new(label.text().as_str())),
+                label.text_range(),
+            )
+        })
+    }
+
+    fn lower_block(&mut self, block: Option<ast::Block>) -> Vec<hir::StmtId> {
+        block
+            .map(|block| block.statements().map(|st| self.lower_stmt(st)).collect())
+            .unwrap_or_default()
     }
 
     pub(crate) fn lower_param(&mut self, param: ast::Param) {
-        let pat = self.lower_pattern(param.pat().unwrap());
+        let pat = self.lower_opt_pattern(param.pat(), &param);
 
-        let ty = self.lower_type(param.ascribed_type().unwrap());
+        let ty = self.lower_opt_type(param.ascribed_type(), &param);
 
         self.add_param(&param, hir::Param { pat, ty });
     }
@@ -159,7 +257,7 @@ where
     pub fn lower_stmt(&mut self, node: ast::Stmt) -> hir::StmtId {
         let hir_stmt = match node {
             ast::Stmt::LetStmt(ref let_stmt) => {
-                let pat = self.lower_pattern(let_stmt.pat().unwrap());
+                let pat = self.lower_opt_pattern(let_stmt.pat(), let_stmt);
 
                 let initializer = if let Some(initializer) = let_stmt.initializer() {
                     Some(self.lower_expr(initializer))
@@ -180,49 +278,334 @@ where
                 }
             }
             ast::Stmt::ExprStmt(ref expr_stmt) => {
-                hir::Stmt::Expr(self.lower_expr(expr_stmt.expr().unwrap()))
+                hir::Stmt::Expr(self.lower_opt_expr(expr_stmt.expr(), expr_stmt))
             }
         };
 
         self.add_stmt(hir_stmt)
     }
 
+    fn lower_literal(&mut self, literal: &ast::Literal) -> hir::LiteralId {
+        self.lower_literal_with(literal, hir::Literal::from_token)
+    }
+
+    fn lower_literal_with(
+        &mut self,
+        literal: &ast::Literal,
+        from_token: fn(syntax::SyntaxToken) -> Result<hir::Literal, (String, hir::Literal)>,
+    ) -> hir::LiteralId {
+        let literal = match from_token(literal.token_kind()) {
+            Ok(lit) => lit,
+            Err((msg, lit)) => {
+                self.ast_map
+                    .insert_lowering_error(util::Span::from_ast(msg, literal));
+                lit
+            }
+        };
+
+        self.db.intern_literal(literal)
+    }
+
+    /// A `let pat = expr` condition becomes a match where the bindings of the pattern
+    /// are only in scope of the `matched` branch
+    fn lower_let_condition(
+        &mut self,
+        cond: &ast::Condition,
+        matched: hir::ExprId,
+        otherwise: hir::ExprId,
+    ) -> hir::Expr {
+        let pat = self.lower_opt_pattern(cond.pat(), cond);
+        let expr = self.lower_opt_expr(cond.expr(), cond);
+
+        let rest = self.add_pat(cond.syntax().text_range(), hir::Pattern::Placeholder);
+
+        hir::Expr::Match {
+            expr,
+            arms: vec![
+                hir::MatchArm {
+                    pats: vec![pat],
+                    guard: None,
+                    expr: matched,
+                },
+                hir::MatchArm {
+                    pats: vec![rest],
+                    guard: None,
+                    expr: otherwise,
+                },
+            ],
+        }
+    }
+
+    /// `for pat in iterable { body }` becomes
+    ///
+    /// ```ignore
+    /// {
+    ///     let $iter = (iterable, 0);
+    ///     while true {
+    ///         match next($iter) {
+    ///             Option::Some(pat) => { body },
+    ///             Option::None => break,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// where `next` hands out the elements of an array one by one or calls the `next`
+    /// method of a class
+    fn lower_for_in(&mut self, node: &ast::Expr, for_expr: &ast::ForExpr) -> hir::Expr {
+        let range = for_expr.syntax().text_range();
+
+        let iter = self.db.intern_name(hir::Name::new("$iter"));
+
+        let iterable_range = for_expr
+            .iterable()
+            .map_or(range, |iterable| iterable.syntax().text_range());
+
+        let iterable = self.lower_opt_expr(for_expr.iterable(), for_expr);
+        let zero = self.db.intern_literal(hir::Literal::Int(0));
+        let index = self.add_expr(node, hir::Expr::Literal(zero));
+        let state = self.add_expr(node, hir::Expr::Tuple(vec![iterable, index]));
+
+        let iter_pat = self.add_pat(
+            range,
+            hir::Pattern::Bind {
+                name: util::Span::from_range(iter, range),
+            },
+        );
+
+        let init = self.add_stmt(hir::Stmt::Let {
+            pat: iter_pat,
+            initializer: Some(state),
+            ascribed_type: None,
+        });
+
+        let pat = self.lower_opt_pattern(for_expr.pat(), for_expr);
+        let pat_range = TextRange::from_to(pat.start(), pat.end());
+
+        let option =
+            util::Span::from_range(self.db.intern_name(hir::Name::new("Option")), pat_range);
+        let some = util::Span::from_range(self.db.intern_name(hir::Name::new("Some")), pat_range);
+        let none = util::Span::from_range(self.db.intern_name(hir::Name::new("None")), pat_range);
+
+        let some_pat = self.add_pat(
+            pat_range,
+            hir::Pattern::Variant {
+                def: option,
+                variant: some,
+                pat: Some(pat),
+            },
+        );
+        let none_pat = self.add_pat(
+            pat_range,
+            hir::Pattern::Variant {
+                def: option,
+                variant: none,
+                pat: None,
+            },
+        );
+
+        let body = self.lower_block(for_expr.loop_body().and_then(|body| body.block()));
+        let body = self.add_block(hir::Block(body));
+        let body = self.add_expr(node, hir::Expr::Block(body));
+
+        let stop = self.add_expr(
+            node,
+            hir::Expr::Break {
+                label: None,
+                expr: None,
+            },
+        );
+
+        let next = self.add_expr(
+            node,
+            hir::Expr::Next(util::Span::from_range(iter, iterable_range)),
+        );
+
+        let step = self.add_expr(
+            node,
+            hir::Expr::Match {
+                expr: next,
+                arms: vec![
+                    hir::MatchArm {
+                        pats: vec![some_pat],
+                        guard: None,
+                        expr: body,
+                    },
+                    hir::MatchArm {
+                        pats: vec![none_pat],
+                        guard: None,
+                        expr: stop,
+                    },
+                ],
+            },
+        );
+
+        let step = self.expr_to_stmt(step);
+        let step = self.add_block(hir::Block(vec![step]));
+
+        let always = self.db.intern_literal(hir::Literal::True);
+        let cond = self.add_expr(node, hir::Expr::Literal(always));
+
+        let label = self.lower_label(for_expr);
+
+        let while_expr = self.add_expr(
+            node,
+            hir::Expr::While {
+                label,
+                cond,
+                body: step,
+                step: None,
+            },
+        );
+
+        let block = hir::Block(vec![init, self.expr_to_stmt(while_expr)]);
+
+        hir::Expr::Block(self.add_block(block))
+    }
+
+    /// `receiver.method(args)`
+    fn lower_method_call(
+        &mut self,
+        call_expr: &ast::CallExpr,
+        field_expr: &ast::FieldExpr,
+        method: ast::IdentExpr,
+    ) -> hir::Expr {
+        let receiver = self.lower_opt_expr(field_expr.base(), field_expr);
+
+        // `foo.bar::<i32>()` is parsed with the type args on the name of the method
+        let type_args = call_expr.type_args().or_else(|| method.type_args());
+
+        let method = match method.name() {
+            Some(name) => util::Span::from_ast(self.db.intern_name(name.into()), &method),
+            None => return hir::Expr::Missing,
+        };
+
+        let args = self.lower_args(call_expr);
+
+        let type_args = match type_args {
+            Some(type_args) => util::Span::from_ast(
+                type_args.types().map(|ty| self.lower_type(ty)).collect(),
+                &type_args,
+            ),
+            None => util::Span::new(Vec::new(), method.start(), method.end()),
+        };
+
+        hir::Expr::MethodCall {
+            receiver,
+            method,
+            args,
+            type_args,
+        }
+    }
+
+    /// `a.b.c` is lowered as a single access of the fields `b` and `c` on the base `a`,
+    /// which can be any expression such as `foo().bar` or `arr[0].bar`
+    fn lower_field_expr(&mut self, field_expr: &ast::FieldExpr) -> hir::Expr {
+        let mut fields = Vec::new();
+        let mut node = field_expr.clone();
+
+        loop {
+            match node.field().and_then(|field| Some((field.name()?, field))) {
+                Some((name, field)) => fields.push(util::Span::from_ast(
+                    self.db.intern_name(name.into()),
+                    &field,
+                )),
+                // The parser has already reported a field access without a name such as `a.`
+                None => return hir::Expr::Missing,
+            }
+
+            match node.base() {
+                Some(ast::Expr::FieldExpr(base)) => node = base,
+                base => {
+                    fields.reverse();
+
+                    let base = self.lower_opt_expr(base, &node);
+
+                    return hir::Expr::Field { base, fields };
+                }
+            }
+        }
+    }
+
+    fn lower_args(&mut self, call_expr: &ast::CallExpr) -> Vec<hir::ExprId> {
+        if let Some(arg_list) = call_expr.arg_list() {
+            arg_list.args().map(|arg| self.lower_expr(arg)).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// The class or enum and the name of `Foo::bar`
+    fn lower_path(
+        &mut self,
+        enum_expr: &ast::EnumExpr,
+    ) -> Option<(util::Span<hir::NameId>, util::Span<hir::NameId>)> {
+        let mut segments = enum_expr.segments().map(|segment| segment.name());
+
+        let (def, variant) = match (segments.next(), segments.next()) {
+            (Some(Some(def)), Some(Some(variant))) => (def, variant),
+            _ => return None,
+        };
+
+        let def = util::Span::from_ast(self.db.intern_name(def.clone().into()), &def);
+
+        let variant = util::Span::from_ast(self.db.intern_name(variant.clone().into()), &variant);
+
+        Some((def, variant))
+    }
+
     pub fn lower_expr(&mut self, node: ast::Expr) -> hir::ExprId {
         let expr = match node {
+            ast::Expr::ArrayExpr(ref array) if array.is_repeat() => {
+                let mut exprs = array.exprs();
+
+                let value = self.lower_opt_expr(exprs.next(), array);
+
+                match exprs.next().and_then(|count| self.lower_array_size(&count)) {
+                    Some(count) => hir::Expr::ArrayRepeat { value, count },
+                    None => hir::Expr::Missing,
+                }
+            }
             ast::Expr::ArrayExpr(ref array) => {
                 hir::Expr::Array(array.exprs().map(|expr| self.lower_expr(expr)).collect())
             }
             ast::Expr::BinExpr(ref bin_expr) => {
-                let lhs = self.lower_expr(bin_expr.lhs().unwrap());
-                let rhs = self.lower_expr(bin_expr.rhs().unwrap());
-
-                let op = hir::BinOp::from_kind(bin_expr.op_kind().unwrap()).unwrap(); // TODO fix the unwraps
+                let lhs = self.lower_opt_expr(bin_expr.lhs(), bin_expr);
+                let rhs = self.lower_opt_expr(bin_expr.rhs(), bin_expr);
 
-                hir::Expr::Binary { lhs, op, rhs }
+                match bin_expr.op_kind().and_then(hir::BinOp::from_kind) {
+                    Some(op) => hir::Expr::Binary { lhs, op, rhs },
+                    None => hir::Expr::Missing,
+                }
             }
             ast::Expr::BlockExpr(ref block) => {
-                let block = hir::Block(
-                    block
-                        .block()
-                        .unwrap()
-                        .statements()
-                        .map(|st| self.lower_stmt(st))
-                        .collect(),
-                );
+                let block = hir::Block(self.lower_block(block.block()));
 
                 hir::Expr::Block(self.add_block(block))
             }
 
-            ast::Expr::BreakExpr(_) => hir::Expr::Break,
+            ast::Expr::BreakExpr(ref break_expr) => hir::Expr::Break {
+                label: self.lower_label(break_expr),
+                expr: break_expr.expr().map(|expr| self.lower_expr(expr)),
+            },
             ast::Expr::CallExpr(ref call_expr) => {
-                let callee = self.lower_expr(call_expr.expr().unwrap());
-                let args = if let Some(arg_list) = call_expr.arg_list() {
-                    arg_list.args().map(|arg| self.lower_expr(arg)).collect()
-                } else {
-                    Vec::new()
-                };
+                if let Some(ast::Expr::FieldExpr(ref field_expr)) = call_expr.expr() {
+                    if let Some(method) = field_expr.field() {
+                        let expr = self.lower_method_call(call_expr, field_expr, method);
+                        return self.add_expr(&node, expr);
+                    }
+                }
+
+                let callee = self.lower_opt_expr(call_expr.expr(), call_expr);
+                let args = self.lower_args(call_expr);
 
-                let type_args = if let Some(type_args) = call_expr.type_args() {
+                // `foo::<i32>()` is parsed with the type args on the name of the function
+                let type_args = call_expr.type_args().or_else(|| match call_expr.expr() {
+                    Some(ast::Expr::IdentExpr(ident)) => ident.type_args(),
+                    _ => None,
+                });
+
+                let type_args = if let Some(type_args) = type_args {
                     util::Span::from_ast(
                         type_args
                             .types()
@@ -231,7 +614,7 @@ where
                         &type_args,
                     )
                 } else {
-                    util::Span::from_ast(Vec::new(), &call_expr.expr().unwrap())
+                    util::Span::from_range(Vec::new(), self.ast_map.expr_span(&callee))
                 };
 
                 hir::Expr::Call {
@@ -241,8 +624,8 @@ where
                 }
             }
             ast::Expr::CastExpr(ref cast_expr) => {
-                let ty = self.lower_type(cast_expr.type_ref().unwrap());
-                let expr = self.lower_expr(cast_expr.expr().unwrap());
+                let ty = self.lower_opt_type(cast_expr.type_ref(), cast_expr);
+                let expr = self.lower_opt_expr(cast_expr.expr(), cast_expr);
 
                 hir::Expr::Cast { expr, ty }
             }
@@ -255,54 +638,84 @@ where
 
                 let mut fields = Vec::new();
 
-                let fields_iter = record_lit.named_field_list().unwrap().fields();
+                let fields_iter = record_lit
+                    .named_field_list()
+                    .into_iter()
+                    .flat_map(|list| list.fields());
 
                 for field in fields_iter {
-                    let name = util::Span::from_ast(
-                        self.db.intern_name(field.name().unwrap().into()),
-                        &field.name().unwrap(),
-                    );
+                    let name = match field.name() {
+                        Some(name) => {
+                            util::Span::from_ast(self.db.intern_name(name.clone().into()), &name)
+                        }
+                        None => continue,
+                    };
 
-                    let expr = self.lower_expr(field.expr().unwrap());
+                    let expr = self.lower_opt_expr(field.expr(), &field);
 
                     fields.push((name, expr));
                 }
 
                 hir::Expr::RecordLiteral { def, fields }
             }
-            ast::Expr::ClosureExpr(ref _closure_expr) => {
-                // let args = closure_expr.a
+            ast::Expr::ClosureExpr(ref closure_expr) => {
+                let params = closure_expr
+                    .params()
+                    .map(|param| hir::ClosureParam {
+                        pat: self.lower_opt_pattern(param.pat(), &param),
+                        ty: param.ascribed_type().map(|ty| self.lower_type(ty)),
+                    })
+                    .collect();
 
-                unimplemented!()
-            }
-            ast::Expr::ContinueExpr(_) => hir::Expr::Continue,
-            ast::Expr::FieldExpr(ref field_expr) => {
-                let mut fields = Vec::new();
+                let returns = closure_expr.type_ref().map(|ty| self.lower_type(ty));
 
-                handle_field_expr(self.db, field_expr, &mut fields);
+                let body = self.lower_opt_expr(closure_expr.body(), closure_expr);
 
-                hir::Expr::Field { fields }
+                hir::Expr::Closure {
+                    params,
+                    returns,
+                    body,
+                }
+            }
+            ast::Expr::ContinueExpr(ref continue_expr) => hir::Expr::Continue {
+                label: self.lower_label(continue_expr),
+            },
+            ast::Expr::FieldExpr(ref field_expr) => self.lower_field_expr(field_expr),
+            ast::Expr::ForExpr(ref for_expr) if for_expr.is_for_in() => {
+                self.lower_for_in(&node, for_expr)
             }
             ast::Expr::ForExpr(ref for_expr) => {
-                let init = self.lower_stmt(for_expr.init().unwrap());
-                let cond = self.lower_expr(for_expr.cond().unwrap());
-                let increment = self.lower_expr(for_expr.increment().unwrap());
-
-                let loop_body = for_expr.loop_body().unwrap().block().unwrap();
-                let mut body = loop_body
-                    .statements()
-                    .map(|st| self.lower_stmt(st))
-                    .collect::<Vec<_>>();
+                let init = for_expr.init().map(|init| self.lower_stmt(init));
+                let cond = match for_expr.condition().and_then(|cond| cond.expr()) {
+                    Some(cond) => self.lower_expr(cond),
+                    None => {
+                        let lit = self.db.intern_literal(hir::Literal::True);
+                        self.add_expr(&node, hir::Expr::Literal(lit))
+                    }
+                };
+                let step = for_expr.increment().map(|incr| self.lower_expr(incr));
 
-                body.push(self.expr_to_stmt(increment));
+                let body = self.lower_block(for_expr.loop_body().and_then(|body| body.block()));
 
-                let body_block = hir::Block(body);
+                let body = self.add_block(hir::Block(body));
 
-                let body = self.add_block(body_block);
+                let label = self.lower_label(for_expr);
 
-                let while_expr = self.add_expr(hir::Expr::While { cond, body });
+                let while_expr = self.add_expr(
+                    &node,
+                    hir::Expr::While {
+                        label,
+                        cond,
+                        body,
+                        step,
+                    },
+                );
 
-                let block = hir::Block(vec![init, self.expr_to_stmt(while_expr)]);
+                let block = hir::Block(
+                    init.into_iter()
+                        .chain(std::iter::once(self.expr_to_stmt(while_expr)))
+                        .collect(),
+                );
 
                 let block = self.add_block(block);
 
@@ -313,61 +726,94 @@ where
                 &ident_expr.name().unwrap(),
             )),
             ast::Expr::IfExpr(ref if_expr) => {
-                let cond = self.lower_expr(if_expr.condition().unwrap().expr().unwrap());
-                let then_branch = self.lower_expr(ast::Expr::from(if_expr.then_branch().unwrap()));
+                let condition = if_expr.condition();
+                let then_branch =
+                    self.lower_opt_expr(if_expr.then_branch().map(ast::Expr::from), if_expr);
                 let else_branch = if let Some(else_branch) = if_expr.else_branch() {
                     Some(self.lower_expr(else_branch.expr()))
                 } else {
                     None
                 };
 
-                hir::Expr::If {
-                    cond,
-                    then_branch,
-                    else_branch,
+                match condition {
+                    Some(ref cond) if cond.pat().is_some() => {
+                        let else_branch = else_branch.unwrap_or_else(|| {
+                            let block = self.add_block(hir::Block(Vec::new()));
+                            self.add_expr(&node, hir::Expr::Block(block))
+                        });
+
+                        self.lower_let_condition(cond, then_branch, else_branch)
+                    }
+                    _ => hir::Expr::If {
+                        cond: self.lower_opt_expr(condition.and_then(|cond| cond.expr()), if_expr),
+                        then_branch,
+                        else_branch,
+                    },
                 }
             }
             ast::Expr::IndexExpr(ref index_expr) => {
-                let base = self.lower_expr(index_expr.base().unwrap());
-                let index = self.lower_expr(index_expr.index().unwrap());
+                let base = self.lower_opt_expr(index_expr.base(), index_expr);
+                let index = self.lower_opt_expr(index_expr.index(), index_expr);
                 hir::Expr::Index { base, index }
             }
 
             ast::Expr::Literal(ref literal_expr) => {
-                let literal = hir::Literal::from_token(literal_expr.token_kind());
-                hir::Expr::Literal(self.db.intern_literal(literal))
+                hir::Expr::Literal(self.lower_literal(literal_expr))
             }
             ast::Expr::MatchExpr(ref match_expr) => {
-                let expr = self.lower_expr(match_expr.expr().unwrap());
+                let expr = self.lower_opt_expr(match_expr.expr(), match_expr);
 
                 hir::Expr::Match {
                     expr,
                     arms: match_expr
                         .match_arm_list()
-                        .unwrap()
-                        .arms()
+                        .into_iter()
+                        .flat_map(|list| list.arms())
                         .map(|match_arm| {
                             let pats = match_arm
                                 .pats()
                                 .map(|pat| self.lower_pattern(pat))
                                 .collect();
 
-                            let expr = self.lower_expr(match_arm.expr().unwrap());
-                            hir::MatchArm { pats, expr }
+                            let guard = match_arm
+                                .match_guard()
+                                .map(|guard| self.lower_opt_expr(guard.expr(), &guard));
+
+                            let expr = self.lower_opt_expr(match_arm.expr(), &match_arm);
+                            hir::MatchArm { pats, guard, expr }
                         })
                         .collect(),
                 }
             }
             ast::Expr::ParenExpr(ref paren_expr) => {
-                let expr = paren_expr.expr().unwrap();
-
-                hir::Expr::Paren(self.lower_expr(expr))
+                hir::Expr::Paren(self.lower_opt_expr(paren_expr.expr(), paren_expr))
             }
             ast::Expr::PrefixExpr(ref prefix_expr) => {
-                let op = hir::UnaryOp::from_kind(prefix_expr.op_kind().unwrap()).unwrap(); // TODO fix the unwraps
-                let expr = self.lower_expr(prefix_expr.expr().unwrap());
+                let op = prefix_expr.op_kind().and_then(hir::UnaryOp::from_kind);
+
+                let number = match prefix_expr.expr() {
+                    Some(ast::Expr::Literal(literal)) => match literal.token_kind().kind() {
+                        syntax::SyntaxKind::INT_NUMBER | syntax::SyntaxKind::FLOAT_NUMBER => {
+                            Some(literal)
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
 
-                hir::Expr::Unary { op, expr }
+                match (op, number) {
+                    (Some(hir::UnaryOp::Minus), Some(literal)) => hir::Expr::Literal(
+                        self.lower_literal_with(&literal, hir::Literal::negative_number),
+                    ),
+                    (Some(op), _) => hir::Expr::Unary {
+                        op,
+                        expr: self.lower_opt_expr(prefix_expr.expr(), prefix_expr),
+                    },
+                    (None, _) => {
+                        self.lower_opt_expr(prefix_expr.expr(), prefix_expr);
+                        hir::Expr::Missing
+                    }
+                }
             }
             ast::Expr::ReturnExpr(ref return_expr) => {
                 let id = if let Some(expr) = return_expr.expr() {
@@ -380,19 +826,75 @@ where
             }
 
             ast::Expr::WhileExpr(ref while_expr) => {
-                let cond = self.lower_expr(while_expr.condition().unwrap().expr().unwrap());
+                let condition = while_expr.condition();
 
-                let loop_body = while_expr.loop_body().unwrap().block().unwrap();
                 let block = hir::Block(
-                    loop_body
-                        .statements()
-                        .map(|st| self.lower_stmt(st))
-                        .collect::<Vec<_>>(),
+                    self.lower_block(while_expr.loop_body().and_then(|body| body.block())),
                 );
 
                 let body = self.add_block(block);
 
-                hir::Expr::While { cond, body }
+                let label = self.lower_label(while_expr);
+
+                match condition {
+                    // `while let pat = expr { body }` loops over
+                    // `match expr { pat => { body }, _ => break }`
+                    Some(ref cond) if cond.pat().is_some() => {
+                        let body = self.add_expr(&node, hir::Expr::Block(body));
+                        let stop = self.add_expr(
+                            &node,
+                            hir::Expr::Break {
+                                label: None,
+                                expr: None,
+                            },
+                        );
+
+                        let step = self.lower_let_condition(cond, body, stop);
+                        let step = self.add_expr(&node, step);
+                        let step = self.expr_to_stmt(step);
+
+                        let always = self.db.intern_literal(hir::Literal::True);
+
+                        hir::Expr::While {
+                            label,
+                            cond: self.add_expr(&node, hir::Expr::Literal(always)),
+                            body: self.add_block(hir::Block(vec![step])),
+                            step: None,
+                        }
+                    }
+                    _ => hir::Expr::While {
+                        label,
+                        cond: self
+                            .lower_opt_expr(condition.and_then(|cond| cond.expr()), while_expr),
+                        body,
+                        step: None,
+                    },
+                }
+            }
+            ast::Expr::DoExpr(ref do_expr) => {
+                let block =
+                    hir::Block(self.lower_block(do_expr.loop_body().and_then(|body| body.block())));
+
+                let body = self.add_block(block);
+
+                let cond =
+                    self.lower_opt_expr(do_expr.condition().and_then(|cond| cond.expr()), do_expr);
+
+                hir::Expr::DoWhile {
+                    label: self.lower_label(do_expr),
+                    body,
+                    cond,
+                }
+            }
+            ast::Expr::LoopExpr(ref loop_expr) => {
+                let block = hir::Block(
+                    self.lower_block(loop_expr.loop_body().and_then(|body| body.block())),
+                );
+
+                hir::Expr::Loop {
+                    label: self.lower_label(loop_expr),
+                    body: self.add_block(block),
+                }
             }
             ast::Expr::TupleExpr(ref tuple_expr) => {
                 let exprs = tuple_expr
@@ -402,32 +904,17 @@ where
 
                 hir::Expr::Tuple(exprs)
             }
-
-            ast::Expr::EnumExpr(ref enum_expr) => {
-                let def = enum_expr.segments().nth(0).unwrap();
-                let variant = enum_expr.segments().nth(1).unwrap();
-
-                let def = util::Span::from_ast(
-                    self.db.intern_name(def.name().unwrap().into()),
-                    &def.name().unwrap(),
-                );
-
-                let variant = util::Span::from_ast(
-                    self.db.intern_name(variant.name().unwrap().into()),
-                    &variant.name().unwrap(),
-                );
-
-                let expr = if let Some(expr) = enum_expr.expr() {
-                    Some(self.lower_expr(expr))
-                } else {
-                    None
-                };
-
-                hir::Expr::Enum { def, variant, expr }
+            ast::Expr::TryExpr(ref try_expr) => {
+                hir::Expr::Try(self.lower_opt_expr(try_expr.expr(), try_expr))
             }
+
+            ast::Expr::EnumExpr(ref enum_expr) => match self.lower_path(enum_expr) {
+                Some((def, name)) => hir::Expr::Path { def, name },
+                None => hir::Expr::Missing,
+            },
         };
 
-        self.add_expr(expr)
+        self.add_expr(&node, expr)
     }
 }
 
@@ -452,7 +939,8 @@ pub(crate) fn lower_function_query(
 
     let exported = function.visibility().is_some();
 
-    let name: Option<crate::hir::Name> = function.name().map(|name| name.into());
+    // Functions without a name are skipped by the callers
+    let name = function.name().unwrap();
 
     if let Some(type_params_list) = function.type_param_list() {
         for type_param in type_params_list.type_params() {
@@ -460,50 +948,49 @@ pub(crate) fn lower_function_query(
         }
     }
 
+    let mut self_param = None;
+
     if let Some(param_list) = function.param_list() {
+        if let Some(param) = param_list.self_param() {
+            let in_class = function.syntax().parent().map_or(false, |parent| {
+                ast::ClassDef::can_cast(parent.kind())
+                    || ast::TraitDef::can_cast(parent.kind())
+                    || ast::ImplDef::can_cast(parent.kind())
+            });
+
+            if in_class {
+                let name = db.intern_name(hir::Name::new("self"));
+                self_param = Some(util::Span::from_ast(name, &param));
+            } else {
+                collector
+                    .ast_map
+                    .insert_lowering_error(util::Span::from_ast(
+                        "Only the methods of a class, trait or impl can take `self`".into(),
+                        &param,
+                    ));
+            }
+        }
+
         for param in param_list.params() {
             collector.lower_param(param);
         }
     }
 
     let body = if let Some(body) = function.body() {
-        Some(
-            body.block()
-                .unwrap()
-                .statements()
-                .map(|statement| collector.lower_stmt(statement))
-                .collect(),
-        )
+        Some(collector.lower_block(body.block()))
     } else {
         None
     };
 
     let returns = if let Some(ret) = function.ret_type() {
-        Some(collector.lower_type(ret.type_ref().unwrap()))
+        Some(collector.lower_opt_type(ret.type_ref(), &ret))
     } else {
         None
     };
 
     let span = function.syntax().text_range();
 
-    let name = util::Span::from_ast(db.intern_name(name.unwrap()), &function.name().unwrap());
-
-    Arc::new(collector.finish(exported, name, body, returns, span))
-}
-
-fn handle_field_expr(
-    db: &impl HirDatabase,
-    node: &ast::FieldExpr,
-    names: &mut Vec<util::Span<hir::NameId>>,
-) {
-    for ident in syntax::children::<ast::FieldExpr, ast::IdentExpr>(node) {
-        names.push(util::Span::from_ast(
-            db.intern_name(ident.name().unwrap().into()),
-            &ident,
-        ));
-    }
+    let name = util::Span::from_ast(db.intern_name(name.clone().into()), &name);
 
-    if let Some(field_expr) = node.field_expr() {
-        handle_field_expr(db, &field_expr, names)
-    }
+    Arc::new(collector.finish(fun_id, exported, name, self_param, body, returns, span))
 }
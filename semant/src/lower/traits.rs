@@ -0,0 +1,26 @@
+use crate::{
+    hir::{self, Trait},
+    util, HirDatabase,
+};
+use std::sync::Arc;
+use syntax::{AstNode, FnDefOwner, NameOwner, VisibilityOwner};
+
+pub(crate) fn lower_trait_query(db: &impl HirDatabase, trait_id: hir::TraitId) -> Arc<Trait> {
+    let trait_def = db.lookup_intern_trait(trait_id);
+
+    let name = trait_def.name().unwrap();
+    let name = util::Span::from_ast(db.intern_name(name.clone().into()), &name);
+
+    let methods = trait_def
+        .functions()
+        .filter(|method| method.name().is_some())
+        .map(|method| db.lower_function(db.intern_function(method)))
+        .collect();
+
+    Arc::new(Trait {
+        exported: trait_def.visibility().is_some(),
+        name,
+        methods,
+        span: trait_def.syntax().text_range(),
+    })
+}
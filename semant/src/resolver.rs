@@ -7,9 +7,10 @@ mod imports;
 mod module;
 mod module_graph;
 mod source_file;
+mod traits;
 #[macro_use]
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
 
 pub(crate) use data::Resolver;
 pub(crate) use data::TypeKind;
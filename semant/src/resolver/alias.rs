@@ -10,6 +10,8 @@ where
 
         let mut poly_tvs = Vec::new();
 
+        self.report_lowering_errors(&alias.ast_map);
+
         self.begin_scope();
 
         for type_param in &alias.type_params {
@@ -19,6 +21,18 @@ where
 
             self.insert_type(&type_param.name, Type::Var(tv), TypeKind::Type)?;
 
+            // An alias is replaced by its type so there is nowhere to check a bound
+            if let Some(bound) = type_param.bounds.first() {
+                self.reporter.error(
+                    "Type aliases can't have trait bounds",
+                    "",
+                    bound.as_reporter_span(),
+                );
+
+                self.end_scope();
+                return Err(());
+            }
+
             poly_tvs.push(tv)
         }
 
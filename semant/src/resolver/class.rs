@@ -1,15 +1,93 @@
 use super::{
-    data::{ItemKind, ResolverDataCollector},
+    data::{FunctionData, ResolverDataCollector},
     TypeKind,
 };
-use crate::{hir::Class, infer::Type, HirDatabase};
-use std::collections::HashMap;
+use crate::{
+    hir::{self, Class, NameId, TypeId},
+    infer::{Type, TypeCon, TypeVar},
+    util, HirDatabase,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 impl<'a, DB> ResolverDataCollector<&'a DB>
 where
     DB: HirDatabase,
 {
+    /// Resolves the classes of a file with every superclass resolved before the classes that
+    /// extend it. The classes of an inheritance cycle are reported and left unresolved
+    pub fn resolve_classes(&mut self, classes: &[Arc<Class>]) {
+        let by_name = classes
+            .iter()
+            .map(|class| (class.name.item, class.as_ref()))
+            .collect::<HashMap<_, _>>();
+
+        let mut resolved = HashMap::new();
+
+        for class in classes {
+            self.resolve_class_after_superclass(class, &by_name, &mut Vec::new(), &mut resolved);
+        }
+    }
+
+    /// Returns whether the class and all of its superclasses were resolved
+    fn resolve_class_after_superclass(
+        &mut self,
+        class: &Class,
+        by_name: &HashMap<NameId, &Class>,
+        path: &mut Vec<NameId>,
+        resolved: &mut HashMap<NameId, bool>,
+    ) -> bool {
+        if let Some(ok) = resolved.get(&class.name.item) {
+            return *ok;
+        }
+
+        let superclass = class.superclass.as_ref().and_then(|superclass| {
+            let name = self.db.lookup_intern_type(superclass.item).name()?;
+            Some((superclass, by_name.get(&name)?))
+        });
+
+        let superclass_ok = match superclass {
+            Some((span, superclass)) => {
+                if let Some(start) = path.iter().position(|name| *name == superclass.name.item) {
+                    let chain = std::iter::once(&class.name.item)
+                        .chain(&path[start..])
+                        .chain(std::iter::once(&class.name.item))
+                        .map(|name| format!("`{}`", self.db.lookup_intern_name(*name)))
+                        .collect::<Vec<_>>();
+
+                    self.reporter.error(
+                        format!(
+                            "The class `{}` inherits from itself",
+                            self.db.lookup_intern_name(class.name.item)
+                        ),
+                        chain.join(" extends "),
+                        span.as_reporter_span(),
+                    );
+
+                    false
+                } else {
+                    path.push(class.name.item);
+                    let ok =
+                        self.resolve_class_after_superclass(superclass, by_name, path, resolved);
+                    path.pop();
+                    ok
+                }
+            }
+            None => true,
+        };
+
+        let ok = superclass_ok && self.resolve_class(class).is_ok();
+
+        resolved.insert(class.name.item, ok);
+
+        ok
+    }
+
     pub fn resolve_class(&mut self, class: &Class) -> Result<(), ()> {
+        self.report_lowering_errors(&class.ast_map);
+
         self.begin_scope();
 
         let mut poly_tvs = Vec::new();
@@ -20,11 +98,39 @@ where
             let tv = self.ctx.type_var();
 
             self.insert_type(&type_param.name, Type::Var(tv), TypeKind::Type)?;
+            self.resolve_bounds(type_param, tv)?;
 
             poly_tvs.push(tv);
         }
 
-        let mut fields = HashMap::new();
+        let superclass = match &class.superclass {
+            Some(superclass) => match self.resolve_superclass(superclass, &mut poly_tvs) {
+                Ok(superclass) => Some(superclass),
+                Err(()) => {
+                    self.end_scope();
+                    return Err(());
+                }
+            },
+            None => None,
+        };
+
+        let (mut fields, mut methods, mut instance_methods, superclass_name) = match &superclass {
+            Some(Type::Class {
+                name,
+                fields,
+                methods,
+                instance_methods,
+                ..
+            }) => (
+                fields.clone(),
+                methods.clone(),
+                instance_methods.clone(),
+                Some(*name),
+            ),
+            _ => (HashMap::new(), HashMap::new(), HashSet::new(), None),
+        };
+
+        let inherited = fields.clone();
 
         for field in &class.fields {
             if fields.contains_key(&field.item.property.item) {
@@ -35,7 +141,18 @@ where
 
                 let span = field.item.property.as_reporter_span();
 
-                self.reporter.error(msg, "", span);
+                match superclass_name {
+                    Some(superclass) if inherited.contains_key(&field.item.property.item) => {
+                        let note = format!(
+                            "`{}` is already a property of `{}`",
+                            self.db.lookup_intern_name(field.item.property.item),
+                            self.db.lookup_intern_name(superclass)
+                        );
+
+                        self.reporter.error(msg, note, span)
+                    }
+                    _ => self.reporter.error(msg, "", span),
+                }
 
                 continue;
             }
@@ -48,19 +165,105 @@ where
             fields.insert(field.item.property.item, ty);
         }
 
-        let mut methods = HashMap::new();
+        // The signatures can refer to their own class such as `fn new() -> Foo`, where the class
+        // is only known by its fields. Inference looks the methods up on the full class
+        self.ctx.insert_type(
+            class.name.item,
+            Type::Poly(
+                poly_tvs.clone(),
+                Box::new(Type::Class {
+                    name: class.name.item,
+                    type_args: poly_tvs.iter().map(|tv| Type::Var(*tv)).collect(),
+                    fields: fields.clone(),
+                    methods: methods.clone(),
+                    instance_methods: instance_methods.clone(),
+                    superclass: superclass.clone().map(Box::new),
+                }),
+            ),
+            TypeKind::Class,
+        );
 
         // forward declare methods
 
+        let mut declared = HashSet::new();
+
         for method in &class.methods {
-            self.add_item(method.name, ItemKind::Function, method.exported);
-            if let Ok(sig) = self.resolve_function_signature(method) {
+            // Methods live in the namespace of their class, so an override can share
+            // the name of the method it replaces
+            if !declared.insert(method.name.item) {
+                let msg = format!(
+                    "The method `{}` is defined multiple times",
+                    self.db.lookup_intern_name(method.name.item)
+                );
+
+                self.reporter.error(msg, "", method.name.as_reporter_span());
+
+                continue;
+            }
+
+            self.function_data
+                .entry(method.name.item)
+                .or_insert_with(FunctionData::new);
+
+            self.begin_scope();
+            let sig = self.resolve_function_signature(method);
+            self.end_scope();
+
+            if let Ok(sig) = sig {
+                if let (Some(overridden), Some(superclass)) =
+                    (methods.get(&method.name.item), superclass_name)
+                {
+                    let same_receiver =
+                        instance_methods.contains(&method.name.item) == method.self_param.is_some();
+
+                    if !same_receiver || !same_signature(overridden, &sig) {
+                        let name = self.db.lookup_intern_name(method.name.item);
+
+                        self.reporter.error(
+                            format!(
+                                "The method `{}` doesn't match the method it overrides",
+                                name
+                            ),
+                            format!(
+                                "An override of `{}::{}` must take and return the same types",
+                                self.db.lookup_intern_name(superclass),
+                                name
+                            ),
+                            method.name.as_reporter_span(),
+                        );
+
+                        continue;
+                    }
+                }
+
                 methods.insert(method.name.item, sig);
+
+                if method.self_param.is_some() {
+                    instance_methods.insert(method.name.item);
+                } else {
+                    instance_methods.remove(&method.name.item);
+                }
             } else {
                 continue;
             }
         }
 
+        let ty = Type::Poly(
+            poly_tvs.clone(),
+            Box::new(Type::Class {
+                name: class.name.item,
+                type_args: poly_tvs.into_iter().map(Type::Var).collect(),
+                fields,
+                methods,
+                instance_methods,
+                superclass: superclass.map(Box::new),
+            }),
+        );
+
+        // The methods can refer to their own class such as `Foo::new()`
+        self.ctx
+            .insert_type(class.name.item, ty.clone(), TypeKind::Class);
+
         for method in &class.methods {
             if let Err(_) = self.resolve_function(method) {
                 continue;
@@ -69,14 +272,119 @@ where
 
         self.end_scope();
 
-        self.insert_type(
-            &class.name,
-            Type::Poly(poly_tvs, Box::new(Type::Class { fields, methods })),
-            TypeKind::Class,
-        )?;
+        self.insert_type(&class.name, ty, TypeKind::Class)?;
 
         Ok(())
     }
+
+    /// Resolves the class after `extends` with its type arguments filled in.
+    /// Without type arguments the subclass is generic over the type params of its superclass
+    fn resolve_superclass(
+        &mut self,
+        superclass: &util::Span<TypeId>,
+        poly_tvs: &mut Vec<TypeVar>,
+    ) -> Result<Type, ()> {
+        let (name, type_args) = match self.db.lookup_intern_type(superclass.item) {
+            hir::Type::Ident(name) => (name, Vec::new()),
+            hir::Type::Poly { name, type_args } => (name, type_args),
+            _ => {
+                self.reporter.error(
+                    "Only classes can be extended",
+                    "",
+                    superclass.as_reporter_span(),
+                );
+
+                return Err(());
+            }
+        };
+
+        let (tvs, ty) = match self.ctx.get_type(&name) {
+            Some(Type::Poly(tvs, ty)) => (tvs, *ty),
+            Some(ty) => (Vec::new(), ty),
+            None => {
+                let msg = format!(
+                    "Use of undefined type `{}`",
+                    self.db.lookup_intern_name(name)
+                );
+
+                self.reporter.error(msg, "", superclass.as_reporter_span());
+
+                return Err(());
+            }
+        };
+
+        if !matches!(ty, Type::Class { .. }) {
+            let msg = format!("`{}` is not a class", self.db.lookup_intern_name(name));
+
+            self.reporter.error(msg, "", superclass.as_reporter_span());
+
+            return Err(());
+        }
+
+        let mut mappings = HashMap::new();
+
+        if type_args.is_empty() {
+            poly_tvs.extend(tvs);
+        } else {
+            for (tv, arg) in tvs.into_iter().zip(&type_args) {
+                mappings.insert(tv, self.resolve_type(arg)?);
+            }
+        }
+
+        Ok(ty.subst(&mappings))
+    }
+}
+
+/// Whether an overriding method has the same signature as the method it overrides.
+/// The type params of generic methods are compared by position
+pub(super) fn same_signature(overridden: &Type, method: &Type) -> bool {
+    match (overridden, method) {
+        (Type::Poly(overridden_tvs, overridden), Type::Poly(tvs, method))
+            if overridden_tvs.len() == tvs.len() =>
+        {
+            let mappings = tvs
+                .iter()
+                .zip(overridden_tvs)
+                .map(|(tv, overridden_tv)| (*tv, Type::Var(*overridden_tv)))
+                .collect();
+
+            without_methods(overridden) == without_methods(&method.subst(&mappings))
+        }
+        (overridden, method) => without_methods(overridden) == without_methods(method),
+    }
+}
+
+/// Classes are compared by their fields as a signature might only know its own class by them
+fn without_methods(ty: &Type) -> Type {
+    match ty {
+        Type::App(types) => Type::App(types.iter().map(without_methods).collect()),
+        Type::Tuple(types) => Type::Tuple(types.iter().map(without_methods).collect()),
+        Type::Poly(tvs, ty) => Type::Poly(tvs.clone(), Box::new(without_methods(ty))),
+        Type::Con(TypeCon::Array { ty, size }) => Type::Con(TypeCon::Array {
+            ty: Box::new(without_methods(ty)),
+            size: *size,
+        }),
+        Type::Con(TypeCon::Optional(ty)) => {
+            Type::Con(TypeCon::Optional(Box::new(without_methods(ty))))
+        }
+        Type::Class {
+            name,
+            type_args,
+            fields,
+            ..
+        } => Type::Class {
+            name: *name,
+            type_args: type_args.iter().map(without_methods).collect(),
+            fields: fields
+                .iter()
+                .map(|(field, ty)| (*field, without_methods(ty)))
+                .collect(),
+            methods: HashMap::new(),
+            instance_methods: HashSet::new(),
+            superclass: None,
+        },
+        ty => ty.clone(),
+    }
 }
 
 #[cfg(test)]
@@ -86,4 +394,20 @@ mod tests {
     create_test!(basic_class);
 
     create_test!(exported_class);
+
+    create_test!(inheritance_cycle, is_err);
+
+    create_test!(inherited_duplicate_field, is_err);
+
+    create_test!(override_signature_mismatch, is_err);
+
+    create_test!(self_in_static_method, is_err);
+
+    create_test!(self_outside_class, is_err);
+
+    create_test!(static_call_of_instance_method, is_err);
+
+    create_test!(field_of_call);
+
+    create_test!(imported_static_method);
 }
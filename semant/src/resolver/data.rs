@@ -3,7 +3,7 @@ use crate::{
     infer::{StackedMap, Type, TypeCon},
     util, Ctx, HirDatabase,
 };
-use errors::Reporter;
+use errors::{Diagnostic, FileId, Reporter};
 use hir::PatId;
 use std::collections::{HashMap, HashSet};
 
@@ -33,6 +33,8 @@ pub struct Resolver {
     pub(crate) items: HashSet<hir::NameId>,
     pub(crate) exported_items: HashSet<hir::NameId>,
     pub(crate) function_data: HashMap<hir::NameId, FunctionData>,
+    /// The warnings found while resolving, they don't stop the file from being checked
+    pub(crate) warnings: Vec<Diagnostic<FileId>>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -46,31 +48,82 @@ pub enum ItemKind {
     Class,
     Function,
     Enum,
+    Trait,
 }
 /// Information at a local variable declared in a block
 #[derive(Copy, Debug, Clone, PartialEq, Eq)]
 pub struct LocalData {
     state: util::Span<State>,
     reads: usize,
+    /// How many closures enclosed the local when it was declared
+    closure_depth: usize,
+    /// The name in the pattern that declared the local
+    binding: util::Span<NameId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct FunctionData {
     pub(crate) scopes: StackedMap<hir::NameId, LocalData>,
+    /// The closures that are currently being resolved, innermost last
+    closures: Vec<hir::ExprId>,
+    /// The outer locals each closure reads, in the order they are first read
+    pub(crate) captures: HashMap<hir::ExprId, Vec<hir::NameId>>,
+    /// The binding that each use of a local refers to
+    pub(crate) bindings: HashMap<util::Span<NameId>, util::Span<NameId>>,
+    /// The loops that are currently being resolved, innermost last
+    loops: Vec<LoopData>,
+}
+
+/// A loop that a `break` or `continue` can target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LoopData {
+    pub(crate) label: Option<hir::NameId>,
+    /// Only a `loop` can be broken out of with a value
+    pub(crate) is_loop: bool,
+    /// The closure the loop is in, as a `break` can't leave a closure
+    closure: Option<hir::ExprId>,
 }
 
 impl FunctionData {
     pub fn new() -> Self {
         Self {
             scopes: StackedMap::new(),
+            closures: Vec::new(),
+            captures: HashMap::new(),
+            bindings: HashMap::new(),
+            loops: Vec::new(),
         }
     }
 }
 
 impl Resolver {
+    pub fn warnings(&self) -> &[Diagnostic<FileId>] {
+        &self.warnings
+    }
+
     pub fn has_export(&self, id: &hir::NameId) -> bool {
         self.exported_items.get(id).is_some()
     }
+
+    /// The outer locals that a closure in the given function captures
+    pub fn captures(&self, fn_name: &hir::NameId, closure: &hir::ExprId) -> &[hir::NameId] {
+        self.function_data
+            .get(fn_name)
+            .and_then(|data| data.captures.get(closure))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The binding that a use of a local in the given function refers to
+    pub fn binding(
+        &self,
+        fn_name: &hir::NameId,
+        name: &util::Span<NameId>,
+    ) -> Option<util::Span<NameId>> {
+        self.function_data
+            .get(fn_name)
+            .and_then(|data| data.bindings.get(name))
+            .copied()
+    }
 }
 
 impl<'a, DB> ResolverDataCollector<&'a DB>
@@ -84,6 +137,7 @@ where
                 items: self.items,
                 exported_items: self.exported_items,
                 function_data: self.function_data,
+                warnings: Vec::new(),
             },
             self.reporter,
         )
@@ -96,6 +150,14 @@ where
         self.ctx.end_scope();
     }
 
+    /// Report the errors found while lowering an item
+    pub(crate) fn report_lowering_errors(&mut self, ast_map: &hir::FunctionAstMap) {
+        for error in ast_map.lowering_errors() {
+            self.reporter
+                .error(error.item.clone(), "", error.as_reporter_span());
+        }
+    }
+
     pub(crate) fn insert_type(
         &mut self,
         name_id: &util::Span<NameId>,
@@ -142,7 +204,7 @@ where
                 ItemKind::Function => {
                     self.function_data.insert(name_id.item, FunctionData::new());
                 }
-                ItemKind::Class | ItemKind::Enum => {}
+                ItemKind::Class | ItemKind::Enum | ItemKind::Trait => {}
             }
         }
     }
@@ -157,6 +219,17 @@ where
         if let Some(state) = data.scopes.get_mut(&name.item) {
             state.state = util::Span::new(State::Read, name.start(), name.end());
             state.reads += 1;
+            data.bindings.insert(*name, state.binding);
+
+            // Every closure entered after the local was declared captures it
+            for closure in &data.closures[state.closure_depth..] {
+                let captures = data.captures.entry(*closure).or_insert_with(Vec::new);
+
+                if !captures.contains(&name.item) {
+                    captures.push(name.item);
+                }
+            }
+
             return Ok(());
         } //check for ident name in function/local scope
 
@@ -164,16 +237,29 @@ where
         // function names when called are stored as
         // and IdentExpr followed by the args
         // so to resolve them we need to look at the file ctx
-        if !self.items.contains(&name.item) {
-            let msg = format!(
-                "Use of undefined variable `{}`",
-                self.db.lookup_intern_name(name.item)
+        if self.items.contains(&name.item) {
+            return Ok(());
+        }
+
+        let span = (name.start().to_usize(), name.end().to_usize());
+
+        if self.db.lookup_intern_name(name.item).as_str() == "self" {
+            self.reporter.error(
+                "`self` can only be used in instance methods",
+                "Add `self` as the first param to make this an instance method",
+                span,
             );
 
-            self.reporter
-                .error(msg, "", (name.start().to_usize(), name.end().to_usize()))
+            return Err(());
         }
 
+        let msg = format!(
+            "Use of undefined variable `{}`",
+            self.db.lookup_intern_name(name.item)
+        );
+
+        self.reporter.error(msg, "", span);
+
         Err(())
     }
 
@@ -206,17 +292,36 @@ where
         }
 
         let function_data = self.function_data.get_mut(&fn_name).unwrap();
+        let closure_depth = function_data.closures.len();
+
         function_data.scopes.insert(
             param.item,
             LocalData {
                 state: util::Span::new(State::Declared, param.start(), param.end()),
                 reads: 0,
+                closure_depth,
+                binding: param,
             },
         );
 
         Ok(())
     }
 
+    /// `self` isn't reported when it's unused as the method might not need it
+    pub(crate) fn add_self_param(&mut self, fn_name: NameId, param: util::Span<NameId>) {
+        let function_data = self.function_data.get_mut(&fn_name).unwrap();
+
+        function_data.scopes.insert(
+            param.item,
+            LocalData {
+                state: util::Span::new(State::Read, param.start(), param.end()),
+                reads: 1,
+                closure_depth: 0,
+                binding: param,
+            },
+        );
+    }
+
     pub(crate) fn local_is_declared(&self, fn_name: &NameId, name: &util::Span<NameId>) -> bool {
         if let Some(state) = self.function_data[fn_name].scopes.get(&name.item) {
             state.state.item == State::Declared
@@ -227,16 +332,109 @@ where
 
     pub(crate) fn define_local(&mut self, fn_name: &NameId, name: &util::Span<NameId>) {
         let function_data = self.function_data.get_mut(&fn_name).unwrap();
+        let closure_depth = function_data.closures.len();
 
         function_data.scopes.update(
             name.item,
             LocalData {
                 state: util::Span::new(State::Defined, name.start(), name.end()),
                 reads: 0,
+                closure_depth,
+                binding: *name,
             },
         );
     }
 
+    /// Locals declared after this are local to the closure
+    pub(crate) fn begin_closure(&mut self, fn_name: NameId, closure: hir::ExprId) {
+        let function_data = self.function_data.get_mut(&fn_name).unwrap();
+
+        function_data.closures.push(closure);
+        function_data
+            .captures
+            .entry(closure)
+            .or_insert_with(Vec::new);
+        function_data.scopes.begin_scope();
+    }
+
+    pub(crate) fn end_closure(&mut self, fn_name: NameId) {
+        self.end_function_scope(fn_name);
+
+        let function_data = self.function_data.get_mut(&fn_name).unwrap();
+
+        function_data.closures.pop();
+    }
+
+    pub(crate) fn begin_loop(&mut self, fn_name: NameId, label: Option<NameId>, is_loop: bool) {
+        let function_data = self.function_data.get_mut(&fn_name).unwrap();
+
+        let closure = function_data.closures.last().copied();
+
+        function_data.loops.push(LoopData {
+            label,
+            is_loop,
+            closure,
+        });
+    }
+
+    pub(crate) fn end_loop(&mut self, fn_name: NameId) {
+        let function_data = self.function_data.get_mut(&fn_name).unwrap();
+
+        function_data.loops.pop();
+    }
+
+    /// Find the loop that a `break` or `continue` targets, which is the innermost loop
+    /// unless a label is given
+    pub(crate) fn resolve_loop_target(
+        &mut self,
+        fn_name: NameId,
+        keyword: &str,
+        label: &Option<util::Span<NameId>>,
+        span: (usize, usize),
+    ) -> Result<LoopData, ()> {
+        let function_data = &self.function_data[&fn_name];
+
+        let closure = function_data.closures.last().copied();
+
+        let mut loops = function_data
+            .loops
+            .iter()
+            .rev()
+            .take_while(|data| data.closure == closure);
+
+        let target = match label {
+            Some(label) => loops.find(|data| data.label == Some(label.item)),
+            None => loops.next(),
+        };
+
+        if let Some(target) = target {
+            return Ok(*target);
+        }
+
+        let in_closure = function_data.loops.last().is_some();
+
+        match label {
+            Some(label) => {
+                let msg = format!(
+                    "Use of undeclared label `{}`",
+                    self.db.lookup_intern_name(label.item)
+                );
+
+                self.reporter.error(msg, "", label.as_reporter_span())
+            }
+            None if in_closure => self.reporter.error(
+                format!("`{}` inside of a closure", keyword),
+                format!("A closure can't `{}` the loop around it", keyword),
+                span,
+            ),
+            None => self
+                .reporter
+                .error(format!("`{}` outside of a loop", keyword), "", span),
+        }
+
+        Err(())
+    }
+
     pub(crate) fn begin_function_scope(&mut self, fn_name: NameId) {
         let function_data = self.function_data.get_mut(&fn_name).unwrap();
 
@@ -247,7 +445,7 @@ where
         let function_data = self.function_data.get_mut(&fn_name).unwrap();
 
         for (name, state) in function_data.scopes.end_scope_iter() {
-            let LocalData { reads, state } = state;
+            let LocalData { reads, state, .. } = state;
 
             if reads == 0 || state.item == State::Declared {
                 let msg = format!("Unused variable `{}`", self.db.lookup_intern_name(name));
@@ -281,8 +479,23 @@ where
                 self.binding_error = true;
 
                 let mut error_occurred = false;
+                let mut seen_rest = false;
 
                 for pat in patterns {
+                    if let hir::Pattern::Rest = ast_map.pat(&pat.item) {
+                        if seen_rest {
+                            self.reporter.error(
+                                "`..` can only be used once per tuple pattern",
+                                "",
+                                pat.as_reporter_span(),
+                            );
+                            error_occurred = true;
+                        }
+
+                        seen_rest = true;
+                        continue;
+                    }
+
                     if let Err(()) = self.resolve_pattern(fn_name, pat, ast_map) {
                         error_occurred = true;
                         continue;
@@ -295,12 +508,211 @@ where
                     return Err(());
                 }
             }
-            hir::Pattern::Placeholder | hir::Pattern::Literal(_) => {}
+            hir::Pattern::Variant { def, variant, pat } => {
+                match self.ctx.get_type(&def.item).map(Type::unwrap_poly) {
+                    Some(Type::Enum(_, _, variants)) => {
+                        if !variants.contains_key(&variant.item) {
+                            let msg = format!(
+                                "Unknown enum variant `{}`",
+                                self.db.lookup_intern_name(variant.item)
+                            );
+
+                            self.reporter.error(msg, "", variant.as_reporter_span());
+
+                            return Err(());
+                        }
+                    }
+                    Some(_) => {
+                        let msg =
+                            format!("`{}` is not an enum", self.db.lookup_intern_name(def.item));
+
+                        self.reporter.error(msg, "", def.as_reporter_span());
+
+                        return Err(());
+                    }
+                    None => {
+                        let msg =
+                            format!("Unknown enum `{}`", self.db.lookup_intern_name(def.item));
+
+                        self.reporter.error(msg, "", def.as_reporter_span());
+
+                        return Err(());
+                    }
+                }
+
+                if let Some(pat) = pat {
+                    self.resolve_pattern(fn_name, pat, ast_map)?;
+                }
+            }
+            hir::Pattern::Record { def, fields, rest } => {
+                let class_fields = match self.ctx.get_type(&def.item) {
+                    Some(Type::Poly(_, inner)) => match *inner {
+                        Type::Class { fields, .. } => fields,
+                        _ => {
+                            let msg = format!(
+                                "`{}` is not a class",
+                                self.db.lookup_intern_name(def.item)
+                            );
+
+                            self.reporter.error(msg, "", def.as_reporter_span());
+
+                            return Err(());
+                        }
+                    },
+                    Some(_) => {
+                        let msg =
+                            format!("`{}` is not a class", self.db.lookup_intern_name(def.item));
+
+                        self.reporter.error(msg, "", def.as_reporter_span());
+
+                        return Err(());
+                    }
+                    None => {
+                        let msg =
+                            format!("Unknown class `{}`", self.db.lookup_intern_name(def.item));
+
+                        self.reporter.error(msg, "", def.as_reporter_span());
+
+                        return Err(());
+                    }
+                };
+
+                self.binding_error = true;
+
+                let mut error_occurred = false;
+
+                for (field, pat) in fields {
+                    if !class_fields.contains_key(&field.item) {
+                        let msg =
+                            format!("Unknown field `{}`", self.db.lookup_intern_name(field.item));
+
+                        self.reporter.error(msg, "", field.as_reporter_span());
+
+                        error_occurred = true;
+                    }
+
+                    if let Err(()) = self.resolve_pattern(fn_name, pat, ast_map) {
+                        error_occurred = true;
+                    }
+                }
+
+                self.binding_error = false;
+
+                if !rest {
+                    let mut missing = class_fields
+                        .keys()
+                        .filter(|name| fields.iter().all(|(field, _)| field.item != **name))
+                        .map(|name| format!("`{}`", self.db.lookup_intern_name(*name)))
+                        .collect::<Vec<_>>();
+
+                    if !missing.is_empty() {
+                        missing.sort();
+
+                        let msg = format!("Pattern does not mention {}", missing.join(", "));
+
+                        self.reporter.error(
+                            msg,
+                            "Use `..` to ignore the other fields",
+                            pat_id.as_reporter_span(),
+                        );
+
+                        error_occurred = true;
+                    }
+                }
+
+                if error_occurred {
+                    return Err(());
+                }
+            }
+            hir::Pattern::Rest => {
+                self.reporter.error(
+                    "`..` can only be used in tuple and class patterns",
+                    "",
+                    pat_id.as_reporter_span(),
+                );
+
+                return Err(());
+            }
+            hir::Pattern::Placeholder | hir::Pattern::Literal(_) | hir::Pattern::Missing => {}
         }
 
         Ok(())
     }
 
+    /// Resolve the alternatives of an or-pattern.
+    /// The names are bound by the first alternative and the others must bind the same names
+    pub(crate) fn resolve_alternatives(
+        &mut self,
+        fn_name: NameId,
+        pats: &[util::Span<PatId>],
+        ast_map: &hir::FunctionAstMap,
+    ) -> Result<(), ()> {
+        let (first, rest) = match pats.split_first() {
+            Some(split) => split,
+            None => return Ok(()),
+        };
+
+        self.resolve_pattern(fn_name, first, ast_map)?;
+
+        let expected = ast_map.pattern_bindings(&first.item);
+
+        let mut error_occurred = false;
+
+        for pat in rest {
+            // The bindings of the other alternatives are only checked and then thrown away
+            self.function_data
+                .get_mut(&fn_name)
+                .unwrap()
+                .scopes
+                .begin_scope();
+
+            let result = self.resolve_pattern(fn_name, pat, ast_map);
+
+            self.function_data
+                .get_mut(&fn_name)
+                .unwrap()
+                .scopes
+                .end_scope();
+
+            if result.is_err() {
+                error_occurred = true;
+                continue;
+            }
+
+            let bindings = ast_map.pattern_bindings(&pat.item);
+
+            for name in &expected {
+                if bindings.iter().all(|binding| binding.item != name.item) {
+                    let msg = format!(
+                        "`{}` is not bound in every alternative",
+                        self.db.lookup_intern_name(name.item)
+                    );
+
+                    self.reporter.error(msg, "", pat.as_reporter_span());
+                    error_occurred = true;
+                }
+            }
+
+            for binding in &bindings {
+                if expected.iter().all(|name| name.item != binding.item) {
+                    let msg = format!(
+                        "`{}` is not bound in every alternative",
+                        self.db.lookup_intern_name(binding.item)
+                    );
+
+                    self.reporter.error(msg, "", binding.as_reporter_span());
+                    error_occurred = true;
+                }
+            }
+        }
+
+        if error_occurred {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
     pub(crate) fn resolve_type(&mut self, id: &util::Span<TypeId>) -> Result<Type, ()> {
         let ty = self.db.lookup_intern_type(id.item);
 
@@ -318,6 +730,9 @@ where
                 ty: Box::new(self.resolve_type(&ty)?),
                 size,
             })),
+            hir::Type::Optional(ty) => Ok(Type::Con(TypeCon::Optional(Box::new(
+                self.resolve_type(&ty)?,
+            )))),
             hir::Type::FnType { params, ret } => {
                 let mut signature = vec![];
 
@@ -365,13 +780,52 @@ where
                     return Err(());
                 }
 
+                let mut args = Vec::new();
+
                 for arg in &type_args {
-                    if let Err(()) = self.resolve_type(arg) {
-                        continue;
+                    args.push(self.resolve_type(arg)?);
+                }
+
+                let (tvs, inner) = match ty {
+                    Type::Poly(tvs, inner) if !tvs.is_empty() => (tvs, inner),
+                    _ => {
+                        let span = (id.start().to_usize(), id.end().to_usize());
+                        self.reporter.error(
+                            format!(
+                                "`{}` doesn't take type arguments",
+                                self.db.lookup_intern_name(name)
+                            ),
+                            "",
+                            span,
+                        );
+
+                        return Err(());
                     }
+                };
+
+                if tvs.len() != args.len() {
+                    let span = (id.start().to_usize(), id.end().to_usize());
+                    self.reporter.error(
+                        format!(
+                            "Wrong number of type arguments for `{}`",
+                            self.db.lookup_intern_name(name)
+                        ),
+                        format!("Expected {} but found {}", tvs.len(), args.len()),
+                        span,
+                    );
+
+                    return Err(());
+                }
+
+                let mut mappings = HashMap::new();
+
+                for ((tv, arg), arg_id) in tvs.iter().zip(args).zip(&type_args) {
+                    self.check_bounds(&arg, tv, arg_id.as_reporter_span())?;
+
+                    mappings.insert(*tv, arg);
                 }
 
-                Ok(ty)
+                Ok(inner.subst(&mappings))
             }
             hir::Type::Ident(name) => {
                 if let Some(ty) = self.ctx.get_type(&name) {
@@ -403,6 +857,8 @@ where
 
                 Err(())
             }
+            // The parser has already reported the error
+            hir::Type::Missing => Ok(Type::Var(self.ctx.type_var())),
         }
     }
 }
@@ -11,6 +11,8 @@ where
     DB: HirDatabase,
 {
     pub fn resolve_enum(&mut self, enum_def: &Enum) -> Result<(), ()> {
+        self.report_lowering_errors(&enum_def.ast_map);
+
         self.begin_scope();
         let mut poly_tvs = Vec::new();
 
@@ -20,6 +22,7 @@ where
             let tv = self.ctx.type_var();
 
             self.insert_type(&type_param.name, Type::Var(tv), TypeKind::Type)?;
+            self.resolve_bounds(type_param, tv)?;
 
             poly_tvs.push(tv);
         }
@@ -54,7 +57,18 @@ where
 
         self.end_scope();
 
-        self.insert_type(&enum_def.name, Type::Enum(variants), TypeKind::Enum)?;
+        self.insert_type(
+            &enum_def.name,
+            Type::Poly(
+                poly_tvs.clone(),
+                Box::new(Type::Enum(
+                    enum_def.name.item,
+                    poly_tvs.into_iter().map(Type::Var).collect(),
+                    variants,
+                )),
+            ),
+            TypeKind::Enum,
+        )?;
 
         Ok(())
     }
@@ -68,5 +82,9 @@ mod tests {
 
     create_test!(enum_dup_variant, is_err);
 
+    create_test!(imported_enum_variant);
+
+    create_test!(variant_with_two_values, is_err);
+
     create_test!(recursive_enum, is_err);
 }
@@ -12,8 +12,6 @@ where
     DB: HirDatabase,
 {
     pub fn resolve_function_signature(&mut self, function: &Function) -> Result<Type, ()> {
-        let name = function.name;
-
         let mut poly_tvs = Vec::new();
 
         for type_param in &function.type_params {
@@ -22,19 +20,16 @@ where
             let tv = self.ctx.type_var();
 
             self.insert_type(&type_param.name, Type::Var(tv), TypeKind::Type)?;
+            self.resolve_bounds(type_param, tv)?;
 
             poly_tvs.push(tv);
         }
 
         let mut signature = Vec::new();
 
-        self.begin_function_scope(name.item);
-        self.begin_function_scope(name.item);
         for param in &function.params {
             let param = function.ast_map.param(&param.item);
 
-            self.resolve_pattern(function.name.item, &param.pat, &function.ast_map)?;
-
             signature.push(self.resolve_type(&param.ty)?);
         }
 
@@ -44,8 +39,6 @@ where
             signature.push(Type::Con(TypeCon::Void))
         }
 
-        self.end_function_scope(name.item);
-
         Ok(Type::Poly(poly_tvs, Box::new(Type::App(signature))))
     }
 
@@ -56,6 +49,20 @@ where
 
         let signature = self.resolve_function_signature(function)?;
 
+        self.begin_function_scope(name.item);
+
+        self.report_lowering_errors(&function.ast_map);
+
+        if let Some(self_param) = function.self_param {
+            self.add_self_param(name.item, self_param);
+        }
+
+        for param in &function.params {
+            let param = function.ast_map.param(&param.item);
+
+            self.resolve_pattern(name.item, &param.pat, &function.ast_map)?;
+        }
+
         if let Some(body) = &function.body {
             for stmt in body {
                 if self
@@ -110,7 +117,85 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::create_test;
+    use crate::{
+        create_test,
+        db::InternDatabase,
+        hir::{self, Name},
+        resolver::tests::MockDatabaseImpl,
+        HirDatabase,
+    };
+    use errors::db::FileDatabase;
+    use std::io::{self, Write};
 
     create_test!(import_fn_as_type, is_err);
+
+    create_test!(closure_undefined_variable, is_err);
+
+    create_test!(record_pattern_missing_field, is_err);
+
+    create_test!(tuple_pattern_double_rest, is_err);
+
+    create_test!(or_pattern_missing_binding, is_err);
+
+    create_test!(integer_literal_overflow, is_err);
+
+    create_test!(invalid_number_suffix, is_err);
+
+    create_test!(negative_literal_overflow, is_err);
+
+    create_test!(array_size_not_literal, is_err);
+
+    create_test!(do_while_body_local_in_cond, is_err);
+
+    create_test!(break_outside_loop, is_err);
+
+    create_test!(undeclared_label, is_err);
+
+    create_test!(break_value_in_while, is_err);
+
+    create_test!(break_in_closure, is_err);
+
+    create_test!(if_let_binding_scope, is_err);
+
+    create_test!(type_arg_count, is_err);
+
+    create_test!(type_args_on_non_generic, is_err);
+
+    #[test]
+    fn closure_captures() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("main.tox");
+
+        write!(
+            &mut std::fs::File::create(&path)?,
+            "export fn main() -> i32 {{ let a = 1; let outer = || {{ let b = 2; let inner = || {{ return a + b; }}; return inner(); }}; return outer(); }}"
+        )?;
+
+        let db = MockDatabaseImpl::default();
+        let handle = db.intern_file(path);
+
+        let resolver = db.resolve_source_file(handle).unwrap();
+        let program = db.lower(handle);
+        let main = &program.functions[0];
+
+        let mut captures = main
+            .ast_map
+            .exprs()
+            .filter(|(_, expr)| match expr {
+                hir::Expr::Closure { .. } => true,
+                _ => false,
+            })
+            .map(|(id, _)| resolver.captures(&main.name.item, id).to_vec())
+            .collect::<Vec<_>>();
+
+        captures.sort_by_key(Vec::len);
+
+        let a = db.intern_name(Name::new("a"));
+        let b = db.intern_name(Name::new("b"));
+
+        // `b` is declared inside of `outer` so only `inner` captures it
+        assert_eq!(captures, vec![vec![a], vec![a, b]]);
+
+        Ok(())
+    }
 }
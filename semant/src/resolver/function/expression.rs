@@ -1,5 +1,5 @@
 use crate::{
-    hir::{Expr, ExprId, FunctionAstMap, NameId},
+    hir::{BlockId, Expr, ExprId, FunctionAstMap, NameId},
     resolver::data::ResolverDataCollector,
     util, HirDatabase,
 };
@@ -8,61 +8,13 @@ impl<'a, DB> ResolverDataCollector<&'a DB>
 where
     DB: HirDatabase,
 {
-    // pub(crate) fn resolve_fields<'b>(
-    //     &mut self,
-    //     field: &util::Span<NameId>,
-    //     ty: crate::infer::Type,
-    //     mut fields: impl Iterator<Item = &'b util::Span<NameId>>,
-    // ) -> Result<(), ()> {
-    //     println!("{:?}", ty);
-    //     match ty {
-    //         crate::infer::Type::Poly(_, ref inner) => match &**inner {
-    //             crate::infer::Type::Class {
-    //                 fields: def_fields, ..
-    //             } => {
-    //                 if !def_fields.contains_key(&field.item) {
-    //                     let msg =
-    //                         format!("Unknown field `{}`", self.db.lookup_intern_name(field.item));
-
-    //                     self.reporter.error(msg, "", field.as_reporter_span());
-    //                     return Err(());
-    //                 }
-
-    //                 if let Some(field) = fields.next() {
-    //                     self.resolve_fields(field, def_fields[&field.item].clone(), fields)?;
-    //                 }
-    //             }
-    //             _ => {
-    //                 let msg = format!(
-    //                     "`{}` doesn't have fields",
-    //                     self.db.lookup_intern_name(field.item)
-    //                 );
-
-    //                 self.reporter.error(msg, "", field.as_reporter_span());
-
-    //                 return Err(());
-    //             }
-    //         },
-    //         _ => {
-    //             let msg = format!(
-    //                 "`{}` doesn't have fields",
-    //                 self.db.lookup_intern_name(field.item)
-    //             );
-
-    //             self.reporter.error(msg, "", field.as_reporter_span());
-    //             return Err(());
-    //         }
-    //     }
-
-    //     Ok(())
-    // }
     pub(crate) fn resolve_expression(
         &mut self,
         fn_name: &util::Span<NameId>,
-        expr: &ExprId,
+        id: &ExprId,
         ast_map: &FunctionAstMap,
     ) -> Result<(), ()> {
-        let expr = ast_map.expr(expr);
+        let expr = ast_map.expr(id);
 
         match expr {
             Expr::Array(exprs) | Expr::Tuple(exprs) => {
@@ -70,6 +22,9 @@ where
                     self.resolve_expression(fn_name, id, ast_map)?;
                 }
             }
+            Expr::ArrayRepeat { value, .. } => {
+                self.resolve_expression(fn_name, value, ast_map)?;
+            }
             Expr::Binary { lhs, rhs, .. } => {
                 self.resolve_expression(fn_name, lhs, ast_map)?;
                 self.resolve_expression(fn_name, rhs, ast_map)?;
@@ -85,7 +40,57 @@ where
 
                 self.end_function_scope(fn_name.item);
             }
-            Expr::Break | Expr::Continue => {}
+            Expr::Break { label, expr } => {
+                let range = ast_map.expr_span(id);
+                let span = (range.start().to_usize(), range.end().to_usize());
+
+                let target = self.resolve_loop_target(fn_name.item, "break", label, span)?;
+
+                if let Some(expr) = expr {
+                    if !target.is_loop {
+                        self.reporter.error(
+                            "`break` with a value is only allowed inside of `loop`",
+                            "`while` and `do while` loops can't be broken out of with a value",
+                            span,
+                        );
+                    }
+
+                    self.resolve_expression(fn_name, expr, ast_map)?;
+                }
+            }
+            Expr::Continue { label } => {
+                let range = ast_map.expr_span(id);
+                let span = (range.start().to_usize(), range.end().to_usize());
+
+                self.resolve_loop_target(fn_name.item, "continue", label, span)?;
+            }
+            Expr::Missing => {}
+            Expr::Closure {
+                params,
+                returns,
+                body,
+            } => {
+                for param in params {
+                    if let Some(ty) = &param.ty {
+                        let _ = self.resolve_type(ty);
+                    }
+                }
+
+                if let Some(returns) = returns {
+                    let _ = self.resolve_type(returns);
+                }
+
+                self.begin_closure(fn_name.item, *id);
+
+                let result = params
+                    .iter()
+                    .try_for_each(|param| self.resolve_pattern(fn_name.item, &param.pat, ast_map))
+                    .and_then(|_| self.resolve_expression(fn_name, body, ast_map));
+
+                self.end_closure(fn_name.item);
+
+                result?;
+            }
             Expr::Call {
                 callee,
                 args,
@@ -97,10 +102,66 @@ where
                     self.resolve_expression(fn_name, id, ast_map)?;
                 }
 
+                match ast_map.expr(callee) {
+                    Expr::Path { def, name } if self.ctx.is_enum(&def.item) && args.len() != 1 => {
+                        let msg = format!(
+                            "The enum variant `{}` takes a single value but {} were supplied",
+                            self.db.lookup_intern_name(name.item),
+                            args.len()
+                        );
+
+                        let range = ast_map.expr_span(id);
+
+                        self.reporter.error(
+                            msg,
+                            "",
+                            (range.start().to_usize(), range.end().to_usize()),
+                        );
+
+                        return Err(());
+                    }
+                    _ => {}
+                }
+
+                let type_params = match ast_map.expr(callee) {
+                    Expr::Ident(name) => match self.ctx.get_type(&name.item) {
+                        Some(crate::infer::Type::Poly(tvs, _)) => tvs,
+                        _ => Vec::new(),
+                    },
+                    _ => Vec::new(),
+                };
+
+                for (i, ty) in type_args.item.iter().enumerate() {
+                    let ty_span = ty.as_reporter_span();
+
+                    let ty = match self.resolve_type(ty) {
+                        Ok(ty) => ty,
+                        Err(()) => continue,
+                    };
+
+                    if let Some(tv) = type_params.get(i) {
+                        self.check_bounds(&ty, tv, ty_span)?;
+                    }
+                }
+            }
+            Expr::MethodCall {
+                receiver,
+                args,
+                type_args,
+                ..
+            } => {
+                // The method is looked up on the type of the receiver during type checking
+                self.resolve_expression(fn_name, receiver, ast_map)?;
+
+                for id in args {
+                    self.resolve_expression(fn_name, id, ast_map)?;
+                }
+
                 type_args.item.iter().for_each(|ty| {
                     let _ = self.resolve_type(ty);
                 })
             }
+            Expr::Path { def, name } => self.resolve_path(def, name)?,
             Expr::Cast { expr, ty } => {
                 self.resolve_expression(fn_name, expr, ast_map)?;
                 let _ = self.resolve_type(ty);
@@ -131,25 +192,37 @@ where
 
                 self.resolve_local(&fn_name.item, name)?
             }
+            Expr::Next(iter) => self.resolve_local(&fn_name.item, iter)?,
             Expr::Index { base, index } => {
                 self.resolve_expression(fn_name, base, ast_map)?;
                 self.resolve_expression(fn_name, index, ast_map)?;
             }
-            Expr::While { cond, body } => {
+            Expr::While {
+                label,
+                cond,
+                body,
+                step,
+            } => {
                 self.resolve_expression(fn_name, cond, ast_map)?;
+                self.resolve_loop_body(fn_name, label, false, body, ast_map)?;
 
-                let block = ast_map.block(body);
-
-                self.begin_function_scope(fn_name.item);
-
-                for id in &block.0 {
-                    self.resolve_statement(fn_name, id, ast_map)?
+                if let Some(step) = step {
+                    self.resolve_expression(fn_name, step, ast_map)?;
                 }
+            }
+            Expr::DoWhile { label, body, cond } => {
+                self.resolve_loop_body(fn_name, label, false, body, ast_map)?;
 
-                self.end_function_scope(fn_name.item);
+                // Locals declared in the body are out of scope in the condition
+                self.resolve_expression(fn_name, cond, ast_map)?;
+            }
+            Expr::Loop { label, body } => {
+                self.resolve_loop_body(fn_name, label, true, body, ast_map)?;
             }
             Expr::Literal(_) => {}
-            Expr::Paren(expr) => self.resolve_expression(fn_name, expr, ast_map)?,
+            Expr::Paren(expr) | Expr::Try(expr) => {
+                self.resolve_expression(fn_name, expr, ast_map)?
+            }
 
             Expr::Unary { expr, .. } => self.resolve_expression(fn_name, expr, ast_map)?,
             Expr::Return(expr) => {
@@ -161,11 +234,17 @@ where
                 self.resolve_expression(fn_name, expr, ast_map)?;
 
                 for arm in arms {
-                    for pat_id in &arm.pats {
-                        self.resolve_pattern(fn_name.item, pat_id, ast_map)?;
+                    self.begin_function_scope(fn_name.item);
+
+                    self.resolve_alternatives(fn_name.item, &arm.pats, ast_map)?;
+
+                    if let Some(guard) = &arm.guard {
+                        self.resolve_expression(fn_name, guard, ast_map)?;
                     }
 
                     self.resolve_expression(fn_name, &arm.expr, ast_map)?;
+
+                    self.end_function_scope(fn_name.item);
                 }
             }
             Expr::RecordLiteral {
@@ -221,57 +300,118 @@ where
                     return Err(());
                 }
             }
-            Expr::Enum { def, variant, expr } => {
-                if let Some(ty) = self.ctx.get_type(&def.item) {
-                    match ty {
-                        crate::infer::Type::Enum(variants) => {
-                            if variants.get(&variant.item).is_none() {
-                                let msg = format!(
-                                    "Unknown enum variant `{}`",
-                                    self.db.lookup_intern_name(variant.item)
-                                );
-                                self.reporter.error(msg, "", variant.as_reporter_span());
-                                return Err(());
-                            }
-                        }
-                        _ => {
-                            let msg = format!(
-                                "`{}` is not an enum",
-                                self.db.lookup_intern_name(def.item)
-                            );
+            Expr::Field { base, .. } => {
+                // Each field is checked against the class of the previous one during type
+                // checking, as that is when the type of the base is known
+                self.resolve_expression(fn_name, base, ast_map)?;
+            }
+        }
 
-                            self.reporter.error(msg, "", def.as_reporter_span());
+        Ok(())
+    }
 
-                            return Err(());
-                        }
-                    }
-                } else {
-                    let msg = format!("Unknown enum `{}`", self.db.lookup_intern_name(def.item));
+    /// `Foo::bar` is a variant when `Foo` is an enum and a static method when it's a class
+    fn resolve_path(
+        &mut self,
+        def: &util::Span<NameId>,
+        name: &util::Span<NameId>,
+    ) -> Result<(), ()> {
+        let instance_methods = match self.ctx.get_type(&def.item).map(|ty| ty.unwrap_poly()) {
+            Some(crate::infer::Type::Enum(_, _, variants)) => {
+                if variants.get(&name.item).is_some() {
+                    return Ok(());
+                }
 
-                    self.reporter.error(msg, "", def.as_reporter_span());
+                let msg = format!(
+                    "Unknown enum variant `{}`",
+                    self.db.lookup_intern_name(name.item)
+                );
 
-                    return Err(());
-                }
+                self.reporter.error(msg, "", name.as_reporter_span());
 
-                if let Some(expr) = expr {
-                    self.resolve_expression(fn_name, expr, ast_map)?;
-                }
+                return Err(());
             }
+            Some(crate::infer::Type::Class {
+                methods,
+                instance_methods,
+                ..
+            }) if methods.contains_key(&name.item) => instance_methods,
+            Some(crate::infer::Type::Class { .. }) => {
+                let msg = format!(
+                    "Unknown method `{}` on `{}`",
+                    self.db.lookup_intern_name(name.item),
+                    self.db.lookup_intern_name(def.item)
+                );
+
+                self.reporter.error(msg, "", name.as_reporter_span());
+
+                return Err(());
+            }
+            Some(_) => {
+                let msg = format!(
+                    "`{}` is not a class or an enum",
+                    self.db.lookup_intern_name(def.item)
+                );
 
-            Expr::Field { fields } => {
-                self.resolve_local(&fn_name.item, &fields[0])?;
-
-                // let ty = self.ctx.get_type(&fields[0].item).unwrap();
+                self.reporter.error(msg, "", def.as_reporter_span());
 
-                // let mut iter = fields.into_iter();
+                return Err(());
+            }
+            None => {
+                let msg = format!(
+                    "Unknown class or enum `{}`",
+                    self.db.lookup_intern_name(def.item)
+                );
 
-                // let field = iter.next().unwrap();
+                self.reporter.error(msg, "", def.as_reporter_span());
 
-                // self.resolve_fields(field, ty, iter)?;
-                // The checking of fields matching up is done during the type checking phase
+                return Err(());
             }
+        };
+
+        if instance_methods.contains(&name.item) {
+            let msg = format!(
+                "`{}::{}` takes `self` and can't be called without an instance",
+                self.db.lookup_intern_name(def.item),
+                self.db.lookup_intern_name(name.item)
+            );
+
+            self.reporter.error(
+                msg,
+                "Call it on an instance of the class",
+                name.as_reporter_span(),
+            );
+
+            return Err(());
         }
 
         Ok(())
     }
+
+    fn resolve_loop_body(
+        &mut self,
+        fn_name: &util::Span<NameId>,
+        label: &Option<util::Span<NameId>>,
+        is_loop: bool,
+        body: &BlockId,
+        ast_map: &FunctionAstMap,
+    ) -> Result<(), ()> {
+        let block = ast_map.block(body);
+
+        self.begin_loop(
+            fn_name.item,
+            label.as_ref().map(|label| label.item),
+            is_loop,
+        );
+        self.begin_function_scope(fn_name.item);
+
+        for id in &block.0 {
+            self.resolve_statement(fn_name, id, ast_map)?
+        }
+
+        self.end_function_scope(fn_name.item);
+        self.end_loop(fn_name.item);
+
+        Ok(())
+    }
 }
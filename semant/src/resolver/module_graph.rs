@@ -49,7 +49,7 @@ impl ModuleGraph {
 }
 
 pub(crate) fn module_graph_query(db: &impl HirDatabase, file: FileId) -> WithError<ModuleGraph> {
-    let program = db.lower(file)?;
+    let program = db.lower(file);
 
     let mut module_graph = ModuleGraph::new();
 
@@ -10,7 +10,7 @@ use std::{
 };
 
 pub fn resolve_exports_query(db: &impl HirDatabase, file: FileId) -> WithError<Arc<Resolver>> {
-    let program = db.lower(file)?;
+    let program = db.lower(file);
     let reporter = Reporter::new(file);
     let ctx = Ctx::new(db);
     let mut collector = ResolverDataCollector {
@@ -37,7 +37,7 @@ pub fn resolve_exports_query(db: &impl HirDatabase, file: FileId) -> WithError<A
 }
 
 pub fn resolve_source_file_query(db: &impl HirDatabase, file: FileId) -> WithError<Arc<Resolver>> {
-    let source_file = db.lower(file)?;
+    let source_file = db.lower(file);
 
     let reporter = Reporter::new(file);
 
@@ -77,20 +77,32 @@ pub fn resolve_source_file_query(db: &impl HirDatabase, file: FileId) -> WithErr
         collector.add_item(enum_def.name, ItemKind::Enum, enum_def.exported);
     }
 
+    for trait_def in &source_file.traits {
+        collector.add_item(trait_def.name, ItemKind::Trait, trait_def.exported);
+    }
+
     for alias in &source_file.type_alias {
         if let Err(_) = collector.resolve_alias(alias) {
             continue;
         };
     }
 
+    for trait_def in &source_file.traits {
+        if let Err(_) = collector.resolve_trait(trait_def) {
+            continue;
+        }
+    }
+
     for enum_def in &source_file.enums {
         if let Err(_) = collector.resolve_enum(enum_def) {
             continue;
         }
     }
 
-    for class in &source_file.classes {
-        if let Err(_) = collector.resolve_class(class) {
+    collector.resolve_classes(&source_file.classes);
+
+    for impl_def in &source_file.impls {
+        if let Err(_) = collector.resolve_impl(impl_def, &source_file.classes) {
             continue;
         }
     }
@@ -101,11 +113,12 @@ pub fn resolve_source_file_query(db: &impl HirDatabase, file: FileId) -> WithErr
         }
     }
 
-    let (resolver, reporter) = collector.finish();
+    let (mut resolver, reporter) = collector.finish();
 
     if reporter.has_errors() {
         Err(reporter.finish())
     } else {
+        resolver.warnings = reporter.finish();
         Ok(Arc::new(resolver))
     }
 }
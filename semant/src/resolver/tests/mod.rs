@@ -19,6 +19,8 @@ impl salsa::Database for MockDatabaseImpl {
     }
 }
 
+impl errors::FileSystem for MockDatabaseImpl {}
+
 use serde::Deserialize;
 use std::{
     fs::{self, File},
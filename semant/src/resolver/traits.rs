@@ -0,0 +1,369 @@
+use super::{
+    class::same_signature,
+    data::{FunctionData, ResolverDataCollector},
+    TypeKind,
+};
+use crate::{
+    hir::{self, Class, Impl, NameId},
+    infer::{Trait, Type, TypeParamBounds, TypeVar},
+    util, HirDatabase,
+};
+use std::{collections::HashSet, sync::Arc};
+
+impl<'a, DB> ResolverDataCollector<&'a DB>
+where
+    DB: HirDatabase,
+{
+    pub fn resolve_trait(&mut self, trait_def: &hir::Trait) -> Result<(), ()> {
+        let mut methods = Trait::default();
+
+        for method in &trait_def.methods {
+            self.report_lowering_errors(&method.ast_map);
+
+            if methods.methods.contains_key(&method.name.item) {
+                let msg = format!(
+                    "The method `{}` is defined multiple times",
+                    self.db.lookup_intern_name(method.name.item)
+                );
+
+                self.reporter.error(msg, "", method.name.as_reporter_span());
+
+                continue;
+            }
+
+            self.begin_scope();
+            let sig = self.resolve_function_signature(method);
+            self.end_scope();
+
+            if let Ok(sig) = sig {
+                methods.methods.insert(method.name.item, sig);
+
+                if method.self_param.is_some() {
+                    methods.instance_methods.insert(method.name.item);
+                }
+            }
+        }
+
+        self.ctx.insert_trait(trait_def.name.item, methods);
+
+        Ok(())
+    }
+
+    /// Checks that an impl defines every method of its trait with the declared signature and
+    /// adds the methods to the class. The methods can name the type params of the class when
+    /// it's one of `classes`
+    pub fn resolve_impl(&mut self, impl_def: &Impl, classes: &[Arc<Class>]) -> Result<(), ()> {
+        self.report_lowering_errors(&impl_def.ast_map);
+
+        let trait_name = match self.db.lookup_intern_type(impl_def.trait_ref.item) {
+            hir::Type::Ident(name) if self.ctx.get_trait(&name).is_some() => name,
+            ty => {
+                let msg = match ty.name() {
+                    Some(name) => format!("Unknown trait `{}`", self.db.lookup_intern_name(name)),
+                    None => "Expected a trait".into(),
+                };
+
+                self.reporter
+                    .error(msg, "", impl_def.trait_ref.as_reporter_span());
+
+                return Err(());
+            }
+        };
+
+        let (class_name, class_tvs, class_ty) = match self
+            .db
+            .lookup_intern_type(impl_def.target.item)
+        {
+            hir::Type::Ident(name) => match self.ctx.get_type(&name) {
+                Some(Type::Poly(tvs, ty)) if matches!(*ty, Type::Class { .. }) => (name, tvs, *ty),
+                _ => {
+                    self.reporter.error(
+                        "Only classes can implement traits",
+                        "",
+                        impl_def.target.as_reporter_span(),
+                    );
+
+                    return Err(());
+                }
+            },
+            _ => {
+                self.reporter.error(
+                    "Only classes can implement traits",
+                    "",
+                    impl_def.target.as_reporter_span(),
+                );
+
+                return Err(());
+            }
+        };
+
+        let trait_def = self.ctx.get_trait(&trait_name).cloned().unwrap();
+
+        let class_str = self.db.lookup_intern_name(class_name);
+        let trait_str = self.db.lookup_intern_name(trait_name);
+
+        let class_ty_with_methods = class_ty.clone();
+
+        let class_type_params = classes
+            .iter()
+            .find(|class| class.name.item == class_name)
+            .map_or_else(Vec::new, |class| {
+                class.type_param_names().zip(class_tvs.clone()).collect()
+            });
+
+        if self.ctx.implements(&class_ty, &trait_name) {
+            self.reporter.error(
+                format!("`{}` already implements `{}`", class_str, trait_str),
+                "",
+                impl_def.target.as_reporter_span(),
+            );
+
+            return Err(());
+        }
+
+        let (mut methods, mut instance_methods) = match class_ty {
+            Type::Class {
+                methods,
+                instance_methods,
+                ..
+            } => (methods, instance_methods),
+            _ => unreachable!(),
+        };
+
+        let mut has_error = false;
+        let mut defined = HashSet::new();
+
+        for method in &impl_def.methods {
+            let name = self.db.lookup_intern_name(method.name.item);
+
+            if !defined.insert(method.name.item) {
+                let msg = format!("The method `{}` is defined multiple times", name);
+
+                self.reporter.error(msg, "", method.name.as_reporter_span());
+
+                has_error = true;
+
+                continue;
+            }
+
+            let expected = match trait_def.methods.get(&method.name.item) {
+                Some(expected) => expected,
+                None => {
+                    self.reporter.error(
+                        format!("`{}` is not a method of `{}`", name, trait_str),
+                        "",
+                        method.name.as_reporter_span(),
+                    );
+
+                    has_error = true;
+
+                    continue;
+                }
+            };
+
+            if methods.contains_key(&method.name.item) {
+                self.reporter.error(
+                    format!("`{}` already has a method `{}`", class_str, name),
+                    "",
+                    method.name.as_reporter_span(),
+                );
+
+                has_error = true;
+
+                continue;
+            }
+
+            self.function_data
+                .entry(method.name.item)
+                .or_insert_with(FunctionData::new);
+
+            self.begin_scope();
+            self.insert_class_type_params(&class_type_params);
+            let sig = self.resolve_function_signature(method);
+            self.end_scope();
+
+            let sig = match sig {
+                Ok(sig) => sig,
+                Err(()) => {
+                    has_error = true;
+                    continue;
+                }
+            };
+
+            let same_receiver = trait_def.instance_methods.contains(&method.name.item)
+                == method.self_param.is_some();
+
+            if !same_receiver || !same_signature(expected, &sig) {
+                self.reporter.error(
+                    format!("The method `{}` doesn't match its declaration", name),
+                    format!(
+                        "`{}::{}` must take and return the same types as in `{}`",
+                        class_str, name, trait_str
+                    ),
+                    method.name.as_reporter_span(),
+                );
+
+                has_error = true;
+
+                continue;
+            }
+
+            methods.insert(method.name.item, sig);
+
+            if method.self_param.is_some() {
+                instance_methods.insert(method.name.item);
+            }
+        }
+
+        let mut missing = trait_def
+            .methods
+            .keys()
+            .filter(|method| !defined.contains(method))
+            .map(|method| format!("`{}`", self.db.lookup_intern_name(*method)))
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            missing.sort();
+
+            self.reporter.error(
+                format!(
+                    "`{}` doesn't implement every method of `{}`",
+                    class_str, trait_str
+                ),
+                format!("Missing {}", missing.join(", ")),
+                impl_def.target.as_reporter_span(),
+            );
+
+            has_error = true;
+        }
+
+        if has_error {
+            return Err(());
+        }
+
+        let ty = match class_ty_with_methods {
+            Type::Class {
+                name,
+                type_args,
+                fields,
+                superclass,
+                ..
+            } => Type::Class {
+                name,
+                type_args,
+                fields,
+                methods,
+                instance_methods,
+                superclass,
+            },
+            _ => unreachable!(),
+        };
+
+        self.ctx.insert_type(
+            class_name,
+            Type::Poly(class_tvs, Box::new(ty)),
+            TypeKind::Class,
+        );
+        self.ctx.insert_impl(trait_name, class_name);
+
+        for method in &impl_def.methods {
+            self.begin_scope();
+            self.insert_class_type_params(&class_type_params);
+            let _ = self.resolve_function(method);
+            self.end_scope();
+        }
+
+        Ok(())
+    }
+
+    /// The class already reported type params that clash with other types
+    fn insert_class_type_params(&mut self, type_params: &[(util::Span<NameId>, TypeVar)]) {
+        for (name, tv) in type_params {
+            self.ctx
+                .insert_type(name.item, Type::Var(*tv), TypeKind::Type);
+        }
+    }
+
+    /// Records the traits that bound a type param such as `T: Show + Debug`
+    pub(crate) fn resolve_bounds(
+        &mut self,
+        type_param: &hir::TypeParam,
+        tv: TypeVar,
+    ) -> Result<(), ()> {
+        let mut bounds: Vec<NameId> = Vec::new();
+
+        for bound in &type_param.bounds {
+            match self.db.lookup_intern_type(bound.item) {
+                hir::Type::Ident(name) if self.ctx.get_trait(&name).is_some() => bounds.push(name),
+                ty => {
+                    let msg = match ty.name() {
+                        Some(name) => {
+                            format!("`{}` is not a trait", self.db.lookup_intern_name(name))
+                        }
+                        None => "Only traits can be used as bounds".into(),
+                    };
+
+                    self.reporter.error(msg, "", bound.as_reporter_span());
+
+                    return Err(());
+                }
+            }
+        }
+
+        self.ctx.insert_type_param(
+            tv,
+            TypeParamBounds {
+                name: type_param.name.item,
+                bounds,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Checks a type argument against the bounds of the type param it is given for
+    pub(crate) fn check_bounds(
+        &mut self,
+        ty: &Type,
+        tv: &TypeVar,
+        span: (usize, usize),
+    ) -> Result<(), ()> {
+        let bounds = match self.ctx.get_type_param(tv) {
+            Some(type_param) => type_param.bounds.clone(),
+            None => return Ok(()),
+        };
+
+        for bound in bounds {
+            if !self.ctx.implements(ty, &bound) {
+                let msg = format!(
+                    "`{}` doesn't implement `{}`",
+                    ty.display(self.db).with_type_params(&self.ctx),
+                    self.db.lookup_intern_name(bound)
+                );
+
+                self.reporter.error(msg, "", span);
+
+                return Err(());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::create_test;
+
+    create_test!(basic_trait);
+
+    create_test!(impl_unknown_trait, is_err);
+
+    create_test!(impl_missing_method, is_err);
+
+    create_test!(impl_signature_mismatch, is_err);
+
+    create_test!(bound_not_a_trait, is_err);
+
+    create_test!(type_arg_bound_not_satisfied, is_err);
+}
@@ -45,3 +45,69 @@ where
         (self.start.to_usize(), self.end.to_usize())
     }
 }
+
+/// The candidate closest to `name` by edit distance, as long as it is close enough to be a typo
+pub(crate) fn closest_name<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let len = name.chars().count();
+    let max_distance = std::cmp::max(1, len / 3);
+
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance && *distance < len)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// The number of single character insertions, deletions, substitutions and swaps of
+/// adjacent characters needed to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for j in 0..=b.len() {
+        distances[0][j] = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = (a[i - 1] != b[j - 1]) as usize;
+
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::closest_name;
+
+    #[test]
+    fn suggests_close_names() {
+        let fields = ["start", "end", "x"];
+
+        assert_eq!(closest_name("strat", fields.iter().copied()), Some("start"));
+        assert_eq!(closest_name("ends", fields.iter().copied()), Some("end"));
+        assert_eq!(closest_name("y", fields.iter().copied()), None);
+        assert_eq!(closest_name("height", fields.iter().copied()), None);
+    }
+}
@@ -58,107 +58,125 @@ pub enum SyntaxKind {
     SLASHEQ, // /= 35
     AMPAMP, // && 36
     PIPEPIPE, // || 37
-    CLASS_KW, // class 38
-    EXTENDS_KW, // extends 39
-    TYPE_KW, // type 40
-    AS_KW, // as 41
-    MATCH_KW, // match 42
-    ENUM_KW, // enum 43
-    FN_KW, // fn 44
-    LET_KW, // let 45
-    IF_KW, // if 46
-    ELSE_KW, // else 47
-    FOR_KW, // for 48
-    WHILE_KW, // while 49
-    RETURN_KW, // return 50
-    BREAK_KW, // break 51
-    CONTINUE_KW, // continue 52
-    DO_KW, // do 53
-    TRUE_KW, // true 54
-    FALSE_KW, // false 55
-    OR_KW, // or 56
-    AND_KW, // and 57
-    EXPORT_KW, // export 58
-    IMPORT_KW, // import 59
-    FROM_KW, // from 60
-    MOD_KW, // mod 61
-    NIL_KW, // nil 62
-    SELF_KW, // self 63
-    INT_NUMBER, // 63
-    FLOAT_NUMBER, // 64
-    CHAR, // 65
-    STRING, // 66
-    ERROR, // 67
-    IDENT, // 68
-    COMMENT, // 69
-    WHITESPACE, // 70
-    BLOCK, // 71
-    SOURCE_FILE, // 72
-    CLASS_DEF, // 73
-    ENUM_DEF, // 74
-    FN_DEF, // 75
-    IMPORT_DEF, // 76
-    MOD_DEF, // 77
-    TYPE_ALIAS_DEF, // 78
-    IMPORT_SEGMENT, // 79
-    IMPORT_LIST, // 80
-    BIND_PAT, // 81
-    PLACEHOLDER_PAT, // 82
-    TUPLE_PAT, // 83
-    LITERAL_PAT, // 84
-    TYPE_REF, // 85
-    FN_TYPE, // 86
-    PAREN_TYPE, // 87
-    ARRAY_TYPE, // 88
-    IDENT_TYPE, // 89
-    RET_TYPE, // 90
-    ARRAY_EXPR, // 91
-    CALL_EXPR, // 92
-    CAST_EXPR, // 93
-    INDEX_EXPR, // 94
-    FIELD_EXPR, // 95
-    BIN_EXPR, // 96
-    PREFIX_EXPR, // 97
-    TUPLE_EXPR, // 98
-    IDENT_EXPR, // 99
-    ENUM_EXPR, // 100
-    IF_EXPR, // 101
-    WHILE_EXPR, // 102
-    CONDITION, // 103
-    LOOP_EXPR, // 104
-    DO_EXPR, // 105
-    FOR_EXPR, // 106
-    CONTINUE_EXPR, // 107
-    BREAK_EXPR, // 108
-    BLOCK_EXPR, // 109
-    RETURN_EXPR, // 110
-    CLOSURE_EXPR, // 111
-    PAREN_EXPR, // 112
-    MATCH_EXPR, // 113
-    MATCH_ARM_LIST, // 114
-    MATCH_ARM, // 115
-    MATCH_GUARD, // 116
-    CLASS_LIT, // 117
-    NAMED_FIELD_LIST, // 118
-    NAMED_FIELD, // 119
-    ENUM_VARIANT, // 120
-    NAMED_FIELD_DEF_LIST, // 121
-    NAMED_FIELD_DEF, // 122
-    RECORD_LITERAL_EXPR, // 123
-    ENUM_VARIANT_LIST, // 124
-    VISIBILITY, // 125
-    LITERAL, // 126
-    NAME, // 127
-    NAME_REF, // 128
-    LET_STMT, // 129
-    EXPR_STMT, // 130
-    TYPE_PARAM_LIST, // 131
-    TYPE_ARG_LIST, // 132
-    TYPE_PARAM, // 133
-    PARAM_LIST, // 134
-    PARAM, // 135
-    SELF_PARAM, // 136
-    ARG_LIST, // 137
+    DOTDOT, // .. 38
+    DOTDOTEQ, // ..= 39
+    PERCENTEQ, // %= 40
+    SHL, // << 41
+    SHR, // >> 42
+    CLASS_KW, // class 43
+    EXTENDS_KW, // extends 44
+    TYPE_KW, // type 45
+    AS_KW, // as 46
+    MATCH_KW, // match 47
+    ENUM_KW, // enum 48
+    FN_KW, // fn 49
+    LET_KW, // let 50
+    IF_KW, // if 51
+    ELSE_KW, // else 52
+    FOR_KW, // for 53
+    IN_KW, // in 54
+    WHILE_KW, // while 55
+    RETURN_KW, // return 56
+    BREAK_KW, // break 57
+    CONTINUE_KW, // continue 58
+    DO_KW, // do 59
+    LOOP_KW, // loop 60
+    TRUE_KW, // true 61
+    FALSE_KW, // false 62
+    OR_KW, // or 63
+    AND_KW, // and 64
+    EXPORT_KW, // export 65
+    IMPORT_KW, // import 66
+    FROM_KW, // from 67
+    MOD_KW, // mod 68
+    NIL_KW, // nil 69
+    SELF_KW, // self 70
+    TRAIT_KW, // trait 71
+    IMPL_KW, // impl 72
+    INT_NUMBER, // 66
+    FLOAT_NUMBER, // 67
+    CHAR, // 68
+    STRING, // 69
+    ERROR, // 70
+    IDENT, // 71
+    COMMENT, // 72
+    WHITESPACE, // 73
+    LABEL, // 74
+    BLOCK, // 75
+    SOURCE_FILE, // 76
+    CLASS_DEF, // 77
+    ENUM_DEF, // 78
+    FN_DEF, // 79
+    IMPORT_DEF, // 80
+    MOD_DEF, // 81
+    TYPE_ALIAS_DEF, // 82
+    IMPORT_SEGMENT, // 83
+    IMPORT_LIST, // 84
+    BIND_PAT, // 85
+    PLACEHOLDER_PAT, // 86
+    TUPLE_PAT, // 87
+    LITERAL_PAT, // 88
+    VARIANT_PAT, // 89
+    RECORD_PAT, // 90
+    RECORD_PAT_FIELD, // 91
+    REST_PAT, // 92
+    TYPE_REF, // 93
+    FN_TYPE, // 94
+    PAREN_TYPE, // 95
+    ARRAY_TYPE, // 96
+    IDENT_TYPE, // 97
+    RET_TYPE, // 98
+    ARRAY_EXPR, // 99
+    CALL_EXPR, // 100
+    CAST_EXPR, // 101
+    INDEX_EXPR, // 102
+    FIELD_EXPR, // 103
+    BIN_EXPR, // 104
+    PREFIX_EXPR, // 105
+    TUPLE_EXPR, // 106
+    IDENT_EXPR, // 107
+    ENUM_EXPR, // 108
+    IF_EXPR, // 109
+    WHILE_EXPR, // 110
+    CONDITION, // 111
+    LOOP_EXPR, // 112
+    DO_EXPR, // 113
+    FOR_EXPR, // 114
+    CONTINUE_EXPR, // 115
+    BREAK_EXPR, // 116
+    BLOCK_EXPR, // 117
+    RETURN_EXPR, // 118
+    CLOSURE_EXPR, // 119
+    PAREN_EXPR, // 120
+    MATCH_EXPR, // 121
+    MATCH_ARM_LIST, // 122
+    MATCH_ARM, // 123
+    MATCH_GUARD, // 124
+    CLASS_LIT, // 125
+    NAMED_FIELD_LIST, // 126
+    NAMED_FIELD, // 127
+    ENUM_VARIANT, // 128
+    NAMED_FIELD_DEF_LIST, // 129
+    NAMED_FIELD_DEF, // 130
+    RECORD_LITERAL_EXPR, // 131
+    ENUM_VARIANT_LIST, // 132
+    VISIBILITY, // 133
+    LITERAL, // 134
+    NAME, // 135
+    NAME_REF, // 136
+    LET_STMT, // 137
+    EXPR_STMT, // 138
+    TYPE_PARAM_LIST, // 139
+    TYPE_ARG_LIST, // 140
+    TYPE_PARAM, // 141
+    PARAM_LIST, // 142
+    PARAM, // 143
+    SELF_PARAM, // 144
+    ARG_LIST, // 145
+    TRAIT_DEF, // 146
+    IMPL_DEF, // 147
+    OPTIONAL_TYPE, // 148
+    TRY_EXPR, // 149
     // Technical kind so that we can cast from u16 safely
     #[doc(hidden)]
     __LAST,
@@ -214,6 +232,11 @@ impl SyntaxKind {
             SLASHEQ => "/=",
             AMPAMP => "&&",
             PIPEPIPE => "||",
+            DOTDOT => "..",
+            DOTDOTEQ => "..=",
+            PERCENTEQ => "%=",
+            SHL => "<<",
+            SHR => ">>",
             CLASS_KW => "class",
             EXTENDS_KW => "extends",
             TYPE_KW => "type",
@@ -225,11 +248,13 @@ impl SyntaxKind {
             IF_KW => "if",
             ELSE_KW => "else",
             FOR_KW => "for",
+            IN_KW => "in",
             WHILE_KW => "while",
             RETURN_KW => "return",
             BREAK_KW => "break",
             CONTINUE_KW => "continue",
             DO_KW => "do",
+            LOOP_KW => "loop",
             TRUE_KW => "true",
             FALSE_KW => "false",
             OR_KW => "or",
@@ -240,6 +265,8 @@ impl SyntaxKind {
             MOD_KW => "mod",
             NIL_KW => "nil",
             SELF_KW => "self",
+            TRAIT_KW => "trait",
+            IMPL_KW => "impl",
             INT_NUMBER => "INT_NUMBER",
             FLOAT_NUMBER => "FLOAT_NUMBER",
             CHAR => "CHAR",
@@ -248,6 +275,7 @@ impl SyntaxKind {
             IDENT => "IDENT",
             COMMENT => "COMMENT",
             WHITESPACE => "WHITESPACE",
+            LABEL => "LABEL",
             BLOCK => "BLOCK",
             SOURCE_FILE => "SOURCE_FILE",
             CLASS_DEF => "CLASS_DEF",
@@ -262,6 +290,10 @@ impl SyntaxKind {
             PLACEHOLDER_PAT => "PLACEHOLDER_PAT",
             TUPLE_PAT => "TUPLE_PAT",
             LITERAL_PAT => "LITERAL_PAT",
+            VARIANT_PAT => "VARIANT_PAT",
+            RECORD_PAT => "RECORD_PAT",
+            RECORD_PAT_FIELD => "RECORD_PAT_FIELD",
+            REST_PAT => "REST_PAT",
             TYPE_REF => "TYPE_REF",
             FN_TYPE => "FN_TYPE",
             PAREN_TYPE => "PAREN_TYPE",
@@ -315,6 +347,10 @@ impl SyntaxKind {
             PARAM => "PARAM",
             SELF_PARAM => "SELF_PARAM",
             ARG_LIST => "ARG_LIST",
+            TRAIT_DEF => "TRAIT_DEF",
+            IMPL_DEF => "IMPL_DEF",
+            OPTIONAL_TYPE => "OPTIONAL_TYPE",
+            TRY_EXPR => "TRY_EXPR",
         
             _ => ""
         }
@@ -537,7 +573,12 @@ impl AstNode for BreakExpr {
 }
 
 
-impl BreakExpr {}
+impl traits::LabelOwner for BreakExpr {}
+impl BreakExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        child_opt(self)
+    }
+}
 
 // CallExpr
 
@@ -628,7 +669,11 @@ impl traits::TypeParamsOwner for ClassDef {}
 impl traits::FnDefOwner for ClassDef {}
 impl traits::NamedFieldsOwner for ClassDef {}
 impl traits::VisibilityOwner for ClassDef {}
-impl ClassDef {}
+impl ClassDef {
+    pub fn superclass(&self) -> Option<TypeRef> {
+        child_opt(self)
+    }
+}
 
 // ClosureExpr
 
@@ -653,10 +698,18 @@ impl AstNode for ClosureExpr {
 
 impl traits::ArgListOwner for ClosureExpr {}
 impl ClosureExpr {
+    pub fn params(&self) -> impl Iterator<Item = Param> {
+        children(self)
+    }
+
     pub fn param_list(&self) -> Option<ParamList> {
         child_opt(self)
     }
 
+    pub fn type_ref(&self) -> Option<TypeRef> {
+        child_opt(self)
+    }
+
     pub fn body(&self) -> Option<Expr> {
         child_opt(self)
     }
@@ -714,8 +767,38 @@ impl AstNode for ContinueExpr {
 }
 
 
+impl traits::LabelOwner for ContinueExpr {}
 impl ContinueExpr {}
 
+// DoExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DoExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for DoExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            DO_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(DoExpr { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl traits::LoopBodyOwner for DoExpr {}
+impl traits::LabelOwner for DoExpr {}
+impl DoExpr {
+    pub fn condition(&self) -> Option<Condition> {
+        child_opt(self)
+    }
+}
+
 // EnumDef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -844,6 +927,8 @@ impl EnumVariantList {
             IfExpr(IfExpr),
             ForExpr(ForExpr),
             WhileExpr(WhileExpr),
+            DoExpr(DoExpr),
+            LoopExpr(LoopExpr),
             ContinueExpr(ContinueExpr),
             BreakExpr(BreakExpr),
             BlockExpr(BlockExpr),
@@ -859,6 +944,7 @@ impl EnumVariantList {
             Literal(Literal),
             TupleExpr(TupleExpr),
             EnumExpr(EnumExpr),
+            TryExpr(TryExpr),
     }
         impl From<ArrayExpr> for Expr {
             fn from(n: ArrayExpr) -> Expr { 
@@ -895,6 +981,16 @@ impl EnumVariantList {
                 Expr::WhileExpr(n)
             }
         }
+        impl From<DoExpr> for Expr {
+            fn from(n: DoExpr) -> Expr { 
+                Expr::DoExpr(n)
+            }
+        }
+        impl From<LoopExpr> for Expr {
+            fn from(n: LoopExpr) -> Expr { 
+                Expr::LoopExpr(n)
+            }
+        }
         impl From<ContinueExpr> for Expr {
             fn from(n: ContinueExpr) -> Expr { 
                 Expr::ContinueExpr(n)
@@ -970,10 +1066,15 @@ impl EnumVariantList {
                 Expr::EnumExpr(n)
             }
         }
+        impl From<TryExpr> for Expr {
+            fn from(n: TryExpr) -> Expr { 
+                Expr::TryExpr(n)
+            }
+        }
 impl AstNode for Expr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-             | ARRAY_EXPR | IDENT_EXPR | PAREN_EXPR | CLOSURE_EXPR | IF_EXPR | FOR_EXPR | WHILE_EXPR | CONTINUE_EXPR | BREAK_EXPR | BLOCK_EXPR | RETURN_EXPR | MATCH_EXPR | RECORD_LITERAL_EXPR | CALL_EXPR | INDEX_EXPR | FIELD_EXPR | CAST_EXPR | PREFIX_EXPR | BIN_EXPR | LITERAL | TUPLE_EXPR | ENUM_EXPR => true,
+             | ARRAY_EXPR | IDENT_EXPR | PAREN_EXPR | CLOSURE_EXPR | IF_EXPR | FOR_EXPR | WHILE_EXPR | DO_EXPR | LOOP_EXPR | CONTINUE_EXPR | BREAK_EXPR | BLOCK_EXPR | RETURN_EXPR | MATCH_EXPR | RECORD_LITERAL_EXPR | CALL_EXPR | INDEX_EXPR | FIELD_EXPR | CAST_EXPR | PREFIX_EXPR | BIN_EXPR | LITERAL | TUPLE_EXPR | ENUM_EXPR | TRY_EXPR => true,
             _ => false,
         }
     }
@@ -987,6 +1088,8 @@ impl AstNode for Expr {
             | IF_EXPR  => Some(Expr::IfExpr(IfExpr {syntax})), 
             | FOR_EXPR  => Some(Expr::ForExpr(ForExpr {syntax})), 
             | WHILE_EXPR  => Some(Expr::WhileExpr(WhileExpr {syntax})), 
+            | DO_EXPR  => Some(Expr::DoExpr(DoExpr {syntax})), 
+            | LOOP_EXPR  => Some(Expr::LoopExpr(LoopExpr {syntax})), 
             | CONTINUE_EXPR  => Some(Expr::ContinueExpr(ContinueExpr {syntax})), 
             | BREAK_EXPR  => Some(Expr::BreakExpr(BreakExpr {syntax})), 
             | BLOCK_EXPR  => Some(Expr::BlockExpr(BlockExpr {syntax})), 
@@ -1001,7 +1104,8 @@ impl AstNode for Expr {
             | BIN_EXPR  => Some(Expr::BinExpr(BinExpr {syntax})), 
             | LITERAL  => Some(Expr::Literal(Literal {syntax})), 
             | TUPLE_EXPR  => Some(Expr::TupleExpr(TupleExpr {syntax})), 
-            | ENUM_EXPR  => Some(Expr::EnumExpr(EnumExpr {syntax})),_ => None
+            | ENUM_EXPR  => Some(Expr::EnumExpr(EnumExpr {syntax})), 
+            | TRY_EXPR  => Some(Expr::TryExpr(TryExpr {syntax})),_ => None
         }
     }
     fn syntax(&self) -> &SyntaxNode {  
@@ -1014,6 +1118,8 @@ impl AstNode for Expr {
                 Expr::IfExpr(kind)  => &kind.syntax, 
                 Expr::ForExpr(kind)  => &kind.syntax, 
                 Expr::WhileExpr(kind)  => &kind.syntax, 
+                Expr::DoExpr(kind)  => &kind.syntax, 
+                Expr::LoopExpr(kind)  => &kind.syntax, 
                 Expr::ContinueExpr(kind)  => &kind.syntax, 
                 Expr::BreakExpr(kind)  => &kind.syntax, 
                 Expr::BlockExpr(kind)  => &kind.syntax, 
@@ -1028,7 +1134,8 @@ impl AstNode for Expr {
                 Expr::BinExpr(kind)  => &kind.syntax, 
                 Expr::Literal(kind)  => &kind.syntax, 
                 Expr::TupleExpr(kind)  => &kind.syntax, 
-                Expr::EnumExpr(kind)  => &kind.syntax,}
+                Expr::EnumExpr(kind)  => &kind.syntax, 
+                Expr::TryExpr(kind)  => &kind.syntax,}
     
     }
 }
@@ -1185,7 +1292,16 @@ impl AstNode for ForExpr {
 
 
 impl traits::LoopBodyOwner for ForExpr {}
-impl ForExpr {}
+impl traits::LabelOwner for ForExpr {}
+impl ForExpr {
+    pub fn pat(&self) -> Option<Pat> {
+        child_opt(self)
+    }
+
+    pub fn condition(&self) -> Option<Condition> {
+        child_opt(self)
+    }
+}
 
 // IdentExpr
 
@@ -1209,7 +1325,11 @@ impl AstNode for IdentExpr {
 
 
 impl traits::NameOwner for IdentExpr {}
-impl IdentExpr {}
+impl IdentExpr {
+    pub fn type_args(&self) -> Option<TypeArgList> {
+        child_opt(self)
+    }
+}
 
 // IdentType
 
@@ -1269,6 +1389,34 @@ impl IfExpr {
     }
 }
 
+// ImplDef
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImplDef {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ImplDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            IMPL_DEF => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(ImplDef { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl traits::FnDefOwner for ImplDef {}
+impl ImplDef {
+    pub fn trait_ref(&self) -> Option<TypeRef> {
+        child_opt(self)
+    }
+}
+
 // ImportDef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1461,6 +1609,31 @@ impl LiteralPat {
     }
 }
 
+// LoopExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LoopExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for LoopExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            LOOP_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(LoopExpr { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl traits::LoopBodyOwner for LoopExpr {}
+impl traits::LabelOwner for LoopExpr {}
+impl LoopExpr {}
+
 // MatchArm
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1487,6 +1660,10 @@ impl MatchArm {
         children(self)
     }
 
+    pub fn match_guard(&self) -> Option<MatchGuard> {
+        child_opt(self)
+    }
+
     pub fn expr(&self) -> Option<Expr> {
         child_opt(self)
     }
@@ -1550,6 +1727,33 @@ impl MatchExpr {
     }
 }
 
+// MatchGuard
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchGuard {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for MatchGuard {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            MATCH_GUARD => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(MatchGuard { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl MatchGuard {
+    pub fn expr(&self) -> Option<Expr> {
+        child_opt(self)
+    }
+}
+
 // ModDef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1730,6 +1934,33 @@ impl NamedFieldList {
     }
 }
 
+// OptionalType
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OptionalType {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for OptionalType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            OPTIONAL_TYPE => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(OptionalType { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl OptionalType {
+    pub fn type_ref(&self) -> Option<TypeRef> {
+        child_opt(self)
+    }
+}
+
 // Param
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1780,6 +2011,10 @@ impl AstNode for ParamList {
 
 
 impl ParamList {
+    pub fn self_param(&self) -> Option<SelfParam> {
+        child_opt(self)
+    }
+
     pub fn params(&self) -> impl Iterator<Item = Param> {
         children(self)
     }
@@ -1849,6 +2084,9 @@ impl ParenType {
             PlaceholderPat(PlaceholderPat),
             TuplePat(TuplePat),
             LiteralPat(LiteralPat),
+            VariantPat(VariantPat),
+            RecordPat(RecordPat),
+            RestPat(RestPat),
     }
         impl From<BindPat> for Pat {
             fn from(n: BindPat) -> Pat { 
@@ -1870,10 +2108,25 @@ impl ParenType {
                 Pat::LiteralPat(n)
             }
         }
+        impl From<VariantPat> for Pat {
+            fn from(n: VariantPat) -> Pat { 
+                Pat::VariantPat(n)
+            }
+        }
+        impl From<RecordPat> for Pat {
+            fn from(n: RecordPat) -> Pat { 
+                Pat::RecordPat(n)
+            }
+        }
+        impl From<RestPat> for Pat {
+            fn from(n: RestPat) -> Pat { 
+                Pat::RestPat(n)
+            }
+        }
 impl AstNode for Pat {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-             | BIND_PAT | PLACEHOLDER_PAT | TUPLE_PAT | LITERAL_PAT => true,
+             | BIND_PAT | PLACEHOLDER_PAT | TUPLE_PAT | LITERAL_PAT | VARIANT_PAT | RECORD_PAT | REST_PAT => true,
             _ => false,
         }
     }
@@ -1883,7 +2136,10 @@ impl AstNode for Pat {
             | BIND_PAT  => Some(Pat::BindPat(BindPat {syntax})), 
             | PLACEHOLDER_PAT  => Some(Pat::PlaceholderPat(PlaceholderPat {syntax})), 
             | TUPLE_PAT  => Some(Pat::TuplePat(TuplePat {syntax})), 
-            | LITERAL_PAT  => Some(Pat::LiteralPat(LiteralPat {syntax})),_ => None
+            | LITERAL_PAT  => Some(Pat::LiteralPat(LiteralPat {syntax})), 
+            | VARIANT_PAT  => Some(Pat::VariantPat(VariantPat {syntax})), 
+            | RECORD_PAT  => Some(Pat::RecordPat(RecordPat {syntax})), 
+            | REST_PAT  => Some(Pat::RestPat(RestPat {syntax})),_ => None
         }
     }
     fn syntax(&self) -> &SyntaxNode {  
@@ -1892,7 +2148,10 @@ impl AstNode for Pat {
                 Pat::BindPat(kind)  => &kind.syntax, 
                 Pat::PlaceholderPat(kind)  => &kind.syntax, 
                 Pat::TuplePat(kind)  => &kind.syntax, 
-                Pat::LiteralPat(kind)  => &kind.syntax,}
+                Pat::LiteralPat(kind)  => &kind.syntax, 
+                Pat::VariantPat(kind)  => &kind.syntax, 
+                Pat::RecordPat(kind)  => &kind.syntax, 
+                Pat::RestPat(kind)  => &kind.syntax,}
     
     }
 }
@@ -1981,6 +2240,89 @@ impl RecordLiteralExpr {
     }
 }
 
+// RecordPat
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RecordPat {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for RecordPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            RECORD_PAT => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(RecordPat { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl traits::NameOwner for RecordPat {}
+impl RecordPat {
+    pub fn fields(&self) -> impl Iterator<Item = RecordPatField> {
+        children(self)
+    }
+
+    pub fn rest_pat(&self) -> Option<RestPat> {
+        child_opt(self)
+    }
+}
+
+// RecordPatField
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RecordPatField {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for RecordPatField {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            RECORD_PAT_FIELD => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(RecordPatField { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl traits::NameOwner for RecordPatField {}
+impl RecordPatField {
+    pub fn pat(&self) -> Option<Pat> {
+        child_opt(self)
+    }
+}
+
+// RestPat
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RestPat {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for RestPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            REST_PAT => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(RestPat { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl RestPat {}
+
 // RetType
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -2035,6 +2377,29 @@ impl ReturnExpr {
     }
 }
 
+// SelfParam
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SelfParam {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for SelfParam {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            SELF_PARAM => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(SelfParam { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl SelfParam {}
+
 // SourceFile
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -2062,6 +2427,8 @@ impl traits::TypeAliasDefOwner for SourceFile {}
 impl traits::EnumDefOwner for SourceFile {}
 impl traits::ExternImportDefOwner for SourceFile {}
 impl traits::ModuleDefOwner for SourceFile {}
+impl traits::TraitDefOwner for SourceFile {}
+impl traits::ImplDefOwner for SourceFile {}
 impl SourceFile {}
 
 // Stmt
@@ -2107,6 +2474,59 @@ impl AstNode for Stmt {
 
 impl Stmt {}
 
+// TraitDef
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraitDef {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for TraitDef {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            TRAIT_DEF => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(TraitDef { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl traits::NameOwner for TraitDef {}
+impl traits::FnDefOwner for TraitDef {}
+impl traits::VisibilityOwner for TraitDef {}
+impl TraitDef {}
+
+// TryExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TryExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for TryExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            TRY_EXPR => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(TryExpr { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl TryExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        child_opt(self)
+    }
+}
+
 // TupleExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -2240,7 +2660,11 @@ impl AstNode for TypeParam {
 
 
 impl traits::NameOwner for TypeParam {}
-impl TypeParam {}
+impl TypeParam {
+    pub fn bounds(&self) -> impl Iterator<Item = TypeRef> {
+        children(self)
+    }
+}
 
 // TypeParamList
 
@@ -2278,6 +2702,7 @@ impl TypeParamList {
             ArrayType(ArrayType),
             FnType(FnType),
             IdentType(IdentType),
+            OptionalType(OptionalType),
     }
         impl From<ParenType> for TypeRef {
             fn from(n: ParenType) -> TypeRef { 
@@ -2299,10 +2724,15 @@ impl TypeParamList {
                 TypeRef::IdentType(n)
             }
         }
+        impl From<OptionalType> for TypeRef {
+            fn from(n: OptionalType) -> TypeRef { 
+                TypeRef::OptionalType(n)
+            }
+        }
 impl AstNode for TypeRef {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-             | PAREN_TYPE | ARRAY_TYPE | FN_TYPE | IDENT_TYPE => true,
+             | PAREN_TYPE | ARRAY_TYPE | FN_TYPE | IDENT_TYPE | OPTIONAL_TYPE => true,
             _ => false,
         }
     }
@@ -2312,7 +2742,8 @@ impl AstNode for TypeRef {
             | PAREN_TYPE  => Some(TypeRef::ParenType(ParenType {syntax})), 
             | ARRAY_TYPE  => Some(TypeRef::ArrayType(ArrayType {syntax})), 
             | FN_TYPE  => Some(TypeRef::FnType(FnType {syntax})), 
-            | IDENT_TYPE  => Some(TypeRef::IdentType(IdentType {syntax})),_ => None
+            | IDENT_TYPE  => Some(TypeRef::IdentType(IdentType {syntax})), 
+            | OPTIONAL_TYPE  => Some(TypeRef::OptionalType(OptionalType {syntax})),_ => None
         }
     }
     fn syntax(&self) -> &SyntaxNode {  
@@ -2321,13 +2752,45 @@ impl AstNode for TypeRef {
                 TypeRef::ParenType(kind)  => &kind.syntax, 
                 TypeRef::ArrayType(kind)  => &kind.syntax, 
                 TypeRef::FnType(kind)  => &kind.syntax, 
-                TypeRef::IdentType(kind)  => &kind.syntax,}
+                TypeRef::IdentType(kind)  => &kind.syntax, 
+                TypeRef::OptionalType(kind)  => &kind.syntax,}
     
     }
 }
 
 impl TypeRef {}
 
+// VariantPat
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VariantPat {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for VariantPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            VARIANT_PAT => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) { Some(VariantPat { syntax }) } else { None }
+    }
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+
+
+impl VariantPat {
+    pub fn segments(&self) -> impl Iterator<Item = Name> {
+        children(self)
+    }
+
+    pub fn pat(&self) -> Option<Pat> {
+        child_opt(self)
+    }
+}
+
 // Visibility
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -2373,6 +2836,7 @@ impl AstNode for WhileExpr {
 
 
 impl traits::LoopBodyOwner for WhileExpr {}
+impl traits::LabelOwner for WhileExpr {}
 impl WhileExpr {
     pub fn condition(&self) -> Option<Condition> {
         child_opt(self)
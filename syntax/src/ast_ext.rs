@@ -1,6 +1,6 @@
 use crate::ast;
 use crate::{
-    child_opt, children, AstChildren, AstNode,
+    child_opt, children, AstChildren, AstNode, LoopBodyOwner,
     SyntaxKind::{self, *},
     SyntaxNode,
 };
@@ -21,16 +21,54 @@ impl ElseBranch {
 }
 
 impl ast::ForExpr {
+    /// Whether this is a `for pat in iterable` loop rather than a C-style `for (init; cond; incr)`
+    pub fn is_for_in(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|it| it.kind() == IN_KW)
+    }
+
+    /// The thing being looped over by a `for pat in iterable` loop
+    pub fn iterable(&self) -> Option<ast::Expr> {
+        self.expr_before_body()
+    }
+
     pub fn init(&self) -> Option<ast::Stmt> {
         children(self).next()
     }
 
-    pub fn cond(&self) -> Option<ast::Expr> {
-        children(self).nth(1)
+    /// The initializer is a statement and the condition has its own node
+    /// so the only expression of a C-style loop besides the body is the increment
+    pub fn increment(&self) -> Option<ast::Expr> {
+        self.expr_before_body()
     }
 
-    pub fn increment(&self) -> Option<ast::Expr> {
-        children(self).nth(2)
+    fn expr_before_body(&self) -> Option<ast::Expr> {
+        let body = self.loop_body()?;
+
+        children::<_, ast::Expr>(self).find(|expr| expr.syntax() != body.syntax())
+    }
+}
+
+impl ast::FieldExpr {
+    /// The expression before the `.`
+    pub fn base(&self) -> Option<ast::Expr> {
+        children(self).next()
+    }
+
+    /// The name after the `.`
+    pub fn field(&self) -> Option<ast::IdentExpr> {
+        match children(self).nth(1)? {
+            ast::Expr::IdentExpr(ident) => Some(ident),
+            _ => None,
+        }
+    }
+}
+
+impl ast::ImplDef {
+    /// The type after `for` that implements the trait
+    pub fn target(&self) -> Option<ast::TypeRef> {
+        children(self).nth(1)
     }
 }
 
@@ -47,7 +85,8 @@ impl ast::BinExpr {
         self.syntax()
             .children_with_tokens()
             .filter_map(|it| it.into_token())
-            .find_map(|c| Some(c.kind()))
+            .map(|c| c.kind())
+            .find(|kind| !kind.is_trivia())
     }
 }
 
@@ -56,7 +95,17 @@ impl ast::PrefixExpr {
         self.syntax()
             .children_with_tokens()
             .filter_map(|it| it.into_token())
-            .find_map(|c| Some(c.kind()))
+            .map(|c| c.kind())
+            .find(|kind| !kind.is_trivia())
+    }
+}
+
+impl ast::ArrayExpr {
+    /// Whether this is a repeat literal `[value; count]`
+    pub fn is_repeat(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|it| it.kind() == SEMI)
     }
 }
 
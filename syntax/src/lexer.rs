@@ -1,6 +1,7 @@
 use crate::ast::SyntaxKind;
 
 use crate::token::Token;
+use crate::unescape::unescape;
 use errors::{
     pos::{CharPosition, Position, Span},
     Reporter,
@@ -42,10 +43,6 @@ impl<'a> Lexer<'a> {
     pub fn lex(&mut self) -> Vec<Span<Token>> {
         let mut tokens = Vec::new();
 
-        if self.input.is_empty() {
-            return Vec::new();
-        }
-
         while let Ok(token) = self.next() {
             if token.value.kind == SyntaxKind::EOF {
                 tokens.push(token);
@@ -128,7 +125,20 @@ impl<'a> Lexer<'a> {
     pub(crate) fn next_token(&mut self) -> Span<Token> {
         while let Some((start, ch)) = self.advance() {
             return match ch {
-                '.' => span(SyntaxKind::DOT, start),
+                '.' => {
+                    if self.peek(|ch| ch == '.') {
+                        self.advance();
+
+                        if self.peek(|ch| ch == '=') {
+                            self.advance();
+                            spans(SyntaxKind::DOTDOTEQ, start, start.span('.').shift('='))
+                        } else {
+                            spans(SyntaxKind::DOTDOT, start, start.span('.'))
+                        }
+                    } else {
+                        span(SyntaxKind::DOT, start)
+                    }
+                }
                 '?' => span(SyntaxKind::QUESTION, start),
                 ';' => span(SyntaxKind::SEMI, start),
                 '{' => span(SyntaxKind::L_CURLY, start),
@@ -139,9 +149,31 @@ impl<'a> Lexer<'a> {
                 ')' => span(SyntaxKind::R_PAREN, start),
                 ',' => span(SyntaxKind::COMMA, start),
                 '_' => span(SyntaxKind::UNDERSCORE, start),
-                '|' => span(SyntaxKind::PIPE, start),
-                '^' => span(SyntaxKind::EXPONENTIAL, start),
-                '%' => span(SyntaxKind::PERCENT, start),
+                '^' => span(SyntaxKind::CARET, start),
+                '&' => {
+                    if self.peek(|ch| ch == '&') {
+                        self.advance();
+                        spans(SyntaxKind::AMPAMP, start, start.span('&'))
+                    } else {
+                        span(SyntaxKind::AMP, start)
+                    }
+                }
+                '|' => {
+                    if self.peek(|ch| ch == '|') {
+                        self.advance();
+                        spans(SyntaxKind::PIPEPIPE, start, start.span('|'))
+                    } else {
+                        span(SyntaxKind::PIPE, start)
+                    }
+                }
+                '%' => {
+                    if self.peek(|ch| ch == '=') {
+                        self.advance();
+                        spans(SyntaxKind::PERCENTEQ, start, start.span('='))
+                    } else {
+                        span(SyntaxKind::PERCENT, start)
+                    }
+                }
                 ':' => {
                     if self.peek(|ch| ch == ':') {
                         self.advance();
@@ -171,6 +203,7 @@ impl<'a> Lexer<'a> {
                     }
                 }
 
+                // `>>` is glued together by the parser so that `A<B<i32>>` closes both lists
                 '>' => {
                     if self.peek(|ch| ch == '=') {
                         self.advance();
@@ -184,6 +217,9 @@ impl<'a> Lexer<'a> {
                     if self.peek(|ch| ch == '=') {
                         self.advance();
                         spans(SyntaxKind::LTEQ, start, start.span(':'))
+                    } else if self.peek(|ch| ch == '<') {
+                        self.advance();
+                        spans(SyntaxKind::SHL, start, start.span('<'))
                     } else {
                         span(SyntaxKind::L_ANGLE, start)
                     }
@@ -221,6 +257,11 @@ impl<'a> Lexer<'a> {
                 }
 
                 '"' => self.string_literal(start),
+                '\'' if self.peek(is_letter_ch) && !self.peek_nth(1, |ch| ch == '\'') => {
+                    self.label(start)
+                }
+                '\'' => self.char_literal(start),
+                'r' if self.peek(|ch| ch == '"' || ch == '#') => self.raw_string_literal(start),
                 '/' => {
                     if self.peek(|ch| ch == '=') {
                         self.advance();
@@ -296,18 +337,137 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// Handles a string which can span multiple lines and contain escapes
     fn string_literal(&mut self, start: Position) -> Span<Token> {
         while let Some((next, ch)) = self.advance() {
-            if ch == '"' {
-                let end = next.shift(ch);
+            match ch {
+                '"' => {
+                    let end = next.shift(ch);
+
+                    self.unescape(start, end);
+
+                    return spans(SyntaxKind::STRING, start, end);
+                }
+                // Skip the escaped char so that `\"` doesn't end the string
+                '\\' => {
+                    self.advance();
+                }
+                _ => (),
+            }
+        }
+
+        self.error(
+            "Unterminated string",
+            "Expected a closing `\"`",
+            (start, self.end),
+        );
+
+        spans(SyntaxKind::ERROR, start, self.end)
+    }
+
+    /// Handles a raw string, i.e `r"..."` or `r#"..."#`, which doesn't decode escapes.
+    /// The string ends at the first `"` followed by as many `#` as the string started with
+    fn raw_string_literal(&mut self, start: Position) -> Span<Token> {
+        let mut hashes = 0;
+
+        while self.peek(|ch| ch == '#') {
+            self.advance();
+            hashes += 1;
+        }
+
+        if !self.peek(|ch| ch == '"') {
+            self.error(
+                "Expected `\"`",
+                "Expected a `\"` to start the raw string",
+                (start, self.end),
+            );
+
+            return spans(SyntaxKind::ERROR, start, self.end);
+        }
+
+        self.advance();
+
+        while let Some((_, ch)) = self.advance() {
+            if ch != '"' {
+                continue;
+            }
+
+            let mut closing = 0;
+
+            while closing < hashes && self.peek(|ch| ch == '#') {
+                self.advance();
+                closing += 1;
+            }
+
+            if closing == hashes {
+                return spans(SyntaxKind::STRING, start, self.end);
+            }
+        }
+
+        self.error(
+            "Unterminated raw string",
+            format!("Expected a closing `\"{}`", "#".repeat(hashes)),
+            (start, self.end),
+        );
+
+        spans(SyntaxKind::ERROR, start, self.end)
+    }
+
+    /// Handles a char literal such as `'a'` or `'\n'`
+    fn char_literal(&mut self, start: Position) -> Span<Token> {
+        while let Some((next, ch)) = self.advance() {
+            match ch {
+                '\'' => {
+                    let end = next.shift(ch);
+
+                    let value = self.unescape(start, end);
+
+                    if value.chars().count() != 1 {
+                        self.error(
+                            "Invalid char literal",
+                            "A char literal must contain exactly one character",
+                            (start, end),
+                        );
+                    }
 
-                return spans(SyntaxKind::STRING, start, end);
+                    return spans(SyntaxKind::CHAR, start, end);
+                }
+                '\\' => {
+                    self.advance();
+                }
+                '\n' => break,
+                _ => (),
             }
         }
 
+        self.error(
+            "Unterminated char literal",
+            "Expected a closing `'`",
+            (start, self.end),
+        );
+
         spans(SyntaxKind::ERROR, start, self.end)
     }
 
+    /// Handles a loop label such as `'outer`.
+    /// A quote followed by a single char and another quote is a char literal instead
+    fn label(&mut self, start: Position) -> Span<Token> {
+        let (end, _) = self.take_whilst(start, is_letter_ch);
+
+        spans(SyntaxKind::LABEL, start, end)
+    }
+
+    /// Decode the contents of the quoted literal between `start` and `end`, reporting any invalid escapes
+    fn unescape(&mut self, start: Position, end: Position) -> String {
+        let offset = start.absolute as usize + 1;
+        let contents = &self.input[offset..end.absolute as usize - 1];
+        let reporter = &mut self.reporter;
+
+        unescape(contents, |range, msg| {
+            reporter.error(msg, "", (offset + range.start, offset + range.end))
+        })
+    }
+
     /// Handles any identifier.
     // New key words should be added to the look_up_identifier function
     pub(crate) fn identifier(&mut self, start: Position) -> Span<Token> {
@@ -315,27 +475,49 @@ impl<'a> Lexer<'a> {
         spans(look_up_identifier(ident), start, end)
     }
 
-    /// Handles number,both int's and floats
+    /// Handles numbers, both ints and floats.
+    /// The digits, base prefix, exponent and suffix are lexed together and checked when the
+    /// literal is lowered, so `0xFF`, `1_000`, `1.5e-3` and `10i32` are all single tokens
     pub(crate) fn number(&mut self, start: Position) -> Span<Token> {
-        let (end, _) = self.take_whilst(start, char::is_numeric);
+        let (mut end, _) = self.take_whilst(start, is_letter_ch);
 
-        match self.lookahead {
-            Some((end, '.')) => {
-                self.advance();
+        let mut kind = SyntaxKind::INT_NUMBER;
+
+        // `1..2` is a range and `1.foo` is a field access so the `.` has to be followed by a digit
+        if self.peek(|ch| ch == '.') && self.peek_nth(1, |ch| ch.is_ascii_digit()) {
+            self.advance();
+            end = self.take_whilst(start, is_letter_ch).0;
+            kind = SyntaxKind::FLOAT_NUMBER;
+        }
 
-                if self.peek(char::is_numeric) {
-                    let (end, _) = self.take_whilst(start, char::is_numeric);
+        let text = self.slice(start, end);
+        let prefixed = text.starts_with("0x") || text.starts_with("0o") || text.starts_with("0b");
 
-                    spans(SyntaxKind::FLOAT_NUMBER, start, end)
-                } else {
-                    spans(SyntaxKind::ERROR, start, end)
-                }
-            }
+        if !prefixed
+            && text.ends_with(&['e', 'E'][..])
+            && self.peek(|ch| ch == '+' || ch == '-')
+            && self.peek_nth(1, |ch| ch.is_ascii_digit())
+        {
+            self.advance();
+            end = self.take_whilst(start, is_letter_ch).0;
+            kind = SyntaxKind::FLOAT_NUMBER;
+        }
+
+        spans(kind, start, end)
+    }
 
-            Some((end, ch)) if ch.is_alphabetic() => spans(SyntaxKind::ERROR, start, end),
+    /// Check the char `n` chars after the lookahead
+    fn peek_nth<F>(&self, n: usize, check: F) -> bool
+    where
+        F: FnOnce(char) -> bool,
+    {
+        let ch = self
+            .lookahead
+            .and_then(|(pos, _)| self.input[pos.absolute as usize..].chars().nth(n));
 
-            Some((end, _)) => spans(SyntaxKind::INT_NUMBER, start, end),
-            None => spans(SyntaxKind::INT_NUMBER, start, end),
+        match ch {
+            Some(ch) => check(ch),
+            None => false,
         }
     }
 }
@@ -359,6 +541,8 @@ fn look_up_identifier(id: &str) -> SyntaxKind {
         "mod" => SyntaxKind::MOD_KW,
         "export" => SyntaxKind::EXPORT_KW,
         "self" => SyntaxKind::SELF_KW,
+        "trait" => SyntaxKind::TRAIT_KW,
+        "impl" => SyntaxKind::IMPL_KW,
         // Functions and vars
         "fn" => SyntaxKind::FN_KW,
         "let" => SyntaxKind::LET_KW,
@@ -366,11 +550,13 @@ fn look_up_identifier(id: &str) -> SyntaxKind {
         "if" => SyntaxKind::IF_KW,
         "else" => SyntaxKind::ELSE_KW,
         "for" => SyntaxKind::FOR_KW,
+        "in" => SyntaxKind::IN_KW,
         "while" => SyntaxKind::WHILE_KW,
         "return" => SyntaxKind::RETURN_KW,
         "break" => SyntaxKind::BREAK_KW,
         "continue" => SyntaxKind::CONTINUE_KW,
         "do" => SyntaxKind::DO_KW,
+        "loop" => SyntaxKind::LOOP_KW,
 
         // Booleans
         "true" => SyntaxKind::TRUE_KW,
@@ -7,8 +7,10 @@ mod lexer;
 mod ast_ext;
 mod token;
 mod traits;
+mod unescape;
 pub use ast::SyntaxKind;
 pub use lexer::Lexer;
+pub use unescape::{char_value, string_value, unescape};
 pub use rowan::{SmolStr, TextRange, TextUnit};
 pub use token::Token;
 pub use traits::*;
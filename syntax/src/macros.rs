@@ -6,6 +6,9 @@ macro_rules! T {
     (while) => {
         $crate::SyntaxKind::WHILE_KW
     };
+    (as) => {
+        $crate::SyntaxKind::AS_KW
+    };
     (for) => {
         $crate::SyntaxKind::FOR_KW
     };
@@ -21,6 +24,12 @@ macro_rules! T {
     (do) => {
         $crate::SyntaxKind::DO_KW
     };
+    (loop) => {
+        $crate::SyntaxKind::LOOP_KW
+    };
+    (in) => {
+        $crate::SyntaxKind::IN_KW
+    };
     (return) => {
         $crate::SyntaxKind::RETURN_KW
     };
@@ -47,7 +56,13 @@ macro_rules! T {
     };
 
     (extends) => {
-        $crate::SyntaxKind::EXTENDS
+        $crate::SyntaxKind::EXTENDS_KW
+    };
+    (trait) => {
+        $crate::SyntaxKind::TRAIT_KW
+    };
+    (impl) => {
+        $crate::SyntaxKind::IMPL_KW
     };
     (export) => {
         $crate::SyntaxKind::EXPORT_KW
@@ -107,6 +122,15 @@ macro_rules! T {
     (.) => {
         $crate::SyntaxKind::DOT
     };
+    (?) => {
+        $crate::SyntaxKind::QUESTION
+    };
+    (..) => {
+        $crate::SyntaxKind::DOTDOT
+    };
+    (..=) => {
+        $crate::SyntaxKind::DOTDOTEQ
+    };
     (;) => {
         $crate::SyntaxKind::SEMI
     };
@@ -135,6 +159,24 @@ macro_rules! T {
     (/) => {
         $crate::SyntaxKind::SLASH
     };
+    (%) => {
+        $crate::SyntaxKind::PERCENT
+    };
+    (%=) => {
+        $crate::SyntaxKind::PERCENTEQ
+    };
+    (&) => {
+        $crate::SyntaxKind::AMP
+    };
+    (^) => {
+        $crate::SyntaxKind::CARET
+    };
+    (<<) => {
+        $crate::SyntaxKind::SHL
+    };
+    (>>) => {
+        $crate::SyntaxKind::SHR
+    };
 
     (&&) => {
         $crate::SyntaxKind::AMPAMP
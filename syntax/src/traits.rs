@@ -196,6 +196,16 @@ pub trait LoopBodyOwner: AstNode {
     }
 }
 
+pub trait LabelOwner: AstNode {
+    /// The `'label` of a loop or the label that a `break` or `continue` targets
+    fn label(&self) -> Option<SyntaxToken> {
+        self.syntax()
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|it| it.kind() == SyntaxKind::LABEL)
+    }
+}
+
 pub trait ArgListOwner: AstNode {
     fn arg_list(&self) -> Option<ast::ArgList> {
         child_opt(self)
@@ -232,6 +242,18 @@ pub trait ClassDefOwner: AstNode {
     }
 }
 
+pub trait TraitDefOwner: AstNode {
+    fn traits(&self) -> AstChildren<ast::TraitDef> {
+        children(self)
+    }
+}
+
+pub trait ImplDefOwner: AstNode {
+    fn impls(&self) -> AstChildren<ast::ImplDef> {
+        children(self)
+    }
+}
+
 pub trait ExternImportDefOwner: AstNode {
     fn imports(&self) -> AstChildren<ast::ImportDef> {
         children(self)
@@ -0,0 +1,158 @@
+//! Decoding of the contents of string and char literals
+use std::ops::Range;
+
+/// Decode the escape sequences in `text`.
+/// Every invalid escape is passed to `error` along with the byte range it covers in `text`
+/// and is left out of the decoded value
+pub fn unescape(text: &str, mut error: impl FnMut(Range<usize>, String)) -> String {
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, ch)) = chars.next() {
+        if ch != '\\' {
+            value.push(ch);
+            continue;
+        }
+
+        let (escape_start, escape) = match chars.next() {
+            Some(escape) => escape,
+            None => {
+                error(
+                    start..text.len(),
+                    "Expected an escape sequence after `\\`".into(),
+                );
+                break;
+            }
+        };
+
+        let decoded = match escape {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            'u' => {
+                let rest = &text[escape_start + 1..];
+
+                let len = rest
+                    .find('}')
+                    .filter(|_| rest.starts_with('{'))
+                    .map_or(0, |close| close + 1);
+
+                // Skip over the braces even when they don't hold a valid code point
+                while let Some((i, _)) = chars.peek() {
+                    if *i >= escape_start + 1 + len {
+                        break;
+                    }
+
+                    chars.next();
+                }
+
+                match unicode_escape(&rest[..len]) {
+                    Some(ch) => ch,
+                    None => {
+                        error(
+                            start..escape_start + 1 + len,
+                            "Invalid unicode escape, expected `\\u{..}` with at most six hex digits"
+                                .into(),
+                        );
+                        continue;
+                    }
+                }
+            }
+            escape => {
+                error(
+                    start..escape_start + escape.len_utf8(),
+                    format!("Unknown character escape `\\{}`", escape),
+                );
+                continue;
+            }
+        };
+
+        value.push(decoded);
+    }
+
+    value
+}
+
+/// Decode `{1F600}` into the char it names
+fn unicode_escape(braced: &str) -> Option<char> {
+    let digits = braced.strip_prefix('{')?.strip_suffix('}')?;
+
+    if digits.is_empty() || digits.len() > 6 {
+        return None;
+    }
+
+    u32::from_str_radix(digits, 16)
+        .ok()
+        .and_then(std::char::from_u32)
+}
+
+/// The value of a `STRING` token, with the quotes removed and the escapes decoded.
+/// Raw strings (`r"..."` or `r#"..."#`) are returned as written
+pub fn string_value(text: &str) -> String {
+    if let Some(raw) = text.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+
+        return raw
+            .get(hashes + 1..raw.len().saturating_sub(hashes + 1))
+            .unwrap_or_default()
+            .into();
+    }
+
+    unescape(strip_quotes(text, '"'), |_, _| {})
+}
+
+/// The value of a `CHAR` token
+pub fn char_value(text: &str) -> char {
+    unescape(strip_quotes(text, '\''), |_, _| {})
+        .chars()
+        .next()
+        .unwrap_or_default()
+}
+
+fn strip_quotes(text: &str, quote: char) -> &str {
+    let text = text.strip_prefix(quote).unwrap_or(text);
+    text.strip_suffix(quote).unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{char_value, string_value, unescape};
+
+    fn errors(text: &str) -> Vec<(std::ops::Range<usize>, String)> {
+        let mut errors = Vec::new();
+        unescape(text, |range, msg| errors.push((range, msg)));
+        errors
+    }
+
+    #[test]
+    fn decodes_escapes() {
+        assert_eq!(
+            string_value(r#""say \"hi\"\n\t\\\u{1F600}""#),
+            "say \"hi\"\n\t\\\u{1F600}"
+        );
+        assert_eq!(char_value(r"'\''"), '\'');
+        assert_eq!(char_value("'a'"), 'a');
+    }
+
+    #[test]
+    fn raw_strings_are_not_decoded() {
+        assert_eq!(string_value(r#"r"a\nb""#), r"a\nb");
+        assert_eq!(string_value(r###"r##"a "# b"##"###), r##"a "# b"##);
+    }
+
+    #[test]
+    fn reports_invalid_escapes() {
+        assert_eq!(
+            errors(r"a\qb"),
+            vec![(1..3, "Unknown character escape `\\q`".into())]
+        );
+        assert_eq!(errors(r"\u{110000}").len(), 1);
+        assert_eq!(errors(r"\u{}").len(), 1);
+        assert_eq!(errors(r"\u41").len(), 1);
+        assert!(errors(r"\u{41}\0").is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+//! Conversions between the byte offsets used by the compiler and the
+//! line/column positions used by the language server protocol.
+//! Columns are counted in UTF-16 code units as required by the protocol.
+use errors::{Diagnostic, FileId, Severity};
+use lsp_types::{DiagnosticSeverity, DocumentSymbol, Position, Range, SymbolKind};
+use semant::ide;
+use syntax::{TextRange, TextUnit};
+
+pub(crate) fn position(text: &str, offset: usize) -> Position {
+    let offset = offset.min(text.len());
+    let line_start = text[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+    let line = text[..line_start].matches('\n').count();
+    let character: usize = text[line_start..offset].chars().map(char::len_utf16).sum();
+
+    Position::new(line as u32, character as u32)
+}
+
+pub(crate) fn offset(text: &str, position: Position) -> TextUnit {
+    let line_start = if position.line == 0 {
+        0
+    } else {
+        text.match_indices('\n')
+            .nth(position.line as usize - 1)
+            .map(|(i, _)| i + 1)
+            .unwrap_or_else(|| text.len())
+    };
+
+    let mut column = 0;
+    let mut offset = line_start;
+
+    for c in text[line_start..].chars() {
+        if column >= position.character as usize || c == '\n' {
+            break;
+        }
+
+        column += c.len_utf16();
+        offset += c.len_utf8();
+    }
+
+    TextUnit::from_usize(offset)
+}
+
+pub(crate) fn range(text: &str, range: TextRange) -> Range {
+    Range::new(
+        position(text, range.start().to_usize()),
+        position(text, range.end().to_usize()),
+    )
+}
+
+pub(crate) fn diagnostic(text: &str, diagnostic: &Diagnostic<FileId>) -> lsp_types::Diagnostic {
+    let span = diagnostic
+        .labels
+        .first()
+        .map(|label| label.range.clone())
+        .unwrap_or(0..0);
+
+    let severity = match diagnostic.severity {
+        Severity::Bug | Severity::Error => DiagnosticSeverity::ERROR,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+        Severity::Note => DiagnosticSeverity::INFORMATION,
+        Severity::Help => DiagnosticSeverity::HINT,
+    };
+
+    let mut message = diagnostic.message.clone();
+
+    for note in diagnostic.notes.iter().filter(|note| !note.is_empty()) {
+        message.push('\n');
+        message.push_str(note);
+    }
+
+    lsp_types::Diagnostic {
+        range: Range::new(position(text, span.start), position(text, span.end)),
+        severity: Some(severity),
+        source: Some("tox".into()),
+        message,
+        ..lsp_types::Diagnostic::default()
+    }
+}
+
+pub(crate) fn symbol(text: &str, symbol: ide::Symbol) -> DocumentSymbol {
+    let kind = match symbol.kind {
+        ide::SymbolKind::Function => SymbolKind::FUNCTION,
+        ide::SymbolKind::Method => SymbolKind::METHOD,
+        ide::SymbolKind::Class => SymbolKind::CLASS,
+        ide::SymbolKind::Field => SymbolKind::FIELD,
+        ide::SymbolKind::Enum => SymbolKind::ENUM,
+        ide::SymbolKind::Variant => SymbolKind::ENUM_MEMBER,
+        ide::SymbolKind::TypeAlias => SymbolKind::TYPE_PARAMETER,
+        ide::SymbolKind::Module => SymbolKind::MODULE,
+        ide::SymbolKind::Trait => SymbolKind::INTERFACE,
+        ide::SymbolKind::Impl => SymbolKind::OBJECT,
+    };
+
+    let children = symbol
+        .children
+        .into_iter()
+        .map(|child| self::symbol(text, child))
+        .collect::<Vec<_>>();
+
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name: symbol.name,
+        detail: None,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: range(text, symbol.range),
+        selection_range: range(text, symbol.selection_range),
+        children: if children.is_empty() {
+            None
+        } else {
+            Some(children)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{offset, position};
+    use lsp_types::Position;
+    use syntax::TextUnit;
+
+    #[test]
+    fn positions_round_trip() {
+        let text = "fn main() {\n    let s = \"é𝄞\";\n}\n";
+
+        let end_of_string = text.find("\";").unwrap();
+        let position_of_end = position(text, end_of_string);
+
+        // `é` is a single UTF-16 code unit and `𝄞` needs two
+        assert_eq!(position_of_end, Position::new(1, 16));
+        assert_eq!(
+            offset(text, position_of_end),
+            TextUnit::from_usize(end_of_string)
+        );
+        assert_eq!(position(text, 0), Position::new(0, 0));
+        assert_eq!(
+            offset(text, Position::new(2, 0)),
+            TextUnit::from_usize(text.len() - 2)
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use salsa::Durability;
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[salsa::database(
+    semant::HirDatabaseStorage,
+    semant::InternDatabaseStorage,
+    parser::ParseDatabaseStorage,
+    errors::FileDatabaseStorage
+)]
+#[derive(Debug, Default)]
+pub struct DatabaseImpl {
+    runtime: salsa::Runtime<DatabaseImpl>,
+    /// The contents of the files that are open in the editor
+    overlays: HashMap<PathBuf, String>,
+}
+
+impl salsa::Database for DatabaseImpl {
+    fn salsa_runtime(&self) -> &salsa::Runtime<DatabaseImpl> {
+        &self.runtime
+    }
+
+    fn salsa_runtime_mut(&mut self) -> &mut salsa::Runtime<DatabaseImpl> {
+        &mut self.runtime
+    }
+}
+
+impl errors::FileSystem for DatabaseImpl {
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        match self.overlays.get(path) {
+            Some(contents) => Ok(contents.clone()),
+            None => fs::read_to_string(path),
+        }
+    }
+}
+
+impl DatabaseImpl {
+    /// Replaces the contents of a file with the unsaved contents from the editor
+    pub(crate) fn set_overlay(&mut self, path: PathBuf, contents: String) {
+        self.overlays.insert(path, contents);
+        self.salsa_runtime_mut().synthetic_write(Durability::LOW);
+    }
+
+    pub(crate) fn remove_overlay(&mut self, path: &Path) {
+        self.overlays.remove(path);
+        self.salsa_runtime_mut().synthetic_write(Durability::LOW);
+    }
+}
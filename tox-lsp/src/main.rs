@@ -0,0 +1,16 @@
+mod convert;
+mod db;
+mod server;
+#[cfg(test)]
+mod tests;
+
+use lsp_server::Connection;
+
+fn main() -> server::ServerResult<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    server::run(connection)?;
+    io_threads.join()?;
+
+    Ok(())
+}
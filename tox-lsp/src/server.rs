@@ -0,0 +1,257 @@
+use crate::{convert, db::DatabaseImpl};
+use errors::{db::FileDatabase, FileId};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as _},
+    DocumentSymbolResponse, GotoDefinitionResponse, Hover, HoverContents, Location, MarkupContent,
+    MarkupKind, PublishDiagnosticsParams, ServerCapabilities, TextDocumentPositionParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use parser::ParseDatabase;
+use semant::{ide, HirDatabase};
+use std::{collections::HashSet, error::Error, path::PathBuf};
+
+pub type ServerResult<T> = Result<T, Box<dyn Error + Sync + Send>>;
+
+struct Server {
+    connection: Connection,
+    db: DatabaseImpl,
+    /// The documents that are currently open in the editor
+    open: HashSet<Url>,
+}
+
+/// Runs the language server until the client asks it to exit
+pub fn run(connection: Connection) -> ServerResult<()> {
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(true.into()),
+        definition_provider: Some(lsp_types::OneOf::Left(true)),
+        document_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+        ..ServerCapabilities::default()
+    };
+
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    let mut server = Server {
+        connection,
+        db: DatabaseImpl::default(),
+        open: HashSet::new(),
+    };
+
+    server.main_loop()
+}
+
+fn path(uri: &Url) -> ServerResult<PathBuf> {
+    uri.to_file_path()
+        .map_err(|_| format!("`{}` is not a file path", uri).into())
+}
+
+impl Server {
+    fn main_loop(&mut self) -> ServerResult<()> {
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+
+                    self.on_request(request)?
+                }
+                Message::Notification(notification) => self.on_notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_request(&mut self, request: Request) -> ServerResult<()> {
+        let response = match request.method.as_str() {
+            HoverRequest::METHOD => self.handle::<HoverRequest, _>(request, Self::hover)?,
+            GotoDefinition::METHOD => {
+                self.handle::<GotoDefinition, _>(request, Self::goto_definition)?
+            }
+            DocumentSymbolRequest::METHOD => {
+                self.handle::<DocumentSymbolRequest, _>(request, Self::document_symbols)?
+            }
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unknown request `{}`", request.method),
+            ),
+        };
+
+        self.connection.sender.send(Message::Response(response))?;
+
+        Ok(())
+    }
+
+    fn handle<R, F>(&self, request: Request, f: F) -> ServerResult<Response>
+    where
+        R: lsp_types::request::Request,
+        F: FnOnce(&Self, R::Params) -> ServerResult<R::Result>,
+    {
+        let (id, params): (RequestId, R::Params) = request.extract(R::METHOD)?;
+
+        Ok(match f(self, params) {
+            Ok(result) => Response::new_ok(id, result),
+            Err(e) => Response::new_err(id, ErrorCode::InternalError as i32, e.to_string()),
+        })
+    }
+
+    fn on_notification(&mut self, notification: Notification) -> ServerResult<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+
+                self.db.set_overlay(path(&uri)?, params.text_document.text);
+                self.open.insert(uri);
+                self.publish_all_diagnostics()
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+
+                // We only ask for full syncs so the last change contains the whole document
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.db
+                        .set_overlay(path(&params.text_document.uri)?, change.text);
+                }
+
+                self.publish_all_diagnostics()
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+
+                self.db.remove_overlay(&path(&uri)?);
+                self.open.remove(&uri);
+                self.publish(uri, Vec::new())?;
+                self.publish_all_diagnostics()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn file(&self, uri: &Url) -> ServerResult<FileId> {
+        Ok(self.db.intern_file(path(uri)?))
+    }
+
+    /// Syntax errors don't stop the later stages so they are reported together
+    /// with the errors of the resolver or, when the file resolves, the type checker
+    fn diagnostics(&self, file: FileId) -> Vec<lsp_types::Diagnostic> {
+        let source = self.db.source(file);
+
+        let (_, mut diagnostics) = self.db.parse(file);
+
+        match self.db.resolve_source_file(file) {
+            Ok(resolver) => {
+                diagnostics.extend(resolver.warnings().iter().cloned());
+
+                match self.db.infer(file) {
+                    Ok(result) => diagnostics.extend(result.warnings().iter().cloned()),
+                    Err(errors) => diagnostics.extend(errors),
+                }
+            }
+            Err(errors) => diagnostics.extend(errors),
+        }
+
+        diagnostics
+            .iter()
+            .filter(|diagnostic| {
+                diagnostic
+                    .labels
+                    .first()
+                    .map_or(true, |label| label.file_id == file)
+            })
+            .map(|diagnostic| convert::diagnostic(&source, diagnostic))
+            .collect()
+    }
+
+    /// An edit in one file can introduce errors in the files that import it
+    fn publish_all_diagnostics(&self) -> ServerResult<()> {
+        for uri in &self.open {
+            let diagnostics = self.diagnostics(self.file(uri)?);
+            self.publish(uri.clone(), diagnostics)?;
+        }
+
+        Ok(())
+    }
+
+    fn publish(&self, uri: Url, diagnostics: Vec<lsp_types::Diagnostic>) -> ServerResult<()> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+
+        self.connection
+            .sender
+            .send(Message::Notification(Notification::new(
+                PublishDiagnostics::METHOD.into(),
+                params,
+            )))?;
+
+        Ok(())
+    }
+
+    fn hover(&self, params: lsp_types::HoverParams) -> ServerResult<Option<Hover>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params.text_document_position_params;
+
+        let file = self.file(&text_document.uri)?;
+        let source = self.db.source(file);
+
+        Ok(
+            ide::hover(&self.db, file, convert::offset(&source, position)).map(|hover| Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::PlainText,
+                    value: hover.contents,
+                }),
+                range: Some(convert::range(&source, hover.range)),
+            }),
+        )
+    }
+
+    fn goto_definition(
+        &self,
+        params: lsp_types::GotoDefinitionParams,
+    ) -> ServerResult<Option<GotoDefinitionResponse>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params.text_document_position_params;
+
+        let file = self.file(&text_document.uri)?;
+        let source = self.db.source(file);
+
+        Ok(
+            ide::goto_definition(&self.db, file, convert::offset(&source, position)).map(|range| {
+                GotoDefinitionResponse::Scalar(Location::new(
+                    text_document.uri,
+                    convert::range(&source, range),
+                ))
+            }),
+        )
+    }
+
+    fn document_symbols(
+        &self,
+        params: lsp_types::DocumentSymbolParams,
+    ) -> ServerResult<Option<DocumentSymbolResponse>> {
+        let file = self.file(&params.text_document.uri)?;
+        let source = self.db.source(file);
+
+        let symbols = ide::document_symbols(&self.db, file)
+            .into_iter()
+            .map(|symbol| convert::symbol(&source, symbol))
+            .collect();
+
+        Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+    }
+}
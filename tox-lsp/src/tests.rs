@@ -0,0 +1,239 @@
+//! Drives the server through an in memory connection the same way an editor would
+use crate::server;
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidOpenTextDocument, Exit, Initialized, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{
+        DocumentSymbolRequest, GotoDefinition, HoverRequest, Initialize, Request as _, Shutdown,
+    },
+    DocumentSymbolResponse, GotoDefinitionResponse, Hover, HoverContents, Position,
+    PublishDiagnosticsParams, Url,
+};
+use serde_json::{json, Value};
+use std::thread::{self, JoinHandle};
+use tempfile::TempDir;
+
+const PROGRAM: &str = "class Point { x:i32; y:i32; }
+fn add(a:i32, b:i32) -> i32 { return a+b; }
+export fn main() -> i32 {
+    let total = add(1, 2);
+    let p = Point { x:total, y:2 };
+    return p.x;
+}";
+
+struct Client {
+    connection: Connection,
+    server: Option<JoinHandle<()>>,
+    next_id: i32,
+    uri: Url,
+    // The documents only exist in memory but the directory keeps the paths unique
+    _dir: TempDir,
+}
+
+impl Client {
+    fn new() -> Client {
+        let (client, server) = Connection::memory();
+
+        let handle = thread::spawn(move || server::run(server).unwrap());
+
+        let dir = tempfile::tempdir().unwrap();
+        let uri = Url::from_file_path(dir.path().join("main.tox")).unwrap();
+
+        let mut client = Client {
+            connection: client,
+            server: Some(handle),
+            next_id: 0,
+            uri,
+            _dir: dir,
+        };
+
+        client.request(Initialize::METHOD, json!({ "capabilities": {} }));
+        client.notify(Initialized::METHOD, json!({}));
+        client
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        self.connection
+            .sender
+            .send(Message::Notification(Notification::new(
+                method.into(),
+                params,
+            )))
+            .unwrap();
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+
+        let id = RequestId::from(self.next_id);
+
+        self.connection
+            .sender
+            .send(Message::Request(Request::new(
+                id.clone(),
+                method.into(),
+                params,
+            )))
+            .unwrap();
+
+        loop {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Response(Response {
+                    id: response_id,
+                    result,
+                    error,
+                }) if response_id == id => {
+                    assert!(error.is_none(), "{:?}", error);
+                    return result.unwrap_or(Value::Null);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn diagnostics(&self) -> PublishDiagnosticsParams {
+        loop {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Notification(notification)
+                    if notification.method == PublishDiagnostics::METHOD =>
+                {
+                    return serde_json::from_value(notification.params).unwrap();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn open(&self, text: &str) -> PublishDiagnosticsParams {
+        self.notify(
+            DidOpenTextDocument::METHOD,
+            json!({
+                "textDocument": { "uri": self.uri, "languageId": "tox", "version": 0, "text": text }
+            }),
+        );
+
+        self.diagnostics()
+    }
+
+    fn position_params(&self, position: Position) -> Value {
+        json!({ "textDocument": { "uri": self.uri }, "position": position })
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        self.request(Shutdown::METHOD, Value::Null);
+        self.notify(Exit::METHOD, Value::Null);
+
+        if let Some(server) = self.server.take() {
+            server.join().unwrap();
+        }
+    }
+}
+
+#[test]
+fn valid_program_has_no_diagnostics() {
+    let client = Client::new();
+
+    let published = client.open(PROGRAM);
+
+    assert_eq!(published.uri, client.uri);
+    assert!(published.diagnostics.is_empty());
+}
+
+#[test]
+fn publishes_type_errors() {
+    let client = Client::new();
+
+    assert!(client.open(PROGRAM).diagnostics.is_empty());
+
+    client.notify(
+        DidChangeTextDocument::METHOD,
+        json!({
+            "textDocument": { "uri": client.uri, "version": 1 },
+            "contentChanges": [{ "text": "export fn main() -> i32 {\n    return true;\n}" }]
+        }),
+    );
+
+    let published = client.diagnostics();
+
+    assert_eq!(published.diagnostics.len(), 1);
+    assert_eq!(published.diagnostics[0].range.start.line, 1);
+}
+
+#[test]
+fn syntax_errors_do_not_hide_other_errors() {
+    let client = Client::new();
+
+    let published = client.open("export fn main() {\n    let x = 1 + ;\n    return y;\n}");
+
+    let lines = published
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.range.start.line)
+        .collect::<Vec<_>>();
+
+    assert!(lines.contains(&1));
+    assert!(lines.contains(&2));
+}
+
+#[test]
+fn hover() {
+    let mut client = Client::new();
+    client.open(PROGRAM);
+
+    // The `total` in `x:total`
+    let params = client.position_params(Position::new(4, 23));
+    let hover: Hover =
+        serde_json::from_value(client.request(HoverRequest::METHOD, params)).unwrap();
+
+    match hover.contents {
+        HoverContents::Markup(markup) => assert_eq!(markup.value, "total: i32"),
+        contents => panic!("unexpected hover contents {:?}", contents),
+    }
+}
+
+#[test]
+fn goto_definition() {
+    let mut client = Client::new();
+    client.open(PROGRAM);
+
+    // The `add` in `add(1, 2)`
+    let params = client.position_params(Position::new(3, 17));
+    let response: GotoDefinitionResponse =
+        serde_json::from_value(client.request(GotoDefinition::METHOD, params)).unwrap();
+
+    match response {
+        GotoDefinitionResponse::Scalar(location) => {
+            assert_eq!(location.uri, client.uri);
+            assert_eq!(location.range.start, Position::new(1, 3));
+        }
+        response => panic!("unexpected definition {:?}", response),
+    }
+}
+
+#[test]
+fn document_symbols() {
+    let mut client = Client::new();
+    client.open(PROGRAM);
+
+    let params = json!({ "textDocument": { "uri": client.uri } });
+    let response: DocumentSymbolResponse =
+        serde_json::from_value(client.request(DocumentSymbolRequest::METHOD, params)).unwrap();
+
+    let symbols = match response {
+        DocumentSymbolResponse::Nested(symbols) => symbols,
+        response => panic!("unexpected symbols {:?}", response),
+    };
+
+    let names = symbols
+        .iter()
+        .map(|symbol| symbol.name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, vec!["Point", "add", "main"]);
+    assert_eq!(symbols[0].children.as_ref().map(Vec::len), Some(2));
+}
@@ -1,6 +1,7 @@
 extern crate console_error_panic_hook;
 extern crate wasm_bindgen;
 use errors::{emit, ColorChoice, Config, Diagnostic, FileDatabase, FileId, Files, StandardStream};
+use parser::ParseDatabase;
 use semant::HirDatabase;
 use std::default::Default;
 use std::io::{self};
@@ -47,6 +48,8 @@ impl salsa::Database for DatabaseImpl {
     }
 }
 
+impl errors::FileSystem for DatabaseImpl {}
+
 impl<'files> Files<'files> for DatabaseImpl {
     type FileId = FileId;
     type Name = String;
@@ -77,8 +80,11 @@ pub fn parse(contents: String) {
 
     let handle = db.intern_content(contents);
 
+    let (_, syntax_errors) = db.parse(handle);
+    errors.extend(syntax_errors);
+
     match db.resolve_source_file(handle) {
-        Ok(_) => {}
+        Ok(resolver) => errors.extend(resolver.warnings().iter().cloned()),
         Err(more_errs) => errors.extend(more_errs),
     }
 
@@ -1,7 +1,7 @@
 use crate::db::{DatabaseImpl, Diagnostics};
 use errors::FileDatabase;
 use parser::{dump_debug, ParseDatabase};
-use semant::HirDatabase;
+use semant::{HirDatabase, Value};
 use std::fs::File;
 use std::io::{self, Write};
 use std::path::PathBuf;
@@ -20,6 +20,12 @@ pub struct Cli {
 
     #[structopt(short, long)]
     pub ast: bool,
+    /// Run the exported `main` function of each file
+    #[structopt(short, long)]
+    pub run: bool,
+    /// Run the exported `main` function of each file using the bytecode vm
+    #[structopt(long)]
+    pub vm: bool,
 }
 
 impl Cli {
@@ -28,19 +34,19 @@ impl Cli {
         let mut errors = Vec::new();
 
         for path in self.source {
-            let handle = db.intern_file(path);
-
-            let tokens = match db.lex(handle) {
-                Ok(source_file) => source_file,
-                Err(more_errors) => {
-                    errors.extend(more_errors);
+            // The database treats a file it can't read as empty so report it here instead
+            if let Err(error) = File::open(&path) {
+                return Err(io::Error::new(
+                    error.kind(),
+                    format!("Couldn't read `{}`: {}", path.display(), error),
+                ));
+            }
 
-                    db.emit(&mut errors)?;
-                    continue;
-                }
-            };
+            let handle = db.intern_file(path);
 
             if self.lex {
+                let (tokens, _) = db.lex(handle);
+
                 if let Some(ref output) = self.output {
                     write!(&mut File::open(output)?, "{:#?}", tokens)?;
                 } else {
@@ -48,13 +54,12 @@ impl Cli {
                 }
             }
 
-            let source_file = match db.parse(handle) {
-                Ok(source_file) => source_file,
-                Err(ref mut more_errors) => {
-                    db.emit(more_errors)?;
-                    continue;
-                }
-            };
+            // Syntax errors are reported but the later stages still run
+            // so that the errors they find are shown as well
+            let (source_file, syntax_errors) = db.parse(handle);
+            let has_syntax_errors = !syntax_errors.is_empty();
+
+            errors.extend(syntax_errors);
 
             if self.ast {
                 if let Some(ref output) = self.output {
@@ -64,27 +69,48 @@ impl Cli {
                 }
             }
 
-            let _hir = match db.lower(handle) {
-                Ok(program) => program,
-                Err(ref mut more_errors) => {
-                    db.emit(more_errors)?;
+            match db.resolve_source_file(handle) {
+                Ok(resolver) => errors.extend(resolver.warnings().iter().cloned()),
+                Err(more_errors) => {
+                    errors.extend(more_errors);
+                    db.emit(&mut errors)?;
                     continue;
                 }
-            };
+            }
 
-            match db.resolve_source_file(handle) {
-                Ok(_) => {}
+            match db.infer(handle) {
+                Ok(result) => errors.extend(result.warnings().iter().cloned()),
                 Err(more_errors) => {
                     errors.extend(more_errors);
+                    db.emit(&mut errors)?;
+                    continue;
                 }
             }
 
-            // match db.infer(handle) {
-            //     Ok(_) => {}
-            //     Err(more_errors) => {
-            //         errors.extend(more_errors);
-            //     }
-            // }
+            if has_syntax_errors {
+                db.emit(&mut errors)?;
+                continue;
+            }
+
+            if self.run {
+                match semant::interpret(&db, handle) {
+                    Ok(Value::Nil) => {}
+                    Ok(value) => println!("{}", value.display(&db)),
+                    Err(more_errors) => {
+                        errors.extend(more_errors);
+                    }
+                }
+            }
+
+            if self.vm {
+                match db.compile(handle).and_then(|program| vm::run(&program)) {
+                    Ok(vm::Value::Nil) => {}
+                    Ok(value) => println!("{}", value),
+                    Err(more_errors) => {
+                        errors.extend(more_errors);
+                    }
+                }
+            }
 
             db.emit(&mut errors)?;
         }
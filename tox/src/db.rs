@@ -41,6 +41,8 @@ impl salsa::Database for DatabaseImpl {
     }
 }
 
+impl errors::FileSystem for DatabaseImpl {}
+
 impl<'files> Files<'files> for DatabaseImpl {
     type FileId = FileId;
     type Name = String;
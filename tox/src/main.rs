@@ -10,7 +10,10 @@ pub type ParseResult<T> = Result<T, ()>;
 fn main() -> std::io::Result<()> {
     let opt = Cli::from_args();
 
-    opt.run()?;
-
-    Ok(())
+    // The interpreter evaluates calls recursively so it needs a bigger stack than the main thread has
+    std::thread::Builder::new()
+        .stack_size(semant::INTERPRETER_STACK_SIZE)
+        .spawn(move || opt.run())?
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
 }
@@ -0,0 +1,366 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+/// The instructions understood by the vm.
+/// Each opcode is a single byte and any operands are stored in the bytes that follow it.
+/// `u16` operands are stored big endian
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum OpCode {
+    /// Push `constants[u16]`
+    Constant,
+    Nil,
+    True,
+    False,
+    Pop,
+    /// Push a copy of the value on top of the stack
+    Dup,
+    /// Push `locals[u8]`
+    GetLocal,
+    /// Pop the top of the stack into `locals[u8]`
+    SetLocal,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    /// Pop two ints and push an array of the ints from the first up to the second
+    Range,
+    /// Like [OpCode::Range] but the array includes the second int
+    RangeInclusive,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Negate,
+    Not,
+    /// Report an overflow if the int on top of the stack doesn't fit into an `i32`.
+    /// Ints are stored as `i64`s so this follows the arithmetic on `i32`s
+    CheckInt32,
+    /// Jump `u16` bytes forward
+    Jump,
+    /// Pop the condition and jump `u16` bytes forward if it is falsy
+    JumpIfFalse,
+    /// Jump `u16` bytes backwards
+    Loop,
+    /// Call the function that is below the `u8` arguments on the stack
+    Call,
+    /// Call the `u16` method of the instance that is below the `u8` arguments on the stack,
+    /// passing the instance as the first argument
+    Invoke,
+    Return,
+    /// Pop `u8` values into a tuple
+    Tuple,
+    /// Pop `u16` values into an array
+    Array,
+    /// Pop the count and the value and push an array holding count copies of the value
+    ArrayRepeat,
+    /// Push the `u8` element of the tuple on top of the stack
+    TupleGet,
+    /// Push the `u8` element of the tuple on top of the stack counting back from the last element
+    TupleGetBack,
+    Index,
+    /// Pop the value, the index and the array and push the updated array
+    SetIndex,
+    /// Push the field `constants[u16]` of the instance on top of the stack
+    GetField,
+    /// Pop the value and the instance and push the updated instance
+    SetField,
+    /// Create the variant `constants[u16]::constants[u16]` which holds a value if the `u8` is set
+    Enum,
+    /// Pop an enum and push whether it is the variant `constants[u16]`
+    IsVariant,
+    /// Pop an enum and push the value its variant holds
+    VariantValue,
+    /// Advance the `(array, index)` iterator held in local `u8` and push
+    /// `Option::Some` of the next element or `Option::None` once the array runs out
+    IterNext,
+    /// Create an instance of `constants[u16]` from `u8` values followed by a
+    /// `u16` name constant per field
+    Instance,
+    /// Convert the value on top of the stack into the `u8` [CastKind]
+    Cast,
+    /// Report that no match arm matched the value on top of the stack
+    NoMatch,
+    /// Report the runtime error `constants[u16]`
+    Error,
+}
+
+const OPCODES: [OpCode; 52] = [
+    OpCode::Constant,
+    OpCode::Nil,
+    OpCode::True,
+    OpCode::False,
+    OpCode::Pop,
+    OpCode::Dup,
+    OpCode::GetLocal,
+    OpCode::SetLocal,
+    OpCode::Add,
+    OpCode::Sub,
+    OpCode::Mul,
+    OpCode::Div,
+    OpCode::Rem,
+    OpCode::BitAnd,
+    OpCode::BitOr,
+    OpCode::BitXor,
+    OpCode::Shl,
+    OpCode::Shr,
+    OpCode::Range,
+    OpCode::RangeInclusive,
+    OpCode::Equal,
+    OpCode::NotEqual,
+    OpCode::Less,
+    OpCode::LessEqual,
+    OpCode::Greater,
+    OpCode::GreaterEqual,
+    OpCode::Negate,
+    OpCode::Not,
+    OpCode::CheckInt32,
+    OpCode::Jump,
+    OpCode::JumpIfFalse,
+    OpCode::Loop,
+    OpCode::Call,
+    OpCode::Invoke,
+    OpCode::Return,
+    OpCode::Tuple,
+    OpCode::Array,
+    OpCode::ArrayRepeat,
+    OpCode::TupleGet,
+    OpCode::TupleGetBack,
+    OpCode::Index,
+    OpCode::SetIndex,
+    OpCode::GetField,
+    OpCode::SetField,
+    OpCode::Enum,
+    OpCode::IsVariant,
+    OpCode::VariantValue,
+    OpCode::IterNext,
+    OpCode::Instance,
+    OpCode::Cast,
+    OpCode::NoMatch,
+    OpCode::Error,
+];
+
+impl OpCode {
+    pub fn from_byte(byte: u8) -> OpCode {
+        OPCODES[byte as usize]
+    }
+}
+
+/// The type a value is converted into by [OpCode::Cast]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CastKind {
+    Int,
+    Float,
+    Str,
+    Int64,
+    Float64,
+}
+
+/// A value known at compile time
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    Int(i64),
+    Float(f64),
+    Char(char),
+    Str(String),
+    /// A reference to a top level function which is resolved when the program is loaded
+    Function(String),
+}
+
+// Floats are only compared to deduplicate the constant pool
+impl Eq for Constant {}
+
+/// The compiled instructions of a function along with its constant pool
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Chunk {
+    pub code: Vec<u8>,
+    pub constants: Vec<Constant>,
+    /// The offset of the first instruction that belongs to each span
+    spans: Vec<(usize, (usize, usize))>,
+}
+
+impl Chunk {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(&mut self, op: OpCode, span: (usize, usize)) {
+        if self.spans.last().map(|(_, last)| *last) != Some(span) {
+            self.spans.push((self.code.len(), span));
+        }
+
+        self.code.push(op as u8);
+    }
+
+    pub fn write_byte(&mut self, byte: u8) {
+        self.code.push(byte);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.code.extend_from_slice(&value.to_be_bytes());
+    }
+
+    pub fn read_u16(&self, offset: usize) -> u16 {
+        u16::from_be_bytes([self.code[offset], self.code[offset + 1]])
+    }
+
+    /// Adds a constant to the pool, reusing an existing entry if the constant has already been added.
+    /// Returns `None` when the pool is full
+    pub fn add_constant(&mut self, constant: Constant) -> Option<u16> {
+        if let Some(index) = self.constants.iter().position(|c| c == &constant) {
+            return u16::try_from(index).ok();
+        }
+
+        let index = u16::try_from(self.constants.len()).ok()?;
+        self.constants.push(constant);
+
+        Some(index)
+    }
+
+    /// The source span of the instruction at `offset`
+    pub fn span(&self, offset: usize) -> (usize, usize) {
+        let index = match self
+            .spans
+            .binary_search_by_key(&offset, |(start, _)| *start)
+        {
+            Ok(index) => index,
+            Err(index) => index.saturating_sub(1),
+        };
+
+        self.spans
+            .get(index)
+            .map(|(_, span)| *span)
+            .unwrap_or((0, 0))
+    }
+
+    fn instruction(&self, f: &mut fmt::Formatter, offset: usize) -> Result<usize, fmt::Error> {
+        let op = OpCode::from_byte(self.code[offset]);
+
+        write!(f, "{:04} {:?}", offset, op)?;
+
+        let next = match op {
+            OpCode::Constant
+            | OpCode::GetField
+            | OpCode::SetField
+            | OpCode::IsVariant
+            | OpCode::Error => {
+                let index = self.read_u16(offset + 1);
+                write!(f, " {} {:?}", index, self.constants[index as usize])?;
+                offset + 3
+            }
+            OpCode::GetLocal
+            | OpCode::SetLocal
+            | OpCode::Call
+            | OpCode::Tuple
+            | OpCode::TupleGet
+            | OpCode::TupleGetBack
+            | OpCode::IterNext
+            | OpCode::Cast => {
+                write!(f, " {}", self.code[offset + 1])?;
+                offset + 2
+            }
+            OpCode::Array => {
+                write!(f, " {}", self.read_u16(offset + 1))?;
+                offset + 3
+            }
+            OpCode::Jump | OpCode::JumpIfFalse => {
+                let jump = self.read_u16(offset + 1) as usize;
+                write!(f, " -> {:04}", offset + 3 + jump)?;
+                offset + 3
+            }
+            OpCode::Loop => {
+                let jump = self.read_u16(offset + 1) as usize;
+                write!(f, " -> {:04}", offset + 3 - jump)?;
+                offset + 3
+            }
+            OpCode::Invoke => {
+                let name = self.read_u16(offset + 1);
+                write!(
+                    f,
+                    " {:?} {}",
+                    self.constants[name as usize],
+                    self.code[offset + 3]
+                )?;
+                offset + 4
+            }
+            OpCode::Enum => {
+                let def = self.read_u16(offset + 1);
+                let variant = self.read_u16(offset + 3);
+                write!(
+                    f,
+                    " {:?}::{:?} {}",
+                    self.constants[def as usize],
+                    self.constants[variant as usize],
+                    self.code[offset + 5]
+                )?;
+                offset + 6
+            }
+            OpCode::Instance => {
+                let def = self.read_u16(offset + 1);
+                let count = self.code[offset + 3] as usize;
+                write!(f, " {:?}", self.constants[def as usize])?;
+
+                for i in 0..count {
+                    let field = self.read_u16(offset + 4 + i * 2);
+                    write!(f, " {:?}", self.constants[field as usize])?;
+                }
+
+                offset + 4 + count * 2
+            }
+            _ => offset + 1,
+        };
+
+        writeln!(f)?;
+
+        Ok(next)
+    }
+}
+
+impl Display for Chunk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut offset = 0;
+
+        while offset < self.code.len() {
+            offset = self.instruction(f, offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chunk, Constant, OpCode};
+
+    #[test]
+    fn encode_and_disassemble() {
+        let mut chunk = Chunk::new();
+
+        let index = chunk.add_constant(Constant::Int(10)).unwrap();
+
+        chunk.write(OpCode::Constant, (0, 2));
+        chunk.write_u16(index);
+        chunk.write(OpCode::Constant, (3, 5));
+        let index = chunk.add_constant(Constant::Int(10)).unwrap();
+        chunk.write_u16(index);
+        chunk.write(OpCode::Add, (0, 5));
+        chunk.write(OpCode::Return, (0, 5));
+
+        assert_eq!(chunk.constants.len(), 1);
+        assert_eq!(chunk.span(4), (3, 5));
+        assert_eq!(chunk.span(7), (0, 5));
+        assert_eq!(
+            chunk.to_string(),
+            "0000 Constant 0 Int(10)\n0003 Constant 0 Int(10)\n0006 Add\n0007 Return\n"
+        );
+    }
+}
@@ -0,0 +1,9 @@
+mod chunk;
+mod machine;
+mod program;
+mod value;
+
+pub use chunk::{CastKind, Chunk, Constant, OpCode};
+pub use machine::run;
+pub use program::{Class, Function, Program};
+pub use value::{Instance, Value};
@@ -0,0 +1,827 @@
+use crate::{CastKind, Constant, Function, Instance, OpCode, Program, Value};
+use errors::{Reporter, WithError};
+use std::{collections::HashMap, convert::TryFrom, rc::Rc, sync::Arc};
+
+/// The maximum amount of nested function calls before we report a stack overflow.
+/// The frames live on the heap so this only stops a runaway recursion from using up the memory
+const MAX_FRAMES: usize = 64 * 1024;
+
+const STACK_UNDERFLOW: &str = "Stack underflow";
+
+/// A function along with its constant pool converted into runtime values
+#[derive(Debug)]
+struct Loaded {
+    function: Arc<Function>,
+    constants: Vec<Value>,
+}
+
+#[derive(Debug)]
+struct CallFrame {
+    function: Rc<Loaded>,
+    ip: usize,
+    /// The index of the first local slot of the function
+    base: usize,
+    /// The height of the stack when the function was called
+    stack_base: usize,
+    /// The local slot of the `for` loop whose iterator this `next` method advances,
+    /// the receiver is stored back into it on return
+    iterator: Option<usize>,
+}
+
+impl CallFrame {
+    fn read_byte(&mut self) -> u8 {
+        let byte = self.function.function.chunk.code[self.ip];
+        self.ip += 1;
+        byte
+    }
+
+    fn read_u16(&mut self) -> u16 {
+        let value = self.function.function.chunk.read_u16(self.ip);
+        self.ip += 2;
+        value
+    }
+
+    fn read_constant(&mut self) -> Value {
+        let index = self.read_u16() as usize;
+        self.function.constants[index].clone()
+    }
+
+    fn read_name(&mut self) -> Rc<str> {
+        match self.read_constant() {
+            Value::Str(name) => name,
+            value => value.to_string().into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct VM {
+    functions: Vec<Rc<Loaded>>,
+    /// The index of each method keyed by the class name and then the method name
+    methods: HashMap<String, HashMap<String, usize>>,
+    stack: Vec<Value>,
+    locals: Vec<Value>,
+    frames: Vec<CallFrame>,
+    reporter: Reporter,
+}
+
+impl VM {
+    fn new(program: &Program) -> Self {
+        let mut globals = HashMap::new();
+        let mut methods = HashMap::new();
+        let mut functions = Vec::new();
+
+        for function in &program.functions {
+            globals.insert(function.name.clone(), functions.len());
+            functions.push(function.clone());
+        }
+
+        for class in &program.classes {
+            let mut class_methods = HashMap::new();
+
+            for method in &class.methods {
+                class_methods.insert(method.name.clone(), functions.len());
+                functions.push(method.clone());
+            }
+
+            methods.insert(class.name.clone(), class_methods);
+        }
+
+        // Methods that aren't overridden are looked up in the superclass
+        for class in &program.classes {
+            let superclasses = std::iter::successors(class.superclass.as_ref(), |name| {
+                program
+                    .classes
+                    .iter()
+                    .find(|class| &class.name == *name)
+                    .and_then(|class| class.superclass.as_ref())
+            });
+
+            // A chain of superclasses without a cycle can't be longer than the amount of classes
+            for name in superclasses.take(program.classes.len()) {
+                let inherited = methods.get(name).cloned().unwrap_or_default();
+
+                let class_methods = methods.get_mut(&class.name).unwrap();
+
+                for (method, index) in inherited {
+                    class_methods.entry(method).or_insert(index);
+                }
+            }
+        }
+
+        // Static methods are called through their path
+        for (class, class_methods) in &methods {
+            for (method, index) in class_methods {
+                globals.insert(format!("{}::{}", class, method), *index);
+            }
+        }
+
+        let functions = functions
+            .into_iter()
+            .map(|function| {
+                let constants = function
+                    .chunk
+                    .constants
+                    .iter()
+                    .map(|constant| match constant {
+                        Constant::Int(i) => Value::Int(*i),
+                        Constant::Float(f) => Value::Float(*f),
+                        Constant::Char(c) => Value::Char(*c),
+                        Constant::Str(s) => Value::Str(s.as_str().into()),
+                        Constant::Function(name) => match globals.get(name.as_str()) {
+                            Some(index) => Value::Function {
+                                name: name.as_str().into(),
+                                index: *index,
+                            },
+                            None => Value::Nil,
+                        },
+                    })
+                    .collect();
+
+                Rc::new(Loaded {
+                    function,
+                    constants,
+                })
+            })
+            .collect();
+
+        Self {
+            functions,
+            methods,
+            stack: Vec::new(),
+            locals: Vec::new(),
+            frames: Vec::new(),
+            reporter: Reporter::new(program.file),
+        }
+    }
+
+    fn error(&mut self, msg: impl Into<String>, frame: &CallFrame, offset: usize) {
+        let span = frame.function.function.chunk.span(offset);
+        self.reporter.error(msg, "", span);
+    }
+
+    fn push(&mut self, value: Value) {
+        self.stack.push(value)
+    }
+
+    /// Sets up a new call frame for the function at `index`, taking the arguments from the stack
+    fn call(&mut self, index: usize, args: usize) -> Result<CallFrame, String> {
+        let function = self.functions[index].clone();
+
+        if function.function.arity != args {
+            return Err(format!(
+                "`{}` expected {} arguments but {} were supplied",
+                function.function.name, function.function.arity, args
+            ));
+        }
+
+        if self.frames.len() == MAX_FRAMES {
+            return Err("Stack overflow".into());
+        }
+
+        let base = self.locals.len();
+
+        self.locals
+            .resize(base + function.function.locals.max(args), Value::Nil);
+
+        let start = match self.stack.len().checked_sub(args) {
+            Some(start) => start,
+            None => return Err(STACK_UNDERFLOW.into()),
+        };
+
+        for (i, arg) in self.stack.drain(start..).enumerate() {
+            self.locals[base + i] = arg;
+        }
+
+        Ok(CallFrame {
+            function,
+            ip: 0,
+            base,
+            stack_base: self.stack.len(),
+            iterator: None,
+        })
+    }
+
+    fn execute(&mut self, mut frame: CallFrame) -> Result<Value, ()> {
+        loop {
+            let offset = frame.ip;
+
+            macro_rules! runtime_error {
+                ($msg:expr) => {{
+                    let msg = $msg;
+                    self.error(msg, &frame, offset);
+                    return Err(());
+                }};
+            }
+
+            // Bytecode that takes more values than are on the stack is a bug in the compiler,
+            // which is reported instead of reading past the values of the caller
+            macro_rules! pop {
+                () => {
+                    match self.stack.pop() {
+                        Some(value) => value,
+                        None => runtime_error!(STACK_UNDERFLOW),
+                    }
+                };
+            }
+
+            // The index of the value `depth` slots below the top of the stack
+            macro_rules! stack_index {
+                ($depth:expr) => {
+                    match self.stack.len().checked_sub($depth) {
+                        Some(index) => index,
+                        None => runtime_error!(STACK_UNDERFLOW),
+                    }
+                };
+            }
+
+            let op = OpCode::from_byte(frame.read_byte());
+
+            match op {
+                OpCode::Constant => {
+                    let value = frame.read_constant();
+                    self.push(value)
+                }
+                OpCode::Nil => self.push(Value::Nil),
+                OpCode::True => self.push(Value::Bool(true)),
+                OpCode::False => self.push(Value::Bool(false)),
+                OpCode::Pop => {
+                    pop!();
+                }
+                OpCode::Dup => {
+                    let value = pop!();
+                    self.push(value.clone());
+                    self.push(value)
+                }
+                OpCode::GetLocal => {
+                    let slot = frame.base + frame.read_byte() as usize;
+                    let value = self.locals[slot].clone();
+                    self.push(value)
+                }
+                OpCode::SetLocal => {
+                    let slot = frame.base + frame.read_byte() as usize;
+                    self.locals[slot] = pop!();
+                }
+                OpCode::Add
+                | OpCode::Sub
+                | OpCode::Mul
+                | OpCode::Div
+                | OpCode::Rem
+                | OpCode::BitAnd
+                | OpCode::BitOr
+                | OpCode::BitXor
+                | OpCode::Shl
+                | OpCode::Shr
+                | OpCode::Range
+                | OpCode::RangeInclusive
+                | OpCode::Equal
+                | OpCode::NotEqual
+                | OpCode::Less
+                | OpCode::LessEqual
+                | OpCode::Greater
+                | OpCode::GreaterEqual => {
+                    let rhs = pop!();
+                    let lhs = pop!();
+
+                    match binary_op(op, lhs, rhs) {
+                        Ok(value) => self.push(value),
+                        Err(msg) => runtime_error!(msg),
+                    }
+                }
+                OpCode::Negate => match pop!() {
+                    Value::Int(i) => match i.checked_neg() {
+                        Some(i) => self.push(Value::Int(i)),
+                        None => {
+                            runtime_error!(format!("Attempt to negate `{}` with overflow", i))
+                        }
+                    },
+                    Value::Float(f) => self.push(Value::Float(-f)),
+                    value => runtime_error!(format!("Cannot apply `-` to `{}`", value)),
+                },
+                OpCode::CheckInt32 => match self.stack.last() {
+                    Some(Value::Int(i)) if i32::try_from(*i).is_err() => {
+                        runtime_error!(format!("`{}` overflows an `i32`", i))
+                    }
+                    _ => {}
+                },
+                OpCode::Not => {
+                    let value = pop!();
+                    self.push(Value::Bool(!value.is_truthy()))
+                }
+                OpCode::Jump => {
+                    let jump = frame.read_u16() as usize;
+                    frame.ip += jump;
+                }
+                OpCode::JumpIfFalse => {
+                    let jump = frame.read_u16() as usize;
+
+                    if !pop!().is_truthy() {
+                        frame.ip += jump;
+                    }
+                }
+                OpCode::Loop => {
+                    let jump = frame.read_u16() as usize;
+                    frame.ip -= jump;
+                }
+                OpCode::Call => {
+                    let args = frame.read_byte() as usize;
+                    let callee = self.stack.remove(stack_index!(args + 1));
+
+                    match callee {
+                        Value::Function { index, .. } => match self.call(index, args) {
+                            Ok(callee) => {
+                                let caller = std::mem::replace(&mut frame, callee);
+                                self.frames.push(caller);
+                            }
+                            Err(msg) => runtime_error!(msg),
+                        },
+                        value => runtime_error!(format!("`{}` is not a function", value)),
+                    }
+                }
+                OpCode::Invoke => {
+                    let name = frame.read_name();
+                    // The receiver is passed as `self` in the first slot
+                    let args = frame.read_byte() as usize + 1;
+
+                    let receiver = stack_index!(args);
+
+                    let index = match &self.stack[receiver] {
+                        Value::Instance(instance) => self
+                            .methods
+                            .get(&*instance.def)
+                            .and_then(|methods| methods.get(&*name))
+                            .copied(),
+                        _ => None,
+                    };
+
+                    match index {
+                        Some(index) => match self.call(index, args) {
+                            Ok(callee) => {
+                                let caller = std::mem::replace(&mut frame, callee);
+                                self.frames.push(caller);
+                            }
+                            Err(msg) => runtime_error!(msg),
+                        },
+                        None => runtime_error!(format!(
+                            "`{}` doesn't have the method `{}`",
+                            self.stack[receiver], name
+                        )),
+                    }
+                }
+                OpCode::Return => {
+                    let value = pop!();
+
+                    if let Some(slot) = frame.iterator {
+                        let receiver = self.locals[frame.base].clone();
+
+                        if let Value::Tuple(state) = &mut self.locals[slot] {
+                            Rc::make_mut(state)[0] = receiver;
+                        }
+                    }
+
+                    self.locals.truncate(frame.base);
+                    self.stack.truncate(frame.stack_base);
+
+                    match self.frames.pop() {
+                        Some(caller) => {
+                            frame = caller;
+                            self.push(value);
+                        }
+                        None => return Ok(value),
+                    }
+                }
+                OpCode::Tuple | OpCode::Array => {
+                    let count = if op == OpCode::Tuple {
+                        frame.read_byte() as usize
+                    } else {
+                        frame.read_u16() as usize
+                    };
+
+                    let values = Rc::new(self.stack.split_off(stack_index!(count)));
+
+                    if op == OpCode::Tuple {
+                        self.push(Value::Tuple(values))
+                    } else {
+                        self.push(Value::Array(values))
+                    }
+                }
+                OpCode::ArrayRepeat => {
+                    let count = pop!();
+                    let value = pop!();
+
+                    match count {
+                        Value::Int(count) if count >= 0 => {
+                            self.push(Value::Array(Rc::new(vec![value; count as usize])))
+                        }
+                        count => runtime_error!(format!("Cannot repeat a value `{}` times", count)),
+                    }
+                }
+                OpCode::TupleGet => {
+                    let index = frame.read_byte() as usize;
+
+                    match pop!() {
+                        Value::Tuple(values) if index < values.len() => {
+                            self.push(values[index].clone())
+                        }
+                        value => runtime_error!(format!(
+                            "`{}` doesn't have an element at index {}",
+                            value, index
+                        )),
+                    }
+                }
+                OpCode::TupleGetBack => {
+                    let index = frame.read_byte() as usize;
+
+                    match pop!() {
+                        Value::Tuple(values) if index < values.len() => {
+                            self.push(values[values.len() - 1 - index].clone())
+                        }
+                        value => runtime_error!(format!(
+                            "`{}` doesn't have an element at index {} from the end",
+                            value, index
+                        )),
+                    }
+                }
+                OpCode::Index => {
+                    let index = pop!();
+                    let base = pop!();
+
+                    match (base, index) {
+                        (Value::Array(values), Value::Int(i)) => {
+                            if i < 0 || i as usize >= values.len() {
+                                runtime_error!(out_of_bounds(values.len(), i))
+                            }
+
+                            self.push(values[i as usize].clone())
+                        }
+                        (Value::Range { start, end }, Value::Int(i)) => {
+                            let len = end.saturating_sub(start).max(0);
+
+                            if i < 0 || i >= len {
+                                runtime_error!(out_of_bounds(len as usize, i))
+                            }
+
+                            self.push(Value::Int(start + i))
+                        }
+                        (base, _) => runtime_error!(format!("Cannot index into `{}`", base)),
+                    }
+                }
+                OpCode::SetIndex => {
+                    let value = pop!();
+                    let index = pop!();
+                    let base = pop!();
+
+                    match (base, index) {
+                        (Value::Array(mut values), Value::Int(i)) => {
+                            if i < 0 || i as usize >= values.len() {
+                                runtime_error!(out_of_bounds(values.len(), i))
+                            }
+
+                            Rc::make_mut(&mut values)[i as usize] = value;
+
+                            self.push(Value::Array(values))
+                        }
+                        (Value::Range { .. }, _) => {
+                            runtime_error!("The elements of a range can't be assigned to")
+                        }
+                        (base, _) => runtime_error!(format!("Cannot index into `{}`", base)),
+                    }
+                }
+                OpCode::GetField => {
+                    let name = frame.read_name();
+
+                    let value = match pop!() {
+                        Value::Instance(instance) => if let Some(value) = instance.field(&name) {
+                            Some(value.clone())
+                        } else {
+                            self.methods
+                                .get(&*instance.def)
+                                .and_then(|methods| methods.get(&*name))
+                                .map(|index| Value::Function {
+                                    name: name.clone(),
+                                    index: *index,
+                                })
+                        }
+                        .ok_or_else(|| Value::Instance(instance)),
+                        value => Err(value),
+                    };
+
+                    match value {
+                        Ok(value) => self.push(value),
+                        Err(value) => {
+                            runtime_error!(format!("`{}` doesn't have the field `{}`", value, name))
+                        }
+                    }
+                }
+                OpCode::SetField => {
+                    let name = frame.read_name();
+                    let value = pop!();
+
+                    match pop!() {
+                        Value::Instance(mut instance) => {
+                            match Rc::make_mut(&mut instance).field_mut(&name) {
+                                Some(field) => *field = value,
+                                None => runtime_error!(format!(
+                                    "`{}` doesn't have the field `{}`",
+                                    instance.def, name
+                                )),
+                            }
+
+                            self.push(Value::Instance(instance))
+                        }
+                        value => {
+                            runtime_error!(format!("`{}` doesn't have the field `{}`", value, name))
+                        }
+                    }
+                }
+                OpCode::Enum => {
+                    let def = frame.read_name();
+                    let variant = frame.read_name();
+
+                    let value = if frame.read_byte() == 1 {
+                        Some(Rc::new(pop!()))
+                    } else {
+                        None
+                    };
+
+                    self.push(Value::Enum {
+                        def,
+                        variant,
+                        value,
+                    })
+                }
+                OpCode::IsVariant => {
+                    let name = frame.read_name();
+
+                    let is_variant = match pop!() {
+                        Value::Enum { variant, .. } => variant == name,
+                        _ => false,
+                    };
+
+                    self.push(Value::Bool(is_variant))
+                }
+                OpCode::VariantValue => match pop!() {
+                    Value::Enum {
+                        value: Some(value), ..
+                    } => self.push((*value).clone()),
+                    value => runtime_error!(format!("`{}` doesn't hold a value", value)),
+                },
+                OpCode::IterNext => {
+                    let slot = frame.base + frame.read_byte() as usize;
+
+                    let next = match &mut self.locals[slot] {
+                        Value::Tuple(state) => match Rc::make_mut(state).as_mut_slice() {
+                            [Value::Array(values), Value::Int(index)] => {
+                                let next = values.get(*index as usize).cloned();
+                                *index += 1;
+                                next
+                            }
+                            [Value::Range { start, end }, Value::Int(index)] => {
+                                let next = start.checked_add(*index).filter(|next| next < end);
+                                *index += 1;
+                                next.map(Value::Int)
+                            }
+                            // A class is advanced by calling its `next` method
+                            [Value::Instance(instance), _] => {
+                                let instance = instance.clone();
+
+                                let index = self
+                                    .methods
+                                    .get(&*instance.def)
+                                    .and_then(|methods| methods.get("next"))
+                                    .copied();
+
+                                let index = match index {
+                                    Some(index) => index,
+                                    None => runtime_error!(format!(
+                                        "`{}` doesn't have the method `next`",
+                                        instance.def
+                                    )),
+                                };
+
+                                self.push(Value::Instance(instance));
+
+                                match self.call(index, 1) {
+                                    Ok(mut callee) => {
+                                        callee.iterator = Some(slot);
+
+                                        let caller = std::mem::replace(&mut frame, callee);
+                                        self.frames.push(caller);
+                                    }
+                                    Err(msg) => runtime_error!(msg),
+                                }
+
+                                continue;
+                            }
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+
+                    let variant = if next.is_some() { "Some" } else { "None" };
+
+                    self.push(Value::Enum {
+                        def: "Option".into(),
+                        variant: variant.into(),
+                        value: next.map(Rc::new),
+                    })
+                }
+                OpCode::Instance => {
+                    let def = frame.read_name();
+                    let count = frame.read_byte() as usize;
+
+                    let values = self.stack.split_off(stack_index!(count));
+                    let fields = values
+                        .into_iter()
+                        .map(|value| (frame.read_name(), value))
+                        .collect();
+
+                    self.push(Value::Instance(Rc::new(Instance { def, fields })))
+                }
+                OpCode::Cast => {
+                    let kind = frame.read_byte();
+                    let value = pop!();
+
+                    self.push(cast(kind, value))
+                }
+                OpCode::NoMatch => {
+                    let value = pop!();
+                    runtime_error!(format!("No match arm matched the value `{}`", value))
+                }
+                OpCode::Error => {
+                    let msg = frame.read_name();
+                    runtime_error!(msg.to_string())
+                }
+            }
+        }
+    }
+}
+
+fn out_of_bounds(len: usize, index: i64) -> String {
+    format!(
+        "Index out of bounds: the length is {} but the index is {}",
+        len, index
+    )
+}
+
+fn cast(kind: u8, value: Value) -> Value {
+    match value {
+        Value::Int(i) if kind == CastKind::Int as u8 => Value::Int(i64::from(i as i32)),
+        Value::Float(f) if kind == CastKind::Int as u8 => Value::Int(i64::from(f as i32)),
+        Value::Float(f) if kind == CastKind::Int64 as u8 => Value::Int(f as i64),
+        Value::Bool(b) if kind == CastKind::Int as u8 || kind == CastKind::Int64 as u8 => {
+            Value::Int(b as i64)
+        }
+        Value::Char(c) if kind == CastKind::Int as u8 || kind == CastKind::Int64 as u8 => {
+            Value::Int(c as i64)
+        }
+        Value::Int(i) if kind == CastKind::Float as u8 => Value::Float(f64::from(i as f32)),
+        Value::Float(f) if kind == CastKind::Float as u8 => Value::Float(f64::from(f as f32)),
+        Value::Int(i) if kind == CastKind::Float64 as u8 => Value::Float(i as f64),
+        value if kind == CastKind::Str as u8 => Value::Str(value.to_string().into()),
+        value => value,
+    }
+}
+
+fn symbol(op: OpCode) -> &'static str {
+    match op {
+        OpCode::Add => "+",
+        OpCode::Sub => "-",
+        OpCode::Mul => "*",
+        OpCode::Div => "/",
+        OpCode::Rem => "%",
+        OpCode::BitAnd => "&",
+        OpCode::BitOr => "|",
+        OpCode::BitXor => "^",
+        OpCode::Shl => "<<",
+        OpCode::Shr => ">>",
+        OpCode::Range => "..",
+        OpCode::RangeInclusive => "..=",
+        OpCode::Equal => "==",
+        OpCode::NotEqual => "!=",
+        OpCode::Less => "<",
+        OpCode::LessEqual => "<=",
+        OpCode::Greater => ">",
+        _ => ">=",
+    }
+}
+
+fn binary_op(op: OpCode, lhs: Value, rhs: Value) -> Result<Value, String> {
+    let value = match (op, &lhs, &rhs) {
+        (OpCode::Equal, lhs, rhs) => Value::Bool(lhs == rhs),
+        (OpCode::NotEqual, lhs, rhs) => Value::Bool(lhs != rhs),
+        (OpCode::Div, Value::Int(_), Value::Int(0)) => {
+            return Err("Attempt to divide by zero".into())
+        }
+        (OpCode::Rem, Value::Int(_), Value::Int(0)) => {
+            return Err("Attempt to calculate the remainder with a divisor of zero".into())
+        }
+        (op, Value::Int(l), Value::Int(r)) => {
+            let (l, r) = (*l, *r);
+
+            let value = match op {
+                OpCode::Add => l.checked_add(r).map(Value::Int),
+                OpCode::Sub => l.checked_sub(r).map(Value::Int),
+                OpCode::Mul => l.checked_mul(r).map(Value::Int),
+                OpCode::Div => l.checked_div(r).map(Value::Int),
+                OpCode::Rem => l.checked_rem(r).map(Value::Int),
+                OpCode::BitAnd => Some(Value::Int(l & r)),
+                OpCode::BitOr => Some(Value::Int(l | r)),
+                OpCode::BitXor => Some(Value::Int(l ^ r)),
+                OpCode::Shl => u32::try_from(r)
+                    .ok()
+                    .and_then(|r| l.checked_shl(r))
+                    .map(Value::Int),
+                OpCode::Shr => u32::try_from(r)
+                    .ok()
+                    .and_then(|r| l.checked_shr(r))
+                    .map(Value::Int),
+                OpCode::Range => Some(Value::Range { start: l, end: r }),
+                OpCode::RangeInclusive => {
+                    r.checked_add(1).map(|end| Value::Range { start: l, end })
+                }
+                OpCode::Less => Some(Value::Bool(l < r)),
+                OpCode::Greater => Some(Value::Bool(l > r)),
+                OpCode::LessEqual => Some(Value::Bool(l <= r)),
+                OpCode::GreaterEqual => Some(Value::Bool(l >= r)),
+                _ => return Err(invalid_operands(op, &lhs, &rhs)),
+            };
+
+            match value {
+                Some(value) => value,
+                None => {
+                    return Err(format!(
+                        "Attempt to `{} {} {}` with overflow",
+                        l,
+                        symbol(op),
+                        r
+                    ))
+                }
+            }
+        }
+        (op, Value::Float(l), Value::Float(r)) => match op {
+            OpCode::Add => Value::Float(l + r),
+            OpCode::Sub => Value::Float(l - r),
+            OpCode::Mul => Value::Float(l * r),
+            OpCode::Div => Value::Float(l / r),
+            OpCode::Rem => Value::Float(l % r),
+            OpCode::Less => Value::Bool(l < r),
+            OpCode::Greater => Value::Bool(l > r),
+            OpCode::LessEqual => Value::Bool(l <= r),
+            OpCode::GreaterEqual => Value::Bool(l >= r),
+            _ => return Err(invalid_operands(op, &lhs, &rhs)),
+        },
+        (op, Value::Char(l), Value::Char(r)) => match op {
+            OpCode::Less => Value::Bool(l < r),
+            OpCode::Greater => Value::Bool(l > r),
+            OpCode::LessEqual => Value::Bool(l <= r),
+            OpCode::GreaterEqual => Value::Bool(l >= r),
+            _ => return Err(invalid_operands(op, &lhs, &rhs)),
+        },
+        (op, Value::Str(l), Value::Str(r)) => match op {
+            OpCode::Add => Value::Str(format!("{}{}", l, r).into()),
+            OpCode::Less => Value::Bool(l < r),
+            OpCode::Greater => Value::Bool(l > r),
+            OpCode::LessEqual => Value::Bool(l <= r),
+            OpCode::GreaterEqual => Value::Bool(l >= r),
+            _ => return Err(invalid_operands(op, &lhs, &rhs)),
+        },
+        (op, _, _) => return Err(invalid_operands(op, &lhs, &rhs)),
+    };
+
+    Ok(value)
+}
+
+fn invalid_operands(op: OpCode, lhs: &Value, rhs: &Value) -> String {
+    format!("Cannot apply `{}` to `{}` and `{}`", symbol(op), lhs, rhs)
+}
+
+/// Runs the `main` function of a compiled program
+pub fn run(program: &Program) -> WithError<Value> {
+    let mut vm = VM::new(program);
+
+    let main = match program.main {
+        Some(main) => main,
+        None => {
+            vm.reporter.error(
+                "Couldn't find an exported `main` function",
+                "",
+                (0usize, 0usize),
+            );
+            return Err(vm.reporter.finish());
+        }
+    };
+
+    // Stands in for the callee that `call` expects to find below the arguments
+    vm.push(Value::Nil);
+
+    let result = match vm.call(main, 0) {
+        Ok(frame) => vm.execute(frame),
+        Err(msg) => {
+            vm.reporter.error(msg, "", (0usize, 0usize));
+            Err(())
+        }
+    };
+
+    result.map_err(|_| vm.reporter.finish())
+}
@@ -0,0 +1,42 @@
+use crate::Chunk;
+use errors::FileId;
+use std::{
+    fmt::{self, Display},
+    sync::Arc,
+};
+
+/// A compiled function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    pub name: String,
+    pub arity: usize,
+    /// The number of local slots the function needs
+    pub locals: usize,
+    pub chunk: Chunk,
+}
+
+/// A class and its compiled methods
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Class {
+    pub name: String,
+    /// The class it extends, whose methods are inherited
+    pub superclass: Option<String>,
+    pub methods: Vec<Arc<Function>>,
+}
+
+/// All the compiled code of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    pub file: FileId,
+    pub functions: Vec<Arc<Function>>,
+    pub classes: Vec<Class>,
+    /// The index of the exported `main` function
+    pub main: Option<usize>,
+}
+
+impl Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "fn {}", self.name)?;
+        write!(f, "{}", self.chunk)
+    }
+}
@@ -0,0 +1,129 @@
+use std::{
+    fmt::{self, Display},
+    rc::Rc,
+};
+
+/// A runtime value.
+/// Compound values are reference counted and copied on write so they behave like plain values
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Char(char),
+    Str(Rc<str>),
+    Nil,
+    Tuple(Rc<Vec<Value>>),
+    Array(Rc<Vec<Value>>),
+    /// The ints from `start` up to but not including `end`
+    Range {
+        start: i64,
+        end: i64,
+    },
+    Function {
+        name: Rc<str>,
+        index: usize,
+    },
+    Enum {
+        def: Rc<str>,
+        variant: Rc<str>,
+        value: Option<Rc<Value>>,
+    },
+    Instance(Rc<Instance>),
+}
+
+/// An instance of a class
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    pub def: Rc<str>,
+    pub fields: Vec<(Rc<str>, Value)>,
+}
+
+impl Value {
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Bool(b) => *b,
+            Value::Nil => false,
+            _ => true,
+        }
+    }
+}
+
+impl Instance {
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|(field, _)| &**field == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn field_mut(&mut self, name: &str) -> Option<&mut Value> {
+        self.fields
+            .iter_mut()
+            .find(|(field, _)| &**field == name)
+            .map(|(_, value)| value)
+    }
+}
+
+fn write_values(f: &mut fmt::Formatter, values: &[Value]) -> fmt::Result {
+    for (i, value) in values.iter().enumerate() {
+        if i + 1 == values.len() {
+            write!(f, "{}", value)?;
+        } else {
+            write!(f, "{},", value)?;
+        }
+    }
+
+    Ok(())
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(float) => write!(f, "{}", float),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Char(c) => write!(f, "{}", c),
+            Value::Str(s) => write!(f, "{}", s),
+            Value::Nil => write!(f, "nil"),
+            Value::Tuple(values) => {
+                write!(f, "(")?;
+                write_values(f, values)?;
+                write!(f, ")")
+            }
+            Value::Array(values) => {
+                write!(f, "[")?;
+                write_values(f, values)?;
+                write!(f, "]")
+            }
+            Value::Range { start, end } => write!(f, "{}..{}", start, end),
+            Value::Function { name, .. } => write!(f, "<fn {}>", name),
+            Value::Enum {
+                def,
+                variant,
+                value,
+            } => {
+                write!(f, "{}::{}", def, variant)?;
+
+                if let Some(value) = value {
+                    write!(f, "({})", value)?;
+                }
+
+                Ok(())
+            }
+            Value::Instance(instance) => {
+                write!(f, "{} {{", instance.def)?;
+
+                for (i, (name, value)) in instance.fields.iter().enumerate() {
+                    write!(f, "{}:{}", name, value)?;
+
+                    if i + 1 != instance.fields.len() {
+                        write!(f, ",")?;
+                    }
+                }
+
+                write!(f, "}}")
+            }
+        }
+    }
+}